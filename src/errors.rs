@@ -19,6 +19,14 @@ pub enum VmfError {
     #[error("Invalid VMF format: {0}")]
     InvalidFormat(String),
 
+    /// A `func_instance` references a file that could not be found.
+    #[error("Instance file not found: {0}")]
+    InstanceNotFound(String),
+
+    /// A `func_instance` (directly or through nested instances) includes itself.
+    #[error("Recursive instance detected: {0}")]
+    InstanceCycle(String),
+
     /// An error occurred while parsing an integer value for a specific key.
     #[error("Integer parse error for key '{key}': {source}")]
     ParseInt {
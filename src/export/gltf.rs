@@ -0,0 +1,256 @@
+//! glTF 2.0 output, as JSON with an embedded buffer or as a binary `.glb` file.
+
+use std::fmt::Write;
+
+use crate::geometry::Mesh;
+use crate::utils::json_escape;
+
+/// glTF component type for `f32`.
+const FLOAT: u32 = 5126;
+/// glTF component type for `u32`.
+const UNSIGNED_INT: u32 = 5125;
+/// glTF buffer view target for vertex attributes.
+const ARRAY_BUFFER: u32 = 34962;
+/// glTF buffer view target for indices.
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// The container used for a glTF file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GltfFormat {
+    /// A `.gltf` JSON document with the buffer embedded as a base64 data URI.
+    Json,
+    /// A binary `.glb` file.
+    #[default]
+    Binary,
+}
+
+impl Mesh {
+    /// Converts the mesh to a glTF 2.0 file.
+    ///
+    /// The mesh becomes a single node with one primitive per material. Positions,
+    /// normals and UVs are stored as `f32`, indices as `u32`.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - Whether a JSON document or a binary `.glb` file is produced.
+    ///
+    /// # Returns
+    ///
+    /// The contents of the file.
+    pub fn to_gltf(&self, format: GltfFormat) -> Vec<u8> {
+        let (mut buffer, layout) = self.gltf_buffer();
+        if self.is_empty() {
+            // An empty scene has no buffer.
+            buffer.clear();
+        }
+        match format {
+            GltfFormat::Json => {
+                let uri = format!(
+                    "data:application/octet-stream;base64,{}",
+                    base64_encode(&buffer)
+                );
+                self.gltf_json(&layout, buffer.len(), Some(&uri))
+                    .into_bytes()
+            }
+            GltfFormat::Binary => {
+                let json = self.gltf_json(&layout, buffer.len(), None);
+                glb(json.into_bytes(), buffer)
+            }
+        }
+    }
+
+    /// Packs the vertex attributes and the indices of every group into one buffer.
+    fn gltf_buffer(&self) -> (Vec<u8>, BufferLayout) {
+        let mut buffer = Vec::new();
+        for vertex in &self.vertices {
+            for c in vertex.position.to_array() {
+                buffer.extend_from_slice(&(c as f32).to_le_bytes());
+            }
+        }
+        let normals = buffer.len();
+        for vertex in &self.vertices {
+            for c in vertex.normal.to_array() {
+                buffer.extend_from_slice(&(c as f32).to_le_bytes());
+            }
+        }
+        let uvs = buffer.len();
+        for vertex in &self.vertices {
+            for c in vertex.uv {
+                buffer.extend_from_slice(&(c as f32).to_le_bytes());
+            }
+        }
+        let indices = buffer.len();
+        let mut group_offsets = Vec::with_capacity(self.groups.len());
+        for group in &self.groups {
+            group_offsets.push(buffer.len() - indices);
+            for &index in &group.indices {
+                buffer.extend_from_slice(&index.to_le_bytes());
+            }
+        }
+
+        let layout = BufferLayout {
+            normals,
+            uvs,
+            indices,
+            group_offsets,
+        };
+        (buffer, layout)
+    }
+
+    /// Writes the glTF JSON document describing the buffer.
+    fn gltf_json(&self, layout: &BufferLayout, buffer_len: usize, uri: Option<&str>) -> String {
+        let vertex_count = self.vertices.len();
+        let groups: Vec<(usize, &_)> = self
+            .groups
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| !g.indices.is_empty())
+            .collect();
+
+        let mut json = String::new();
+        json.push_str(r#"{"asset":{"version":"2.0","generator":"vmf-forge"},"scene":0,"#);
+        if vertex_count == 0 || groups.is_empty() {
+            json.push_str(r#""scenes":[{"nodes":[]}]}"#);
+            return json;
+        }
+        json.push_str(r#""scenes":[{"nodes":[0]}],"nodes":[{"mesh":0}],"#);
+
+        // Meshes: one primitive per material. Accessors 0-2 are the vertex attributes.
+        json.push_str(r#""meshes":[{"primitives":["#);
+        for (i, _) in groups.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                r#"{{"attributes":{{"POSITION":0,"NORMAL":1,"TEXCOORD_0":2}},"indices":{},"material":{}}}"#,
+                i + 3,
+                i
+            );
+        }
+        json.push_str("]}],");
+
+        json.push_str(r#""materials":["#);
+        for (i, (_, group)) in groups.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                r#"{{"name":"{}","pbrMetallicRoughness":{{"metallicFactor":0}}}}"#,
+                json_escape(&group.material)
+            );
+        }
+        json.push_str("],");
+
+        let _ = write!(json, r#""buffers":[{{"byteLength":{}"#, buffer_len);
+        if let Some(uri) = uri {
+            let _ = write!(json, r#","uri":"{}""#, uri);
+        }
+        json.push_str("}],");
+
+        let _ = write!(
+            json,
+            r#""bufferViews":[{{"buffer":0,"byteOffset":0,"byteLength":{},"target":{ARRAY_BUFFER}}},{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{ARRAY_BUFFER}}},{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{ARRAY_BUFFER}}},{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{ELEMENT_ARRAY_BUFFER}}}],"#,
+            layout.normals,
+            layout.normals,
+            layout.uvs - layout.normals,
+            layout.uvs,
+            layout.indices - layout.uvs,
+            layout.indices,
+            buffer_len - layout.indices,
+        );
+
+        // POSITION requires its bounds.
+        let (min, max) = self.vertices.iter().fold(
+            ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]),
+            |(mut min, mut max), v| {
+                for (axis, c) in v.position.to_array().into_iter().enumerate() {
+                    min[axis] = min[axis].min(c as f32);
+                    max[axis] = max[axis].max(c as f32);
+                }
+                (min, max)
+            },
+        );
+        let _ = write!(
+            json,
+            r#""accessors":[{{"bufferView":0,"componentType":{FLOAT},"count":{vertex_count},"type":"VEC3","min":[{},{},{}],"max":[{},{},{}]}},{{"bufferView":1,"componentType":{FLOAT},"count":{vertex_count},"type":"VEC3"}},{{"bufferView":2,"componentType":{FLOAT},"count":{vertex_count},"type":"VEC2"}}"#,
+            min[0], min[1], min[2], max[0], max[1], max[2],
+        );
+        for (index, group) in &groups {
+            let _ = write!(
+                json,
+                r#",{{"bufferView":3,"byteOffset":{},"componentType":{UNSIGNED_INT},"count":{},"type":"SCALAR"}}"#,
+                layout.group_offsets[*index],
+                group.indices.len()
+            );
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+/// Byte offsets of the sections in the glTF buffer. Positions start at 0.
+struct BufferLayout {
+    normals: usize,
+    uvs: usize,
+    indices: usize,
+    /// The offset of each group's indices, relative to `indices`.
+    group_offsets: Vec<usize>,
+}
+
+/// Wraps a JSON document and a buffer into a GLB container.
+fn glb(mut json: Vec<u8>, mut buffer: Vec<u8>) -> Vec<u8> {
+    // Chunks are 4-byte aligned: JSON is padded with spaces, binary data with zeros.
+    while !json.len().is_multiple_of(4) {
+        json.push(b' ');
+    }
+    while !buffer.len().is_multiple_of(4) {
+        buffer.push(0);
+    }
+
+    let bin_chunk = if buffer.is_empty() {
+        0
+    } else {
+        8 + buffer.len()
+    };
+    let total = 12 + 8 + json.len() + bin_chunk;
+    let mut output = Vec::with_capacity(total);
+    output.extend_from_slice(b"glTF");
+    output.extend_from_slice(&2u32.to_le_bytes());
+    output.extend_from_slice(&(total as u32).to_le_bytes());
+
+    output.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    output.extend_from_slice(b"JSON");
+    output.extend_from_slice(&json);
+
+    if !buffer.is_empty() {
+        output.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+        output.extend_from_slice(b"BIN\0");
+        output.extend_from_slice(&buffer);
+    }
+    output
+}
+
+/// Encodes bytes as standard base64 with padding.
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
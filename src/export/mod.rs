@@ -0,0 +1,139 @@
+//! Export of brush geometry to mesh formats for previews and other engines.
+//!
+//! Brushes are triangulated into a [`Mesh`] with one group per material and UVs
+//! computed from the texture axes, which can then be written as Wavefront OBJ
+//! (with an MTL material library) or glTF 2.0 (JSON with an embedded buffer, or binary).
+
+use std::collections::HashMap;
+
+use crate::errors::VmfResult;
+use crate::geometry::{Mesh, MeshVertex};
+use crate::prelude::Solid;
+
+mod gltf;
+mod obj;
+
+pub use gltf::GltfFormat;
+
+/// The size of one Hammer unit in meters (one inch).
+pub const HAMMER_UNITS_TO_METERS: f64 = 0.0254;
+
+/// Tool materials that are invisible in game and skipped by default.
+pub const INVISIBLE_TOOL_MATERIALS: [&str; 2] = ["TOOLS/TOOLSNODRAW", "TOOLS/TOOLSSKYBOX"];
+
+/// The material size used for UVs when a material is missing from `ExportOptions::material_sizes`.
+pub const DEFAULT_MATERIAL_SIZE: [u32; 2] = [512, 512];
+
+/// Options for exporting brushes as meshes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportOptions {
+    /// Multiplies every position, for example `HAMMER_UNITS_TO_METERS`. Defaults to `1.0`.
+    pub unit_scale: f64,
+    /// Whether Source's Z-up coordinates are converted to Y-up. Defaults to `true`,
+    /// which is what glTF requires and most OBJ consumers expect.
+    pub y_up: bool,
+    /// Materials whose faces are skipped, compared case-insensitively.
+    /// Defaults to `INVISIBLE_TOOL_MATERIALS`.
+    pub skip_materials: Vec<String>,
+    /// Whether hidden solids and entities, and objects in hidden visgroups, are exported.
+    pub include_hidden: bool,
+    /// Whether the brushes of brush entities are exported alongside the world.
+    pub include_entity_brushes: bool,
+    /// Objects in these visgroups (or any of their children) are skipped.
+    pub exclude_visgroups: Vec<i32>,
+    /// The `[width, height]` of materials in texels, keyed by material name
+    /// (case-insensitive). Used to turn texture coordinates into UVs.
+    pub material_sizes: HashMap<String, [u32; 2]>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            unit_scale: 1.0,
+            y_up: true,
+            skip_materials: INVISIBLE_TOOL_MATERIALS
+                .iter()
+                .map(|m| m.to_string())
+                .collect(),
+            include_hidden: false,
+            include_entity_brushes: true,
+            exclude_visgroups: Vec::new(),
+            material_sizes: HashMap::new(),
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Returns `true` if faces with this material are skipped.
+    pub fn skips_material(&self, material: &str) -> bool {
+        self.skip_materials
+            .iter()
+            .any(|m| m.eq_ignore_ascii_case(material))
+    }
+
+    /// Returns the size of a material in texels, falling back to `DEFAULT_MATERIAL_SIZE`.
+    pub fn material_size(&self, material: &str) -> [u32; 2] {
+        self.material_sizes
+            .get(material)
+            .or_else(|| {
+                self.material_sizes
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(material))
+                    .map(|(_, size)| size)
+            })
+            .copied()
+            .unwrap_or(DEFAULT_MATERIAL_SIZE)
+    }
+}
+
+impl Solid {
+    /// Triangulates the faces of the solid into a mesh.
+    ///
+    /// If the solid has displacements, only the displaced surfaces are included, as in
+    /// game. Positions stay in Hammer units and Z-up; `unit_scale` and `y_up` of the
+    /// options are applied by `VmfFile::to_mesh`. Faces with skipped materials are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The skipped materials and material sizes.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the mesh, or a `VmfError` if a plane or texture axis is malformed.
+    pub fn to_mesh(&self, options: &ExportOptions) -> VmfResult<Mesh> {
+        let has_displacement = self.sides.iter().any(|side| side.dispinfo.is_some());
+
+        let mut mesh = Mesh::new();
+        for face in self.faces()? {
+            let material = face.side.material.as_str();
+            if options.skips_material(material) {
+                continue;
+            }
+            if has_displacement {
+                if let Some(disp) = &face.side.dispinfo {
+                    let surface = disp.build_mesh(face.side, self)?;
+                    mesh.append(&surface.to_mesh(face.side, options.material_size(material))?);
+                }
+                continue;
+            }
+
+            let [width, height] = options.material_size(material);
+            let normal = face.plane.normal.normalize();
+            let vertices = face
+                .polygon
+                .vertices
+                .iter()
+                .map(|&position| {
+                    let [s, t] = face.side.texture_coords(position)?;
+                    Ok(MeshVertex {
+                        position,
+                        normal,
+                        uv: [s / f64::from(width.max(1)), t / f64::from(height.max(1))],
+                    })
+                })
+                .collect::<VmfResult<Vec<_>>>()?;
+            mesh.add_polygon(material, &vertices);
+        }
+        Ok(mesh)
+    }
+}
@@ -0,0 +1,85 @@
+//! Wavefront OBJ and MTL output.
+
+use std::fmt::Write;
+
+use crate::geometry::Mesh;
+use crate::utils::format_float;
+
+impl Mesh {
+    /// Converts the mesh to a Wavefront OBJ document.
+    ///
+    /// Every material group becomes a `usemtl` section. OBJ puts the texture origin at the
+    /// bottom left, so V is flipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `mtl_file` - The name of the material library to reference with `mtllib`, if any.
+    ///
+    /// # Returns
+    ///
+    /// The OBJ document as a string.
+    pub fn to_obj(&self, mtl_file: Option<&str>) -> String {
+        let mut output = String::new();
+        output.push_str("# Exported by vmf-forge\n");
+        if let Some(mtl_file) = mtl_file {
+            let _ = writeln!(output, "mtllib {}", mtl_file);
+        }
+
+        for vertex in &self.vertices {
+            let _ = writeln!(output, "v {}", vertex.position);
+        }
+        for vertex in &self.vertices {
+            let _ = writeln!(
+                output,
+                "vt {} {}",
+                format_float(vertex.uv[0]),
+                format_float(1.0 - vertex.uv[1])
+            );
+        }
+        for vertex in &self.vertices {
+            let _ = writeln!(output, "vn {}", vertex.normal);
+        }
+
+        for group in self.groups.iter().filter(|g| !g.indices.is_empty()) {
+            let _ = writeln!(output, "usemtl {}", obj_name(&group.material));
+            for triangle in group.indices.chunks_exact(3) {
+                output.push('f');
+                for index in triangle {
+                    // OBJ indices are 1-based.
+                    let i = index + 1;
+                    let _ = write!(output, " {}/{}/{}", i, i, i);
+                }
+                output.push('\n');
+            }
+        }
+        output
+    }
+
+    /// Converts the materials of the mesh to an MTL material library.
+    ///
+    /// Each material gets a plain white diffuse color and a `map_Kd` pointing at
+    /// `<material>.png`, so converted textures can be dropped next to the model.
+    ///
+    /// # Returns
+    ///
+    /// The MTL document as a string.
+    pub fn to_mtl(&self) -> String {
+        let mut output = String::new();
+        output.push_str("# Exported by vmf-forge\n");
+        for group in self.groups.iter().filter(|g| !g.indices.is_empty()) {
+            let name = obj_name(&group.material);
+            let _ = writeln!(output, "\nnewmtl {}", name);
+            output.push_str("Kd 1 1 1\n");
+            let _ = writeln!(output, "map_Kd {}.png", name.to_lowercase());
+        }
+        output
+    }
+}
+
+/// Returns a material name that is safe to use in OBJ and MTL statements.
+fn obj_name(material: &str) -> String {
+    material
+        .chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect()
+}
@@ -0,0 +1,229 @@
+//! Axis-aligned bounding boxes and the extents of solids, entities and the world.
+
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+
+use super::Vector3;
+use crate::errors::VmfResult;
+use crate::prelude::{Entity, Solid, World};
+
+/// An axis-aligned bounding box.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Aabb {
+    /// The minimum corner.
+    pub min: Vector3,
+    /// The maximum corner.
+    pub max: Vector3,
+}
+
+impl Aabb {
+    /// Creates a box from two corners, in any order.
+    pub fn new(a: Vector3, b: Vector3) -> Self {
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    /// Creates a box containing a single point.
+    pub fn from_point(point: Vector3) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// Creates the smallest box containing all `points`.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the box, or `None` if there are no points.
+    pub fn from_points(points: impl IntoIterator<Item = Vector3>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = Self::from_point(points.next()?);
+        Some(points.fold(first, |aabb, p| aabb.including(p)))
+    }
+
+    /// Returns the box grown to include `point`.
+    pub fn including(&self, point: Vector3) -> Aabb {
+        Aabb {
+            min: self.min.min(point),
+            max: self.max.max(point),
+        }
+    }
+
+    /// Returns the smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Returns the overlap of both boxes, or `None` if they don't intersect.
+    pub fn intersection(&self, other: &Aabb) -> Option<Aabb> {
+        let min = self.min.max(other.min);
+        let max = self.max.min(other.max);
+        (min.x <= max.x && min.y <= max.y && min.z <= max.z).then_some(Aabb { min, max })
+    }
+
+    /// Returns the box grown by `amount` on every side (shrunk if negative).
+    pub fn expanded(&self, amount: f64) -> Aabb {
+        Aabb {
+            min: self.min - Vector3::splat(amount),
+            max: self.max + Vector3::splat(amount),
+        }
+    }
+
+    /// Returns the center of the box.
+    pub fn center(&self) -> Vector3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Returns the size of the box along each axis.
+    pub fn size(&self) -> Vector3 {
+        self.max - self.min
+    }
+
+    /// Returns the volume of the box.
+    pub fn volume(&self) -> f64 {
+        let size = self.size();
+        size.x * size.y * size.z
+    }
+
+    /// Returns the eight corners of the box.
+    pub fn corners(&self) -> [Vector3; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            Vector3::new(a.x, a.y, a.z),
+            Vector3::new(b.x, a.y, a.z),
+            Vector3::new(a.x, b.y, a.z),
+            Vector3::new(b.x, b.y, a.z),
+            Vector3::new(a.x, a.y, b.z),
+            Vector3::new(b.x, a.y, b.z),
+            Vector3::new(a.x, b.y, b.z),
+            Vector3::new(b.x, b.y, b.z),
+        ]
+    }
+
+    /// Returns `true` if `point` is inside the box or on its boundary.
+    pub fn contains_point(&self, point: Vector3) -> bool {
+        point.x >= self.min.x
+            && point.y >= self.min.y
+            && point.z >= self.min.z
+            && point.x <= self.max.x
+            && point.y <= self.max.y
+            && point.z <= self.max.z
+    }
+
+    /// Returns `true` if `other` lies entirely inside this box.
+    pub fn contains(&self, other: &Aabb) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    /// Returns `true` if the boxes overlap or touch.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.intersection(other).is_some()
+    }
+}
+
+/// Controls which objects are taken into account when computing bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundsOptions {
+    /// Include hidden world solids and hidden entities. Defaults to `false`.
+    pub include_hidden: bool,
+    /// Include brushes owned by entities. Defaults to `true`.
+    pub include_entity_brushes: bool,
+    /// Include the origins of point entities. Defaults to `true`.
+    pub include_point_entities: bool,
+}
+
+impl Default for BoundsOptions {
+    fn default() -> Self {
+        Self {
+            include_hidden: false,
+            include_entity_brushes: true,
+            include_point_entities: true,
+        }
+    }
+}
+
+/// Unions an optional accumulator with an optional box.
+pub(crate) fn union_bounds(acc: Option<Aabb>, next: Option<Aabb>) -> Option<Aabb> {
+    match (acc, next) {
+        (Some(a), Some(b)) => Some(a.union(&b)),
+        (a, b) => a.or(b),
+    }
+}
+
+impl Solid {
+    /// Computes the bounding box of the brush from its vertices.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the box, or `None` if the solid has no faces,
+    /// or a `VmfError` if a plane is malformed.
+    pub fn bounds(&self) -> VmfResult<Option<Aabb>> {
+        Ok(Aabb::from_points(self.vertices()?))
+    }
+}
+
+impl Entity {
+    /// Computes the bounding box of the entity.
+    ///
+    /// Brush entities use the union of their solids; point entities use their
+    /// `origin`. Hidden entities are treated like any other here; filtering them
+    /// is left to `World::bounds` and `VmfFile::bounds`.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Which kinds of entities to include.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the box, or `None` if the entity has no extent or is
+    /// excluded by `options`, or a `VmfError` if a plane is malformed.
+    pub fn bounds(&self, options: &BoundsOptions) -> VmfResult<Option<Aabb>> {
+        match &self.solids {
+            Some(solids) if !solids.is_empty() => {
+                if !options.include_entity_brushes {
+                    return Ok(None);
+                }
+                let mut bounds = None;
+                for solid in solids {
+                    bounds = union_bounds(bounds, solid.bounds()?);
+                }
+                Ok(bounds)
+            }
+            _ if options.include_point_entities => Ok(self.origin().map(Aabb::from_point)),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl World {
+    /// Computes the bounding box of the world brushes.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Whether hidden world solids are included.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the box, or `None` if there are no brushes,
+    /// or a `VmfError` if a plane is malformed.
+    pub fn bounds(&self, options: &BoundsOptions) -> VmfResult<Option<Aabb>> {
+        let hidden: &[Solid] = if options.include_hidden {
+            &self.hidden
+        } else {
+            &[]
+        };
+
+        let mut bounds = None;
+        for solid in self.solids.iter().chain(hidden) {
+            bounds = union_bounds(bounds, solid.bounds()?);
+        }
+        Ok(bounds)
+    }
+}
@@ -0,0 +1,144 @@
+//! Face polygons, vertices and edges of brushes, computed from their side planes.
+
+use super::{ON_EPSILON, Plane, Polygon, Vector3};
+use crate::errors::VmfResult;
+use crate::prelude::{Side, Solid};
+
+/// The distance below which two brush vertices are considered the same point.
+const VERTEX_EPSILON: f64 = 0.01;
+
+/// A side of a solid together with the polygon it contributes to the brush.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Face<'a> {
+    /// The side this face belongs to.
+    pub side: &'a Side,
+    /// The index of the side in `Solid::sides`.
+    pub side_index: usize,
+    /// The plane of the side, facing out of the solid.
+    pub plane: Plane,
+    /// The face polygon, wound counter-clockwise when viewed from outside.
+    pub polygon: Polygon,
+}
+
+impl Solid {
+    /// Parses the plane of every side.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing one plane per side, or a `VmfError` if a plane is malformed.
+    pub fn planes(&self) -> VmfResult<Vec<Plane>> {
+        self.sides.iter().map(Side::to_plane).collect()
+    }
+
+    /// Computes the polygon of every side by clipping a huge winding on its
+    /// plane against the planes of all other sides, the way VBSP builds brush windings.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing one entry per side, `None` where a side is clipped
+    /// away entirely, or a `VmfError` if a plane is malformed.
+    pub fn side_polygons(&self) -> VmfResult<Vec<Option<Polygon>>> {
+        let planes = self.planes()?;
+        Ok(polygons_from_planes(&planes))
+    }
+
+    /// Computes the faces of the brush.
+    ///
+    /// Sides that don't contribute any area to the brush (for example redundant
+    /// planes) are left out.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the faces in side order, or a `VmfError` if a plane is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// for face in vmf.world.solids[0].faces()? {
+    ///     println!("side {}: {} vertices", face.side.id, face.polygon.len());
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn faces(&self) -> VmfResult<Vec<Face<'_>>> {
+        let planes = self.planes()?;
+        let polygons = polygons_from_planes(&planes);
+
+        Ok(self
+            .sides
+            .iter()
+            .zip(planes)
+            .zip(polygons)
+            .enumerate()
+            .filter_map(|(side_index, ((side, plane), polygon))| {
+                Some(Face {
+                    side,
+                    side_index,
+                    plane,
+                    polygon: polygon?,
+                })
+            })
+            .collect())
+    }
+
+    /// Computes the unique vertices of the brush.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the vertices in the order they are first met
+    /// while walking the faces, or a `VmfError` if a plane is malformed.
+    pub fn vertices(&self) -> VmfResult<Vec<Vector3>> {
+        let mut vertices: Vec<Vector3> = Vec::new();
+        for face in self.faces()? {
+            for vertex in face.polygon.vertices {
+                if !vertices.iter().any(|v| v.approx_eq(vertex, VERTEX_EPSILON)) {
+                    vertices.push(vertex);
+                }
+            }
+        }
+        Ok(vertices)
+    }
+
+    /// Computes the unique edges of the brush.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing each edge once as a `(start, end)` pair, or a
+    /// `VmfError` if a plane is malformed.
+    pub fn edges(&self) -> VmfResult<Vec<(Vector3, Vector3)>> {
+        let mut edges: Vec<(Vector3, Vector3)> = Vec::new();
+        for face in self.faces()? {
+            for (a, b) in face.polygon.edges() {
+                let known = edges.iter().any(|&(c, d)| {
+                    (c.approx_eq(a, VERTEX_EPSILON) && d.approx_eq(b, VERTEX_EPSILON))
+                        || (c.approx_eq(b, VERTEX_EPSILON) && d.approx_eq(a, VERTEX_EPSILON))
+                });
+                if !known {
+                    edges.push((a, b));
+                }
+            }
+        }
+        Ok(edges)
+    }
+}
+
+/// Builds the polygon of each plane of a convex brush, `None` where nothing is left.
+pub(crate) fn polygons_from_planes(planes: &[Plane]) -> Vec<Option<Polygon>> {
+    planes
+        .iter()
+        .enumerate()
+        .map(|(i, plane)| {
+            let mut polygon = Polygon::from_plane(plane);
+            for (j, other) in planes.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                polygon = polygon.clip_to_back(other, ON_EPSILON)?;
+            }
+            // A winding that is clipped to a sliver has no real area.
+            (polygon.area() > ON_EPSILON).then_some(polygon)
+        })
+        .collect()
+}
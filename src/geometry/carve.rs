@@ -0,0 +1,55 @@
+//! Subtracting one convex brush from another, like Hammer's Carve.
+
+use super::ClipMode;
+use crate::errors::VmfResult;
+use crate::prelude::{IdAllocator, Solid};
+
+impl Solid {
+    /// Subtracts a convex `cutter` from this brush.
+    ///
+    /// The brush is split by each cutter plane in turn; the part in front of a plane is
+    /// outside the cutter and kept, the rest goes on to the next plane and is finally
+    /// discarded. Surviving faces keep their material and alignment. Faces created on a
+    /// cutter plane take the material and alignment of that cutter side. The first
+    /// piece keeps the brush's ID and side IDs, and every other piece gets fresh IDs from `ids`.
+    ///
+    /// # Arguments
+    ///
+    /// * `cutter` - The convex brush to subtract.
+    /// * `ids` - Where new solid and side IDs come from, usually `VmfFile::id_allocator`.
+    ///   Nothing is taken from it if the brushes don't overlap.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing `None` if the brushes don't overlap, otherwise the convex
+    /// pieces left over (empty if the brush lies inside the cutter), or a `VmfError` if
+    /// a plane is malformed or the brush has a displacement.
+    pub fn subtract(&self, cutter: &Solid, ids: &mut IdAllocator) -> VmfResult<Option<Vec<Solid>>> {
+        let mut local_ids = *ids;
+        let mut pieces = Vec::new();
+        let mut remaining = self.clone();
+
+        for (side, plane) in cutter.sides.iter().zip(cutter.planes()?) {
+            let clipped =
+                remaining.clip(&plane, ClipMode::KeepBoth, &side.material, &mut local_ids)?;
+            match (clipped.front, clipped.back) {
+                (Some(mut front), Some(mut back)) => {
+                    // Both pieces end with the new side on the cutter plane.
+                    for piece in [&mut front, &mut back] {
+                        if let Some(cap) = piece.sides.last_mut() {
+                            cap.copy_alignment_from(side);
+                        }
+                    }
+                    pieces.push(front);
+                    remaining = back;
+                }
+                (None, Some(back)) => remaining = back,
+                // Entirely in front of a cutter plane: the brushes don't overlap.
+                _ => return Ok(None),
+            }
+        }
+
+        *ids = local_ids;
+        Ok(Some(pieces))
+    }
+}
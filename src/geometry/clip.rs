@@ -0,0 +1,244 @@
+//! Splitting brushes by a plane, like Hammer's clipping tool.
+
+use std::collections::HashMap;
+
+use super::{ON_EPSILON, Plane, PlaneSide, Polygon, Vector3, polygons_from_planes};
+use crate::errors::{VmfError, VmfResult};
+use crate::prelude::{IdAllocator, Side, Solid};
+
+/// The default texture scale of new faces, in world units per texel.
+const DEFAULT_TEXTURE_SCALE: f64 = 0.25;
+
+/// The default lightmap scale of new faces.
+const DEFAULT_LIGHTMAP_SCALE: u16 = 16;
+
+/// Which pieces of a clipped brush are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClipMode {
+    /// Keep the pieces on both sides of the plane.
+    #[default]
+    KeepBoth,
+    /// Keep only the piece in front of the plane, where its normal points.
+    KeepFront,
+    /// Keep only the piece behind the plane.
+    KeepBack,
+}
+
+/// The pieces of a clipped brush.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ClipResult {
+    /// The piece in front of the plane, if it exists and was kept.
+    pub front: Option<Solid>,
+    /// The piece behind the plane, if it exists and was kept.
+    pub back: Option<Solid>,
+}
+
+impl Solid {
+    /// Splits the brush by a plane.
+    ///
+    /// Each piece keeps the original sides that still bound it and gets a new side on
+    /// the clipping plane, with `material` and world-aligned texture axes. The first
+    /// piece that is kept keeps the solid's ID and side IDs; everything else gets new
+    /// IDs from `ids`. If the plane doesn't cut the brush, the brush is returned
+    /// unchanged on the side it lies on.
+    ///
+    /// # Arguments
+    ///
+    /// * `plane` - The clipping plane. Its normal points towards the front piece.
+    /// * `mode` - Which pieces are kept.
+    /// * `material` - The material of the new faces on the clipping plane.
+    /// * `ids` - Where new solid and side IDs come from, usually `VmfFile::id_allocator`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the pieces, or a `VmfError` if a plane is malformed
+    /// or the brush has a displacement.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("your_map.vmf")?;
+    /// let mut ids = vmf.id_allocator();
+    /// let plane = Plane::new(Vector3::Z, 64.0);
+    /// let pieces = vmf.world.solids[0].clip(&plane, ClipMode::KeepBoth, "TOOLS/TOOLSNODRAW", &mut ids)?;
+    /// vmf.world.solids.remove(0);
+    /// vmf.world.solids.extend(pieces.front.into_iter().chain(pieces.back));
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn clip(
+        &self,
+        plane: &Plane,
+        mode: ClipMode,
+        material: &str,
+        ids: &mut IdAllocator,
+    ) -> VmfResult<ClipResult> {
+        if let Some(side) = self.sides.iter().find(|s| s.dispinfo.is_some()) {
+            return Err(VmfError::InvalidFormat(format!(
+                "Cannot clip solid {}: side {} is a displacement",
+                self.id, side.id
+            )));
+        }
+
+        let vertices = self.vertices()?;
+        let classes: Vec<PlaneSide> = vertices
+            .iter()
+            .map(|&v| plane.classify(v, ON_EPSILON))
+            .collect();
+        let has_front = classes.contains(&PlaneSide::Front);
+        let has_back = classes.contains(&PlaneSide::Back);
+
+        let keep_front = mode != ClipMode::KeepBack;
+        let keep_back = mode != ClipMode::KeepFront;
+        let mut result = ClipResult::default();
+
+        // The plane misses the brush: it lies entirely on one side.
+        if !has_back || !has_front {
+            if has_front && keep_front {
+                result.front = Some(self.clone());
+            } else if has_back && keep_back {
+                result.back = Some(self.clone());
+            }
+            return Ok(result);
+        }
+
+        let mut keeps_original_ids = true;
+        if keep_front {
+            result.front =
+                Some(self.clip_piece(&plane.flipped(), material, ids, keeps_original_ids)?);
+            keeps_original_ids = false;
+        }
+        if keep_back {
+            result.back = Some(self.clip_piece(plane, material, ids, keeps_original_ids)?);
+        }
+        Ok(result)
+    }
+
+    /// Maps each side of this brush to the sides of `pieces` that came from it.
+    ///
+    /// Pieces made by `clip`, `subtract` or `hollow` copy the plane of the side they
+    /// came from, so a side is matched to every piece side on the same plane. Sides
+    /// with no match, such as those carved away entirely, map to an empty list.
+    ///
+    /// # Arguments
+    ///
+    /// * `pieces` - The brushes that replace this one.
+    ///
+    /// # Returns
+    ///
+    /// The IDs of the sides of `pieces` for each side ID of this brush, ready for
+    /// `VmfFile::remap_overlay_sides`.
+    pub fn derived_sides(&self, pieces: &[Solid]) -> HashMap<u32, Vec<u32>> {
+        self.sides
+            .iter()
+            .map(|side| {
+                let ids = pieces
+                    .iter()
+                    .flat_map(|piece| &piece.sides)
+                    .filter(|piece_side| piece_side.plane == side.plane)
+                    .map(|piece_side| piece_side.id)
+                    .collect();
+                (side.id, ids)
+            })
+            .collect()
+    }
+
+    /// Builds the piece of the brush behind `cap_plane`, closed by a new side on it.
+    fn clip_piece(
+        &self,
+        cap_plane: &Plane,
+        material: &str,
+        ids: &mut IdAllocator,
+        keep_ids: bool,
+    ) -> VmfResult<Solid> {
+        let mut planes = self.planes()?;
+        planes.push(*cap_plane);
+        let polygons = polygons_from_planes(&planes);
+
+        let mut piece = Solid {
+            id: if keep_ids {
+                self.id
+            } else {
+                ids.next_object_id()
+            },
+            sides: Vec::with_capacity(planes.len()),
+            editor: self.editor.clone(),
+        };
+        for (side, polygon) in self.sides.iter().zip(&polygons) {
+            if polygon.is_some() {
+                let mut side = side.clone();
+                if !keep_ids {
+                    side.id = ids.next_side_id();
+                }
+                piece.sides.push(side);
+            }
+        }
+
+        let cap = polygons.last().cloned().flatten().ok_or_else(|| {
+            VmfError::InvalidFormat(format!("Clipping plane doesn't cut solid {}", self.id))
+        })?;
+        piece
+            .sides
+            .push(new_side(ids.next_side_id(), cap_plane, &cap, material)?);
+        Ok(piece)
+    }
+}
+
+/// Creates a side on `plane` with world-aligned texture axes and default scales.
+///
+/// The plane points are picked from `polygon`, the face the side will have, so they
+/// stay close to the brush.
+pub(crate) fn new_side(
+    id: u32,
+    plane: &Plane,
+    polygon: &Polygon,
+    material: &str,
+) -> VmfResult<Side> {
+    let scale = DEFAULT_TEXTURE_SCALE;
+    let mut side = Side {
+        id,
+        material: material.to_string(),
+        u_axis: format!("[1 0 0 0] {}", scale),
+        v_axis: format!("[0 -1 0 0] {}", scale),
+        rotation: Some(0.0),
+        lightmap_scale: DEFAULT_LIGHTMAP_SCALE,
+        ..Default::default()
+    };
+    side.set_plane_points(plane_points(plane, polygon));
+    side.align_to_world()?;
+    Ok(side)
+}
+
+/// Picks three points of `polygon` spanning the largest triangle, wound the way
+/// Hammer expects for a side facing along `plane`'s normal.
+///
+/// Falls back to `Plane::to_points` if the polygon is degenerate.
+pub(crate) fn plane_points(plane: &Plane, polygon: &Polygon) -> [Vector3; 3] {
+    let vertices = &polygon.vertices;
+    let mut best = None;
+    let mut best_area = 0.0;
+    for i in 0..vertices.len() {
+        for j in i + 1..vertices.len() {
+            for k in j + 1..vertices.len() {
+                let area = (vertices[j] - vertices[i])
+                    .cross(vertices[k] - vertices[i])
+                    .length_squared();
+                if area > best_area {
+                    best_area = area;
+                    best = Some([vertices[i], vertices[j], vertices[k]]);
+                }
+            }
+        }
+    }
+
+    let Some([a, b, c]) = best else {
+        return plane.to_points(64.0);
+    };
+    // Hammer points are clockwise from outside, so their normal is (a - b) × (c - b).
+    if (a - b).cross(c - b).dot(plane.normal) > 0.0 {
+        [a, b, c]
+    } else {
+        [c, b, a]
+    }
+}
@@ -0,0 +1,407 @@
+//! Displacement surface generation, following Source's `CCoreDispInfo`.
+
+use indexmap::IndexMap;
+
+use super::{Mesh, MeshVertex, Vector3};
+use crate::errors::{VmfError, VmfResult};
+use crate::prelude::{Side, Solid};
+use crate::vmf::world::{DispInfo, DispRows};
+
+/// Triangles whose normal has at least this much Z are walkable.
+const WALKABLE_NORMAL_Z: f64 = 0.7;
+/// Triangles whose normal has at least this much Z are buildable.
+const BUILDABLE_NORMAL_Z: f64 = 0.8;
+/// The `triangle_tags` bits Hammer uses, from `COREDISPTRI_TAG_*`.
+const TAG_WALKABLE: u32 = 1 << 0;
+const TAG_FORCE_WALKABLE_BIT: u32 = 1 << 1;
+const TAG_FORCE_WALKABLE_VAL: u32 = 1 << 2;
+const TAG_BUILDABLE: u32 = 1 << 3;
+const TAG_FORCE_BUILDABLE_BIT: u32 = 1 << 4;
+const TAG_FORCE_BUILDABLE_VAL: u32 = 1 << 5;
+
+/// The generated surface of a displacement.
+///
+/// Vertices are stored row by row, in the same order as the rows of the `DispInfo`:
+/// vertex `(row, column)` is at index `row * size + column`, where `size` is `2^power + 1`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DispMesh {
+    /// The power of the displacement; the grid has `2^power + 1` vertices per side.
+    pub power: u8,
+    /// The displaced vertex positions.
+    pub vertices: Vec<Vector3>,
+    /// The vertex positions on the undisplaced base face, used for texture coordinates.
+    pub flat_vertices: Vec<Vector3>,
+    /// The blend alpha of each vertex, from 0 to 255.
+    pub alphas: Vec<f64>,
+    /// The triangles, as indices into `vertices`, wound counter-clockwise when viewed
+    /// from the front. Each quad is split along alternating diagonals.
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl DispMesh {
+    /// Returns the number of vertices along each side of the grid.
+    pub fn size(&self) -> usize {
+        (1 << self.power) + 1
+    }
+
+    /// Returns the displaced vertex at `(row, column)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` or `column` is outside the grid.
+    pub fn vertex(&self, row: usize, column: usize) -> Vector3 {
+        self.vertices[row * self.size() + column]
+    }
+
+    /// Computes smooth vertex normals by averaging the normals of the adjacent
+    /// triangles, weighted by their area.
+    pub fn normals(&self) -> Vec<Vector3> {
+        let mut normals = vec![Vector3::ZERO; self.vertices.len()];
+        for triangle in &self.triangles {
+            let [a, b, c] = triangle.map(|i| self.vertices[i as usize]);
+            let normal = (b - a).cross(c - a);
+            for &i in triangle {
+                normals[i as usize] += normal;
+            }
+        }
+        normals
+            .into_iter()
+            .map(|n| {
+                if n.length_squared() > 0.0 {
+                    n.normalize()
+                } else {
+                    n
+                }
+            })
+            .collect()
+    }
+
+    /// Converts the surface to a `Mesh` with the material and texture axes of its side.
+    ///
+    /// Texture coordinates come from the undisplaced base face, as in Source.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The side the displacement belongs to.
+    /// * `material_size` - The `[width, height]` of the material in texels, used to scale the UVs.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the mesh, or a `VmfError` if the texture axes are malformed.
+    pub fn to_mesh(&self, side: &Side, material_size: [u32; 2]) -> VmfResult<Mesh> {
+        let [width, height] = material_size.map(|s| f64::from(s.max(1)));
+        let normals = self.normals();
+
+        let mut mesh = Mesh::new();
+        for ((&position, &flat), normal) in
+            self.vertices.iter().zip(&self.flat_vertices).zip(normals)
+        {
+            let [s, t] = side.texture_coords(flat)?;
+            mesh.vertices.push(MeshVertex {
+                position,
+                normal,
+                uv: [s / width, t / height],
+            });
+        }
+        for &triangle in &self.triangles {
+            mesh.add_triangle(&side.material, triangle);
+        }
+        Ok(mesh)
+    }
+}
+
+impl DispInfo {
+    /// Generates the displaced surface of a side, the way VBSP and Hammer do.
+    ///
+    /// The four corners of the base face are taken from the side's polygon and ordered
+    /// starting from the corner closest to `start_position`. Every grid vertex is then
+    /// interpolated across the face and moved by `elevation` along the face normal,
+    /// by `normals × distances` and by `offsets`.
+    ///
+    /// # Arguments
+    ///
+    /// * `side` - The side this displacement belongs to.
+    /// * `solid` - The solid containing `side`, used to compute the base face.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the surface, or a `VmfError` if the side isn't part of the
+    /// solid, its face doesn't have four corners, or the displacement data is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("displacements.vmf")?;
+    /// for solid in vmf.world.solids.iter() {
+    ///     for side in &solid.sides {
+    ///         if let Some(disp) = &side.dispinfo {
+    ///             let surface = disp.build_mesh(side, solid)?;
+    ///             println!("{} triangles", surface.triangles.len());
+    ///         }
+    ///     }
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn build_mesh(&self, side: &Side, solid: &Solid) -> VmfResult<DispMesh> {
+        let face = solid
+            .faces()?
+            .into_iter()
+            .find(|face| std::ptr::eq(face.side, side) || face.side.id == side.id)
+            .ok_or_else(|| {
+                VmfError::InvalidFormat(format!(
+                    "Side {} has no face in solid {}",
+                    side.id, solid.id
+                ))
+            })?;
+
+        // Source winds faces clockwise when viewed from the front.
+        let mut corners: Vec<Vector3> = face.polygon.reversed().vertices;
+        if corners.len() != 4 {
+            return Err(VmfError::InvalidFormat(format!(
+                "Displacement on side {} needs a face with 4 corners, found {}",
+                side.id,
+                corners.len()
+            )));
+        }
+        let start: Vector3 = self.start_position.parse()?;
+        let start_index = (0..4)
+            .min_by(|&a, &b| {
+                corners[a]
+                    .distance(start)
+                    .total_cmp(&corners[b].distance(start))
+            })
+            .unwrap_or(0);
+        corners.rotate_left(start_index);
+
+        let size = grid_size(self.power);
+        let normals = grid(self.normals.vectors()?, size, "normals", true)?;
+        let distances = grid(self.distances.floats()?, size, "distances", true)?;
+        let offsets = grid(self.offsets.vectors()?, size, "offsets", false)?;
+        let alphas = grid(self.alphas.floats()?, size, "alphas", false)?;
+        let elevation = face.plane.normal.normalize() * f64::from(self.elevation);
+
+        let step = 1.0 / (size - 1) as f64;
+        let mut mesh = DispMesh {
+            power: self.power,
+            vertices: Vec::with_capacity(size * size),
+            flat_vertices: Vec::with_capacity(size * size),
+            alphas: Vec::with_capacity(size * size),
+            triangles: Vec::with_capacity(2 * (size - 1) * (size - 1)),
+        };
+        for row in 0..size {
+            let t = row as f64 * step;
+            let row_start = corners[0].lerp(corners[1], t);
+            let row_end = corners[3].lerp(corners[2], t);
+            for column in 0..size {
+                let flat = row_start.lerp(row_end, column as f64 * step);
+                let offset = offsets.get(row).map_or(Vector3::ZERO, |r| r[column]);
+                let displaced =
+                    flat + elevation + normals[row][column] * distances[row][column] + offset;
+                mesh.flat_vertices.push(flat);
+                mesh.vertices.push(displaced);
+                mesh.alphas.push(alphas.get(row).map_or(0.0, |r| r[column]));
+            }
+        }
+
+        // Rows run along the first edge and columns along the last, so (a, b, c, d)
+        // goes counter-clockwise around each quad when viewed from the front.
+        for row in 0..size - 1 {
+            for column in 0..size - 1 {
+                let a = (row * size + column) as u32;
+                let b = a + 1;
+                let c = b + size as u32;
+                let d = a + size as u32;
+                if (row * size + column).is_multiple_of(2) {
+                    mesh.triangles.push([a, b, c]);
+                    mesh.triangles.push([a, c, d]);
+                } else {
+                    mesh.triangles.push([a, b, d]);
+                    mesh.triangles.push([b, c, d]);
+                }
+            }
+        }
+        Ok(mesh)
+    }
+
+    /// Recomputes `triangle_tags` from the slope of each triangle of the surface,
+    /// keeping the bits forced in Hammer if the tags match the grid.
+    pub(crate) fn update_triangle_tags(&mut self, side: &Side, solid: &Solid) -> VmfResult<()> {
+        let mesh = self.build_mesh(side, solid)?;
+        let mut previous: Vec<u32> = self
+            .triangle_tags
+            .floats()?
+            .into_iter()
+            .flatten()
+            .map(|tag| tag as u32)
+            .collect();
+        if previous.len() != mesh.triangles.len() {
+            previous = vec![0; mesh.triangles.len()];
+        }
+        let tags: Vec<f64> = mesh
+            .triangles
+            .iter()
+            .zip(&previous)
+            .map(|(&[a, b, c], &previous)| {
+                let [a, b, c] = [a, b, c].map(|i| mesh.vertices[i as usize]);
+                f64::from(triangle_tag((b - a).cross(c - a).normalize(), previous))
+            })
+            .collect();
+        let rows: Vec<Vec<f64>> = tags
+            .chunks(2 * (grid_size(self.power) - 1))
+            .map(<[f64]>::to_vec)
+            .collect();
+        self.triangle_tags = DispRows::from_floats(&rows);
+        Ok(())
+    }
+}
+
+impl Side {
+    /// Creates a flat displacement for this side, the way Hammer's "Create" does.
+    ///
+    /// The start position is the corner of the face nearest its minimum, every normal
+    /// and offset normal is the face normal, and distances, offsets and alphas are 0.
+    /// Triangle tags are set from the slope of the face and every vertex is allowed.
+    ///
+    /// # Arguments
+    ///
+    /// * `solid` - The solid containing this side, used to compute the face.
+    /// * `power` - The power of the displacement, from 2 to 4.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the displacement, or a `VmfError` if the power is out
+    /// of range, the side isn't part of the solid or its face isn't a quad.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("your_map.vmf")?;
+    /// let solid = &mut vmf.world.solids[0];
+    /// let disp = solid.sides[0].make_displacement(solid, 3)?;
+    /// solid.sides[0].dispinfo = Some(disp);
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn make_displacement(&self, solid: &Solid, power: u8) -> VmfResult<DispInfo> {
+        check_power(power)?;
+        let face = solid
+            .faces()?
+            .into_iter()
+            .find(|face| std::ptr::eq(face.side, self) || face.side.id == self.id)
+            .ok_or_else(|| {
+                VmfError::InvalidFormat(format!(
+                    "Side {} has no face in solid {}",
+                    self.id, solid.id
+                ))
+            })?;
+        let corners = &face.polygon.vertices;
+        if corners.len() != 4 {
+            return Err(VmfError::InvalidFormat(format!(
+                "Displacement on side {} needs a face with 4 corners, found {}",
+                self.id,
+                corners.len()
+            )));
+        }
+
+        let min = corners.iter().fold(corners[0], |acc, &v| acc.min(v));
+        let start = corners
+            .iter()
+            .copied()
+            .min_by(|a, b| a.distance(min).total_cmp(&b.distance(min)))
+            .unwrap_or(min);
+        let normal = face.plane.normal.normalize();
+        let size = grid_size(power);
+        let tag = f64::from(triangle_tag(normal, 0));
+
+        Ok(DispInfo {
+            power,
+            start_position: format!("[{}]", start),
+            flags: Some(0),
+            elevation: 0.0,
+            subdiv: false,
+            normals: DispRows::from_vectors(&vec![vec![normal; size]; size]),
+            distances: DispRows::from_floats(&vec![vec![0.0; size]; size]),
+            offsets: DispRows::from_vectors(&vec![vec![Vector3::ZERO; size]; size]),
+            offset_normals: DispRows::from_vectors(&vec![vec![normal; size]; size]),
+            alphas: DispRows::from_floats(&vec![vec![0.0; size]; size]),
+            triangle_tags: DispRows::from_floats(&vec![vec![tag; 2 * (size - 1)]; size - 1]),
+            allowed_verts: all_verts_allowed(),
+        })
+    }
+}
+
+/// Returns an error unless `power` is a displacement power Source supports.
+pub(crate) fn check_power(power: u8) -> VmfResult<()> {
+    if (2..=4).contains(&power) {
+        Ok(())
+    } else {
+        Err(VmfError::InvalidFormat(format!(
+            "Displacement power must be 2, 3 or 4, got {}",
+            power
+        )))
+    }
+}
+
+/// Returns the number of vertices along each side of a displacement grid.
+pub(crate) fn grid_size(power: u8) -> usize {
+    (1usize << power) + 1
+}
+
+/// Returns the `triangle_tags` value Hammer gives a triangle facing `normal`.
+///
+/// The walkable and buildable bits follow the slope unless `previous` forces them,
+/// and every other bit of `previous` is kept.
+pub(crate) fn triangle_tag(normal: Vector3, previous: u32) -> u32 {
+    let forced = |bit: u32, value: u32, slope: bool| {
+        if previous & bit != 0 {
+            previous & value != 0
+        } else {
+            slope
+        }
+    };
+    let mut tag = previous & !(TAG_WALKABLE | TAG_BUILDABLE);
+    if forced(
+        TAG_FORCE_WALKABLE_BIT,
+        TAG_FORCE_WALKABLE_VAL,
+        normal.z >= WALKABLE_NORMAL_Z,
+    ) {
+        tag |= TAG_WALKABLE;
+    }
+    if forced(
+        TAG_FORCE_BUILDABLE_BIT,
+        TAG_FORCE_BUILDABLE_VAL,
+        normal.z >= BUILDABLE_NORMAL_Z,
+    ) {
+        tag |= TAG_BUILDABLE;
+    }
+    tag
+}
+
+/// Returns the `allowed_verts` of a displacement with every vertex allowed.
+pub(crate) fn all_verts_allowed() -> IndexMap<String, Vec<i32>> {
+    IndexMap::from([("10".to_string(), vec![-1; 10])])
+}
+
+/// Checks that parsed displacement rows form a `size` × `size` grid.
+///
+/// Optional grids may be missing entirely and are returned empty.
+pub(super) fn grid<T>(
+    rows: Vec<Vec<T>>,
+    size: usize,
+    name: &str,
+    required: bool,
+) -> VmfResult<Vec<Vec<T>>> {
+    if rows.is_empty() && !required {
+        return Ok(rows);
+    }
+    if rows.len() != size || rows.iter().any(|row| row.len() != size) {
+        return Err(VmfError::InvalidFormat(format!(
+            "Displacement {} must be a {}x{} grid",
+            name, size, size
+        )));
+    }
+    Ok(rows)
+}
@@ -0,0 +1,150 @@
+//! Detection and snapping of brushes that are off the editor grid.
+
+use std::fmt;
+
+use super::{SolidProblem, Vector3};
+use crate::errors::{VmfError, VmfResult};
+use crate::prelude::Solid;
+
+/// A coordinate closer than this to a grid line is considered on the grid.
+const GRID_EPSILON: f64 = 0.001;
+
+/// A solid with plane points or vertices off the grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OffGrid {
+    /// The ID of the solid.
+    pub solid_id: u64,
+    /// The ID of the entity owning the solid, or `None` for world brushes.
+    pub entity_id: Option<u64>,
+    /// The IDs of the sides with a plane point off the grid.
+    pub side_ids: Vec<u32>,
+    /// The brush vertices off the grid.
+    pub vertices: Vec<Vector3>,
+}
+
+impl fmt::Display for OffGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "solid {}", self.solid_id)?;
+        if let Some(entity_id) = self.entity_id {
+            write!(f, " (entity {})", entity_id)?;
+        }
+        write!(
+            f,
+            ": {} off-grid sides, {} off-grid vertices",
+            self.side_ids.len(),
+            self.vertices.len()
+        )
+    }
+}
+
+impl Solid {
+    /// Looks for plane points and vertices that don't lie on a grid.
+    ///
+    /// Plane points off the grid are usually what makes a brush drift, while
+    /// off-grid vertices are expected on brushes with slanted sides.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid size, for example `ViewSettings::grid_spacing`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing what is off the grid, `None` if everything is on it,
+    /// or a `VmfError` if the grid size is not positive or a plane is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// if let Some(off_grid) = vmf.world.solids[0].off_grid(8.0)? {
+    ///     println!("{}", off_grid);
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn off_grid(&self, grid: f64) -> VmfResult<Option<OffGrid>> {
+        check_grid(grid)?;
+
+        let mut side_ids = Vec::new();
+        for side in &self.sides {
+            if side.plane_points()?.iter().any(|&p| !on_grid(p, grid)) {
+                side_ids.push(side.id);
+            }
+        }
+        let vertices: Vec<Vector3> = self
+            .vertices()?
+            .into_iter()
+            .filter(|&v| !on_grid(v, grid))
+            .collect();
+
+        if side_ids.is_empty() && vertices.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(OffGrid {
+            solid_id: self.id,
+            entity_id: None,
+            side_ids,
+            vertices,
+        }))
+    }
+
+    /// Snaps the plane points of every side, and the start position of any
+    /// displacement, to a grid.
+    ///
+    /// Snapping can collapse thin brushes or turn slanted sides inside out, so the
+    /// snapped brush is validated and the solid is left unchanged if it is invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid size, for example `ViewSettings::grid_spacing`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the problems the snapped brush would have, empty if
+    /// the solid was snapped, or a `VmfError` if the grid size is not positive or a
+    /// plane is malformed.
+    pub fn snap_to_grid(&mut self, grid: f64) -> VmfResult<Vec<SolidProblem>> {
+        check_grid(grid)?;
+
+        let mut snapped = self.clone();
+        for side in &mut snapped.sides {
+            let points = side.plane_points()?.map(|p| snap(p, grid));
+            side.set_plane_points(points);
+            if let Some(dispinfo) = &mut side.dispinfo {
+                let start = snap(dispinfo.start_position.parse()?, grid);
+                dispinfo.start_position = format!("[{}]", start);
+            }
+        }
+
+        let problems = snapped.validate();
+        if problems.is_empty() {
+            *self = snapped;
+        }
+        Ok(problems)
+    }
+}
+
+/// Returns an error unless `grid` is a usable grid size.
+fn check_grid(grid: f64) -> VmfResult<()> {
+    if grid.is_finite() && grid > 0.0 {
+        Ok(())
+    } else {
+        Err(VmfError::InvalidFormat(format!(
+            "Invalid grid size {}",
+            grid
+        )))
+    }
+}
+
+fn snap(point: Vector3, grid: f64) -> Vector3 {
+    Vector3::new(
+        (point.x / grid).round() * grid,
+        (point.y / grid).round() * grid,
+        (point.z / grid).round() * grid,
+    )
+}
+
+fn on_grid(point: Vector3, grid: f64) -> bool {
+    snap(point, grid).approx_eq(point, GRID_EPSILON)
+}
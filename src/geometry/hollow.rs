@@ -0,0 +1,94 @@
+//! Turning a brush into a shell of walls, like Hammer's Make Hollow.
+
+use super::clip::plane_points;
+use super::{Plane, polygons_from_planes};
+use crate::errors::{VmfError, VmfResult};
+use crate::prelude::{IdAllocator, Solid};
+
+impl Solid {
+    /// Replaces the brush with walls enclosing its former interior.
+    ///
+    /// Like Hammer, a positive `thickness` builds the walls inside the brush and a
+    /// negative one builds them around it, leaving the brush's volume empty. The shell
+    /// is carved with `Solid::subtract`, so the walls don't overlap and each wall face
+    /// takes the material and texture axes of the original face it lies on or
+    /// faces. The first wall keeps the brush's ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `thickness` - The wall thickness in units; negative to build outwards.
+    /// * `ids` - Where new solid and side IDs come from, usually `VmfFile::id_allocator`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the walls, or a `VmfError` if the thickness is zero,
+    /// the walls are thicker than the brush allows, a plane is malformed or the brush
+    /// has a displacement.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("your_map.vmf")?;
+    /// let mut ids = vmf.id_allocator();
+    /// let room = vmf.world.solids.remove(0);
+    /// vmf.world.solids.extend(room.hollow(16.0, &mut ids)?);
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn hollow(&self, thickness: f64, ids: &mut IdAllocator) -> VmfResult<Vec<Solid>> {
+        if thickness == 0.0 || !thickness.is_finite() {
+            return Err(VmfError::InvalidFormat(format!(
+                "Invalid wall thickness {} for solid {}",
+                thickness, self.id
+            )));
+        }
+        if let Some(side) = self.sides.iter().find(|s| s.dispinfo.is_some()) {
+            return Err(VmfError::InvalidFormat(format!(
+                "Cannot hollow solid {}: side {} is a displacement",
+                self.id, side.id
+            )));
+        }
+
+        let offset = self.offset(-thickness)?.ok_or_else(|| {
+            VmfError::InvalidFormat(format!(
+                "Walls of {} units are too thick for solid {}",
+                thickness, self.id
+            ))
+        })?;
+        let (outer, inner) = if thickness > 0.0 {
+            (self.clone(), offset)
+        } else {
+            (offset, self.clone())
+        };
+        outer
+            .subtract(&inner, ids)?
+            .ok_or_else(|| VmfError::InvalidFormat(format!("Cannot hollow solid {}", self.id)))
+    }
+
+    /// Moves every side plane outwards by `distance`, or inwards if it is negative.
+    ///
+    /// Sides that no longer touch the brush are dropped, and `None` is returned if
+    /// too few are left to close it.
+    fn offset(&self, distance: f64) -> VmfResult<Option<Solid>> {
+        let planes: Vec<Plane> = self
+            .planes()?
+            .iter()
+            .map(|p| Plane::new(p.normal, p.dist + distance))
+            .collect();
+        let polygons = polygons_from_planes(&planes);
+
+        let mut solid = Solid {
+            sides: Vec::with_capacity(planes.len()),
+            ..self.clone()
+        };
+        for ((side, plane), polygon) in self.sides.iter().zip(&planes).zip(&polygons) {
+            if let Some(polygon) = polygon {
+                let mut side = side.clone();
+                side.set_plane_points(plane_points(plane, polygon));
+                solid.sides.push(side);
+            }
+        }
+        Ok((solid.sides.len() >= 4).then_some(solid))
+    }
+}
@@ -0,0 +1,176 @@
+//! A 3x3 matrix used for rotations and other linear transforms.
+
+use std::ops::Mul;
+
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+
+use super::Vector3;
+
+/// A row-major 3x3 matrix.
+///
+/// Rotation matrices follow the Source engine convention: the columns are the
+/// forward (X), left (Y) and up (Z) axes of the rotated frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Matrix3 {
+    /// The matrix elements, indexed as `m[row][column]`.
+    pub m: [[f64; 3]; 3],
+}
+
+impl Default for Matrix3 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Matrix3 {
+    /// The identity matrix.
+    pub const IDENTITY: Matrix3 = Matrix3 {
+        m: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    };
+
+    /// Creates a matrix from its three columns.
+    pub fn from_columns(x: Vector3, y: Vector3, z: Vector3) -> Self {
+        Self {
+            m: [[x.x, y.x, z.x], [x.y, y.y, z.y], [x.z, y.z, z.z]],
+        }
+    }
+
+    /// Creates a diagonal scaling matrix.
+    pub fn from_scale(scale: Vector3) -> Self {
+        Self {
+            m: [
+                [scale.x, 0.0, 0.0],
+                [0.0, scale.y, 0.0],
+                [0.0, 0.0, scale.z],
+            ],
+        }
+    }
+
+    /// Creates a rotation matrix from Source engine angles, in degrees.
+    ///
+    /// The angles are given as `(pitch, yaw, roll)`, in the same order as the
+    /// `angles` key of an entity, and produce the same matrix as the engine's `AngleMatrix`.
+    pub fn from_angles(angles: Vector3) -> Self {
+        let (sp, cp) = angles.x.to_radians().sin_cos();
+        let (sy, cy) = angles.y.to_radians().sin_cos();
+        let (sr, cr) = angles.z.to_radians().sin_cos();
+
+        Self {
+            m: [
+                [cp * cy, sr * sp * cy - cr * sy, cr * sp * cy + sr * sy],
+                [cp * sy, sr * sp * sy + cr * cy, cr * sp * sy - sr * cy],
+                [-sp, sr * cp, cr * cp],
+            ],
+        }
+    }
+
+    /// Creates a rotation of `degrees` around `axis` (right-handed).
+    pub fn from_axis_angle(axis: Vector3, degrees: f64) -> Self {
+        let a = axis.normalize();
+        let (s, c) = degrees.to_radians().sin_cos();
+        let t = 1.0 - c;
+
+        Self {
+            m: [
+                [
+                    t * a.x * a.x + c,
+                    t * a.x * a.y - s * a.z,
+                    t * a.x * a.z + s * a.y,
+                ],
+                [
+                    t * a.x * a.y + s * a.z,
+                    t * a.y * a.y + c,
+                    t * a.y * a.z - s * a.x,
+                ],
+                [
+                    t * a.x * a.z - s * a.y,
+                    t * a.y * a.z + s * a.x,
+                    t * a.z * a.z + c,
+                ],
+            ],
+        }
+    }
+
+    /// Converts a rotation matrix back into Source engine angles `(pitch, yaw, roll)`, in degrees.
+    ///
+    /// This mirrors the engine's `MatrixAngles`, so the result can be written
+    /// directly into an entity's `angles` key.
+    pub fn to_angles(&self) -> Vector3 {
+        let forward = self.column(0);
+        let left = self.column(1);
+        let up = self.column(2);
+        let xy_dist = (forward.x * forward.x + forward.y * forward.y).sqrt();
+
+        if xy_dist > 0.001 {
+            Vector3::new(
+                (-forward.z).atan2(xy_dist).to_degrees(),
+                forward.y.atan2(forward.x).to_degrees(),
+                left.z.atan2(up.z).to_degrees(),
+            )
+        } else {
+            Vector3::new(
+                (-forward.z).atan2(xy_dist).to_degrees(),
+                (-left.x).atan2(left.y).to_degrees(),
+                0.0,
+            )
+        }
+    }
+
+    /// Returns the column at `index` as a vector.
+    pub fn column(&self, index: usize) -> Vector3 {
+        Vector3::new(self.m[0][index], self.m[1][index], self.m[2][index])
+    }
+
+    /// Returns the row at `index` as a vector.
+    pub fn row(&self, index: usize) -> Vector3 {
+        Vector3::from(self.m[index])
+    }
+
+    /// Returns the transposed matrix.
+    pub fn transpose(&self) -> Matrix3 {
+        Matrix3::from_columns(self.row(0), self.row(1), self.row(2))
+    }
+
+    /// Returns the determinant of the matrix.
+    pub fn determinant(&self) -> f64 {
+        self.row(0).dot(self.row(1).cross(self.row(2)))
+    }
+
+    /// Returns the inverse of the matrix, or `None` if it is singular.
+    pub fn inverse(&self) -> Option<Matrix3> {
+        let det = self.determinant();
+        if det.abs() < 1e-12 {
+            return None;
+        }
+
+        let (r0, r1, r2) = (self.row(0), self.row(1), self.row(2));
+        // The columns of the inverse are the cross products of the rows, divided by the determinant.
+        Some(Matrix3::from_columns(
+            r1.cross(r2) / det,
+            r2.cross(r0) / det,
+            r0.cross(r1) / det,
+        ))
+    }
+}
+
+impl Mul<Vector3> for Matrix3 {
+    type Output = Vector3;
+
+    fn mul(self, v: Vector3) -> Vector3 {
+        Vector3::new(self.row(0).dot(v), self.row(1).dot(v), self.row(2).dot(v))
+    }
+}
+
+impl Mul for Matrix3 {
+    type Output = Matrix3;
+
+    fn mul(self, rhs: Matrix3) -> Matrix3 {
+        Matrix3::from_columns(
+            self * rhs.column(0),
+            self * rhs.column(1),
+            self * rhs.column(2),
+        )
+    }
+}
@@ -0,0 +1,131 @@
+//! Triangle meshes with per-material groups, the common form for exporting geometry.
+
+use super::Vector3;
+
+/// A single mesh vertex.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MeshVertex {
+    /// The position of the vertex.
+    pub position: Vector3,
+    /// The unit normal of the face the vertex belongs to.
+    pub normal: Vector3,
+    /// The texture coordinates, in material sizes: `[1, 1]` is one full repeat.
+    /// V points down the texture, like in Hammer and glTF.
+    pub uv: [f64; 2],
+}
+
+/// The triangles of a mesh that share a material.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MeshGroup {
+    /// The material name, as written in the VMF.
+    pub material: String,
+    /// Indices into `Mesh::vertices`, three per triangle, wound counter-clockwise
+    /// when viewed from the front.
+    pub indices: Vec<u32>,
+}
+
+/// A triangle mesh, with its triangles grouped by material.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Mesh {
+    /// The vertices of the mesh.
+    pub vertices: Vec<MeshVertex>,
+    /// The triangles of the mesh, one group per material.
+    pub groups: Vec<MeshGroup>,
+}
+
+impl Mesh {
+    /// Creates an empty mesh.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if the mesh has no triangles.
+    pub fn is_empty(&self) -> bool {
+        self.groups.iter().all(|group| group.indices.is_empty())
+    }
+
+    /// Returns the number of triangles in the mesh.
+    pub fn triangle_count(&self) -> usize {
+        self.groups
+            .iter()
+            .map(|group| group.indices.len() / 3)
+            .sum()
+    }
+
+    /// Adds a convex polygon to the mesh as a triangle fan.
+    ///
+    /// # Arguments
+    ///
+    /// * `material` - The material of the polygon.
+    /// * `vertices` - The polygon's vertices, wound counter-clockwise when viewed from the front.
+    ///   Polygons with fewer than three vertices are ignored.
+    pub fn add_polygon(&mut self, material: &str, vertices: &[MeshVertex]) {
+        if vertices.len() < 3 {
+            return;
+        }
+        let first = self.vertices.len() as u32;
+        self.vertices.extend_from_slice(vertices);
+
+        let group = self.group_mut(material);
+        for i in 1..vertices.len() as u32 - 1 {
+            group
+                .indices
+                .extend_from_slice(&[first, first + i, first + i + 1]);
+        }
+    }
+
+    /// Adds a triangle to the mesh.
+    ///
+    /// # Arguments
+    ///
+    /// * `material` - The material of the triangle.
+    /// * `indices` - Indices of existing vertices, wound counter-clockwise when viewed from the front.
+    pub fn add_triangle(&mut self, material: &str, indices: [u32; 3]) {
+        self.group_mut(material).indices.extend_from_slice(&indices);
+    }
+
+    /// Appends all vertices and triangles of another mesh.
+    pub fn append(&mut self, other: &Mesh) {
+        let offset = self.vertices.len() as u32;
+        self.vertices.extend_from_slice(&other.vertices);
+        for group in &other.groups {
+            self.group_mut(&group.material)
+                .indices
+                .extend(group.indices.iter().map(|i| i + offset));
+        }
+    }
+
+    /// Multiplies every position by `factor`, for example to convert Hammer units to meters.
+    pub fn scale(&mut self, factor: f64) {
+        for vertex in &mut self.vertices {
+            vertex.position = vertex.position * factor;
+        }
+    }
+
+    /// Converts the mesh from Source's Z-up coordinates to Y-up coordinates.
+    ///
+    /// `(x, y, z)` becomes `(x, z, -y)`. This is a rotation, so the winding of the
+    /// triangles stays the same.
+    pub fn z_up_to_y_up(&mut self) {
+        let convert = |v: Vector3| Vector3::new(v.x, v.z, -v.y);
+        for vertex in &mut self.vertices {
+            vertex.position = convert(vertex.position);
+            vertex.normal = convert(vertex.normal);
+        }
+    }
+
+    /// Returns the group for `material`, creating it if needed.
+    fn group_mut(&mut self, material: &str) -> &mut MeshGroup {
+        let index = match self.groups.iter().position(|g| g.material == material) {
+            Some(index) => index,
+            None => {
+                self.groups.push(MeshGroup {
+                    material: material.to_string(),
+                    indices: Vec::new(),
+                });
+                self.groups.len() - 1
+            }
+        };
+        &mut self.groups[index]
+    }
+}
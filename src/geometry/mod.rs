@@ -0,0 +1,44 @@
+//! This module provides the geometric types used to work with brushes and entities:
+//! vectors, matrices, planes, polygons, meshes, displacement surfaces, bounding boxes and affine transforms.
+
+mod aabb;
+mod brush;
+mod carve;
+mod clip;
+mod displacement;
+mod grid;
+mod hollow;
+mod matrix;
+mod mesh;
+mod plane;
+mod polygon;
+mod primitive;
+mod sculpt;
+mod sew;
+mod texture;
+mod transform;
+mod validation;
+mod vector;
+
+pub(crate) use aabb::union_bounds;
+pub use aabb::{Aabb, BoundsOptions};
+pub use brush::Face;
+pub(crate) use brush::polygons_from_planes;
+pub use clip::{ClipMode, ClipResult};
+pub use displacement::DispMesh;
+pub(crate) use displacement::{check_power, grid_size};
+pub use grid::OffGrid;
+pub use matrix::Matrix3;
+pub use mesh::{Mesh, MeshGroup, MeshVertex};
+pub use plane::{Plane, PlaneSide};
+pub use polygon::{BASE_WINDING_SIZE, ON_EPSILON, Polygon};
+pub use primitive::{ArchOptions, TorusOptions};
+pub(crate) use sculpt::sculpt_solids;
+pub use sculpt::{Falloff, SculptBrush, SculptOp};
+pub use sew::DispSew;
+pub(crate) use sew::sew_solids;
+pub use texture::Justify;
+pub use transform::{TextureLock, Transform};
+pub use validation::{SolidProblem, SolidProblemKind};
+pub use vector::Vector3;
+pub(crate) use vector::parse_numbers;
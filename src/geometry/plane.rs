@@ -0,0 +1,150 @@
+//! An infinite plane, as described by the `plane` key of a side.
+
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+
+use super::Vector3;
+
+/// Where a point lies relative to a plane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaneSide {
+    /// In front of the plane (on the side the normal points to).
+    Front,
+    /// Behind the plane.
+    Back,
+    /// Within the epsilon of the plane.
+    On,
+}
+
+/// A plane defined by `dot(normal, point) == dist`.
+///
+/// For brush sides the normal points out of the solid, so the inside of a brush
+/// is behind all of its planes.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Plane {
+    /// The unit normal of the plane.
+    pub normal: Vector3,
+    /// The distance of the plane from the origin along the normal.
+    pub dist: f64,
+}
+
+impl Plane {
+    /// Creates a plane from a normal (which is normalized) and a distance.
+    pub fn new(normal: Vector3, dist: f64) -> Self {
+        Self {
+            normal: normal.normalize(),
+            dist,
+        }
+    }
+
+    /// Creates a plane from three points in Hammer's winding order.
+    ///
+    /// Hammer lists plane points clockwise when seen from outside the solid, so the
+    /// resulting normal points outwards.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the plane, or `None` if the points are collinear.
+    pub fn from_points(a: Vector3, b: Vector3, c: Vector3) -> Option<Self> {
+        let normal = (a - b).cross(c - b);
+        let length = normal.length();
+        if length < 1e-9 {
+            return None;
+        }
+
+        let normal = normal / length;
+        Some(Self {
+            normal,
+            dist: normal.dot(a),
+        })
+    }
+
+    /// Creates a plane through `point` with the given `normal`.
+    pub fn from_point_normal(point: Vector3, normal: Vector3) -> Self {
+        let normal = normal.normalize();
+        Self {
+            normal,
+            dist: normal.dot(point),
+        }
+    }
+
+    /// Returns the signed distance from the plane to `point` (positive in front).
+    pub fn distance_to(&self, point: Vector3) -> f64 {
+        self.normal.dot(point) - self.dist
+    }
+
+    /// Classifies `point` against the plane.
+    ///
+    /// # Arguments
+    ///
+    /// * `point` - The point to classify.
+    /// * `epsilon` - Points closer to the plane than this are considered on it.
+    pub fn classify(&self, point: Vector3, epsilon: f64) -> PlaneSide {
+        let distance = self.distance_to(point);
+        if distance > epsilon {
+            PlaneSide::Front
+        } else if distance < -epsilon {
+            PlaneSide::Back
+        } else {
+            PlaneSide::On
+        }
+    }
+
+    /// Returns the plane facing the opposite direction.
+    pub fn flipped(&self) -> Plane {
+        Plane {
+            normal: -self.normal,
+            dist: -self.dist,
+        }
+    }
+
+    /// Projects `point` onto the plane.
+    pub fn project(&self, point: Vector3) -> Vector3 {
+        point - self.normal * self.distance_to(point)
+    }
+
+    /// Returns `true` if both planes are the same within the given tolerances.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The plane to compare against.
+    /// * `normal_epsilon` - Allowed difference per normal component.
+    /// * `dist_epsilon` - Allowed difference in distance.
+    pub fn approx_eq(&self, other: &Plane, normal_epsilon: f64, dist_epsilon: f64) -> bool {
+        self.normal.approx_eq(other.normal, normal_epsilon)
+            && (self.dist - other.dist).abs() <= dist_epsilon
+    }
+
+    /// Returns three points on the plane in Hammer's winding order, suitable for a side's `plane` key.
+    ///
+    /// The points are spaced `size` units apart around the point closest to the origin.
+    pub fn to_points(&self, size: f64) -> [Vector3; 3] {
+        let (right, up) = self.basis();
+        let origin = self.normal * self.dist;
+        // Clockwise when viewed from the front: up-left, up-right, down-right.
+        [
+            origin - right * size + up * size,
+            origin + right * size + up * size,
+            origin + right * size - up * size,
+        ]
+    }
+
+    /// Returns two unit vectors that, together with the normal, form a right-handed basis.
+    ///
+    /// The first vector is chosen the way VBSP builds its base windings, so that
+    /// it is as close to the world's horizontal plane as possible.
+    pub fn basis(&self) -> (Vector3, Vector3) {
+        let n = self.normal;
+        let abs = n.abs();
+        // Pick the world axis least aligned with the normal as the initial "up".
+        let up_axis = if abs.z >= abs.x && abs.z >= abs.y {
+            Vector3::X
+        } else {
+            Vector3::Z
+        };
+        let up = (up_axis - n * up_axis.dot(n)).normalize();
+        let right = up.cross(n);
+        (right, up)
+    }
+}
@@ -0,0 +1,207 @@
+//! Convex planar polygons (windings) and plane clipping.
+
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+
+use super::{Plane, PlaneSide, Vector3};
+
+/// The distance within which a point is considered to lie on a plane while clipping.
+///
+/// This is the `ON_EPSILON` used by VBSP when chopping brush windings.
+pub const ON_EPSILON: f64 = 0.1;
+
+/// The half-extent of the initial winding created for a plane.
+///
+/// It is large enough to cover the whole `[-16384, 16384]` map volume along any direction.
+pub const BASE_WINDING_SIZE: f64 = 65536.0;
+
+/// A convex planar polygon.
+///
+/// Vertices are ordered counter-clockwise when viewed from the front of the
+/// polygon's plane, so for a brush face they are counter-clockwise seen from outside.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Polygon {
+    /// The vertices of the polygon.
+    pub vertices: Vec<Vector3>,
+}
+
+impl Polygon {
+    /// Creates a polygon from its vertices.
+    pub fn new(vertices: Vec<Vector3>) -> Self {
+        Self { vertices }
+    }
+
+    /// Creates a huge square polygon lying on `plane`, ready to be clipped down to a face.
+    pub fn from_plane(plane: &Plane) -> Self {
+        let (right, up) = plane.basis();
+        let origin = plane.normal * plane.dist;
+        let right = right * BASE_WINDING_SIZE;
+        let up = up * BASE_WINDING_SIZE;
+
+        Self {
+            vertices: vec![
+                origin - right - up,
+                origin + right - up,
+                origin + right + up,
+                origin - right + up,
+            ],
+        }
+    }
+
+    /// Returns `true` if the polygon has fewer than three vertices.
+    pub fn is_empty(&self) -> bool {
+        self.vertices.len() < 3
+    }
+
+    /// Returns the number of vertices.
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Returns an iterator over the edges of the polygon as `(start, end)` pairs.
+    pub fn edges(&self) -> impl Iterator<Item = (Vector3, Vector3)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// Returns the area-weighted normal of the polygon (Newell's method), not normalized.
+    fn area_normal(&self) -> Vector3 {
+        let mut normal = Vector3::ZERO;
+        for (a, b) in self.edges() {
+            normal += a.cross(b);
+        }
+        normal * 0.5
+    }
+
+    /// Returns the area of the polygon.
+    pub fn area(&self) -> f64 {
+        self.area_normal().length()
+    }
+
+    /// Returns the unit normal of the polygon, following its winding.
+    pub fn normal(&self) -> Vector3 {
+        self.area_normal().normalize()
+    }
+
+    /// Returns the average of the polygon's vertices.
+    pub fn center(&self) -> Vector3 {
+        if self.vertices.is_empty() {
+            return Vector3::ZERO;
+        }
+        let sum = self.vertices.iter().fold(Vector3::ZERO, |acc, &v| acc + v);
+        sum / self.vertices.len() as f64
+    }
+
+    /// Returns the polygon with its winding reversed.
+    pub fn reversed(&self) -> Polygon {
+        let mut vertices = self.vertices.clone();
+        vertices.reverse();
+        Polygon { vertices }
+    }
+
+    /// Splits the polygon by `plane`.
+    ///
+    /// Vertices within `epsilon` of the plane are shared by both halves. If the
+    /// whole polygon lies on the plane, it is returned as the front half when it
+    /// faces the same way as the plane and as the back half otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `plane` - The splitting plane.
+    /// * `epsilon` - The on-plane tolerance.
+    ///
+    /// # Returns
+    ///
+    /// The `(front, back)` halves, either of which may be `None`.
+    pub fn split(&self, plane: &Plane, epsilon: f64) -> (Option<Polygon>, Option<Polygon>) {
+        let distances: Vec<f64> = self
+            .vertices
+            .iter()
+            .map(|&v| plane.distance_to(v))
+            .collect();
+        let sides: Vec<PlaneSide> = self
+            .vertices
+            .iter()
+            .map(|&v| plane.classify(v, epsilon))
+            .collect();
+
+        let front_count = sides.iter().filter(|&&s| s == PlaneSide::Front).count();
+        let back_count = sides.iter().filter(|&&s| s == PlaneSide::Back).count();
+
+        if front_count == 0 && back_count == 0 {
+            return if self.normal().dot(plane.normal) > 0.0 {
+                (Some(self.clone()), None)
+            } else {
+                (None, Some(self.clone()))
+            };
+        }
+        if back_count == 0 {
+            return (Some(self.clone()), None);
+        }
+        if front_count == 0 {
+            return (None, Some(self.clone()));
+        }
+
+        let n = self.vertices.len();
+        let mut front = Vec::with_capacity(n + 2);
+        let mut back = Vec::with_capacity(n + 2);
+        for i in 0..n {
+            let j = (i + 1) % n;
+            let a = self.vertices[i];
+
+            match sides[i] {
+                PlaneSide::On => {
+                    front.push(a);
+                    back.push(a);
+                    continue;
+                }
+                PlaneSide::Front => front.push(a),
+                PlaneSide::Back => back.push(a),
+            }
+
+            if sides[j] == PlaneSide::On || sides[j] == sides[i] {
+                continue;
+            }
+
+            // The edge crosses the plane; generate the split point.
+            let b = self.vertices[j];
+            let t = distances[i] / (distances[i] - distances[j]);
+            let mut mid = a.lerp(b, t);
+            // Avoid round-off error when the plane is axial.
+            for axis in 0..3 {
+                if plane.normal[axis] == 1.0 {
+                    mid[axis] = plane.dist;
+                } else if plane.normal[axis] == -1.0 {
+                    mid[axis] = -plane.dist;
+                }
+            }
+            front.push(mid);
+            back.push(mid);
+        }
+
+        let front = Polygon::new(front);
+        let back = Polygon::new(back);
+        (
+            (!front.is_empty()).then_some(front),
+            (!back.is_empty()).then_some(back),
+        )
+    }
+
+    /// Clips the polygon to the back side of `plane`, keeping the part inside a brush.
+    ///
+    /// # Returns
+    ///
+    /// The remaining polygon, or `None` if nothing is left.
+    pub fn clip_to_back(&self, plane: &Plane, epsilon: f64) -> Option<Polygon> {
+        // A polygon lying on the plane is kept regardless of its facing, like VBSP does.
+        if self
+            .vertices
+            .iter()
+            .all(|&v| plane.classify(v, epsilon) == PlaneSide::On)
+        {
+            return Some(self.clone());
+        }
+        self.split(plane, epsilon).1
+    }
+}
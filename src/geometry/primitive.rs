@@ -0,0 +1,570 @@
+//! Building brushes from parametric shapes, like the primitives of Hammer's Block Tool.
+
+use std::f64::consts::PI;
+
+use super::clip::new_side;
+use super::{Aabb, Plane, Vector3, polygons_from_planes};
+use crate::errors::{VmfError, VmfResult};
+use crate::prelude::{IdAllocator, Solid};
+
+/// The settings of an arch, matching Hammer's Arch Properties dialog.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArchOptions {
+    /// The number of brushes the arch is made of. Defaults to 8.
+    pub sides: u32,
+    /// The thickness of the arch in units, measured inwards from the bounds. Defaults to 32.
+    pub wall_width: f64,
+    /// The angle covered by the arch in degrees, up to 360. Defaults to 360.
+    pub arc: f64,
+    /// The angle the arch starts at in degrees, counter-clockwise from +X. Defaults to 0.
+    pub start_angle: f64,
+    /// How much higher each brush is than the one before it, for spiral stairs. Defaults to 0.
+    pub add_height: f64,
+}
+
+impl Default for ArchOptions {
+    fn default() -> Self {
+        Self {
+            sides: 8,
+            wall_width: 32.0,
+            arc: 360.0,
+            start_angle: 0.0,
+            add_height: 0.0,
+        }
+    }
+}
+
+/// The settings of a torus.
+///
+/// A torus is built like an arch, but each brush has a rounded cross-section that
+/// fills the height of the bounds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TorusOptions {
+    /// The number of brushes around the ring. Defaults to 8.
+    pub sides: u32,
+    /// The number of sides of the cross-section. Defaults to 8.
+    pub tube_sides: u32,
+    /// The width of the tube in units, measured inwards from the bounds. Defaults to 32.
+    pub ring_width: f64,
+    /// The angle covered by the torus in degrees, up to 360. Defaults to 360.
+    pub arc: f64,
+    /// The angle the torus starts at in degrees, counter-clockwise from +X. Defaults to 0.
+    pub start_angle: f64,
+    /// How much higher each brush is than the one before it. Defaults to 0.
+    pub add_height: f64,
+}
+
+impl Default for TorusOptions {
+    fn default() -> Self {
+        Self {
+            sides: 8,
+            tube_sides: 8,
+            ring_width: 32.0,
+            arc: 360.0,
+            start_angle: 0.0,
+            add_height: 0.0,
+        }
+    }
+}
+
+impl Solid {
+    /// Creates a box filling `bounds`.
+    ///
+    /// Like every primitive, the brush gets a fresh ID, one `material` on all sides and
+    /// world-aligned texture axes. The sides are in Hammer's order: top, bottom, -X, +X,
+    /// +Y and -Y.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The box to fill. It must have a size in every direction.
+    /// * `material` - The material of every side.
+    /// * `ids` - Where the solid and side IDs come from, usually `VmfFile::id_allocator`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the brush, or a `VmfError` if the bounds are flat.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::default();
+    /// let mut ids = vmf.id_allocator();
+    /// let bounds = Aabb::new(Vector3::ZERO, Vector3::new(256.0, 256.0, 16.0));
+    /// let floor = Solid::block(&bounds, "dev/dev_measuregeneric01", &mut ids)?;
+    /// assert!(floor.is_valid());
+    /// vmf.world.solids.push(floor);
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn block(bounds: &Aabb, material: &str, ids: &mut IdAllocator) -> VmfResult<Solid> {
+        check_bounds(bounds)?;
+        let (min, max) = (bounds.min, bounds.max);
+        let planes = [
+            Plane::new(Vector3::Z, max.z),
+            Plane::new(-Vector3::Z, -min.z),
+            Plane::new(-Vector3::X, -min.x),
+            Plane::new(Vector3::X, max.x),
+            Plane::new(Vector3::Y, max.y),
+            Plane::new(-Vector3::Y, -min.y),
+        ];
+        solid_from_planes(&planes, material, ids)
+    }
+
+    /// Creates a wedge filling `bounds`, full height along its -Y side and sloping
+    /// down to the bottom edge at +Y.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the brush, or a `VmfError` if the bounds are flat.
+    pub fn wedge(bounds: &Aabb, material: &str, ids: &mut IdAllocator) -> VmfResult<Solid> {
+        check_bounds(bounds)?;
+        let (min, max) = (bounds.min, bounds.max);
+        let corner = |x: f64, y: f64, z: f64| Vector3::new(x, y, z);
+        let faces = [
+            // Slope
+            vec![
+                corner(min.x, min.y, max.z),
+                corner(max.x, min.y, max.z),
+                corner(max.x, max.y, min.z),
+                corner(min.x, max.y, min.z),
+            ],
+            // Bottom
+            vec![
+                corner(min.x, min.y, min.z),
+                corner(max.x, min.y, min.z),
+                corner(max.x, max.y, min.z),
+                corner(min.x, max.y, min.z),
+            ],
+            // -X and +X
+            vec![
+                corner(min.x, min.y, min.z),
+                corner(min.x, min.y, max.z),
+                corner(min.x, max.y, min.z),
+            ],
+            vec![
+                corner(max.x, min.y, min.z),
+                corner(max.x, min.y, max.z),
+                corner(max.x, max.y, min.z),
+            ],
+            // -Y
+            vec![
+                corner(min.x, min.y, min.z),
+                corner(max.x, min.y, min.z),
+                corner(max.x, min.y, max.z),
+                corner(min.x, min.y, max.z),
+            ],
+        ];
+        solid_from_faces(&faces, material, ids)
+    }
+
+    /// Creates an upright cylinder with `sides` sides, inscribed in `bounds`.
+    ///
+    /// The first vertex of the base lies on the +X side of the bounds and the others
+    /// follow counter-clockwise. The base is an ellipse if the bounds aren't square.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the brush, or a `VmfError` if the bounds are flat or
+    /// there are fewer than 3 sides.
+    pub fn cylinder(
+        bounds: &Aabb,
+        sides: u32,
+        material: &str,
+        ids: &mut IdAllocator,
+    ) -> VmfResult<Solid> {
+        Solid::cone(bounds, sides, 1.0, material, ids)
+    }
+
+    /// Creates an upright pyramid with `sides` sides, its base inscribed in the bottom
+    /// of `bounds` and its tip in the middle of the top.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the brush, or a `VmfError` if the bounds are flat or
+    /// there are fewer than 3 sides.
+    pub fn spike(
+        bounds: &Aabb,
+        sides: u32,
+        material: &str,
+        ids: &mut IdAllocator,
+    ) -> VmfResult<Solid> {
+        Solid::cone(bounds, sides, 0.0, material, ids)
+    }
+
+    /// Creates an upright cone with `sides` sides, its base inscribed in the bottom of
+    /// `bounds`, cut off at the top of the bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The box the cone is inscribed in.
+    /// * `sides` - The number of sides around the cone, at least 3.
+    /// * `top_ratio` - The size of the top relative to the base, from 0 for a spike to
+    ///   1 for a cylinder.
+    /// * `material` - The material of every side.
+    /// * `ids` - Where the solid and side IDs come from, usually `VmfFile::id_allocator`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the brush, or a `VmfError` if the bounds are flat,
+    /// there are fewer than 3 sides or `top_ratio` is out of range.
+    pub fn cone(
+        bounds: &Aabb,
+        sides: u32,
+        top_ratio: f64,
+        material: &str,
+        ids: &mut IdAllocator,
+    ) -> VmfResult<Solid> {
+        check_bounds(bounds)?;
+        check_sides(sides, 3)?;
+        if !(0.0..=1.0).contains(&top_ratio) {
+            return Err(VmfError::InvalidFormat(format!(
+                "Cone top ratio must be between 0 and 1, got {}",
+                top_ratio
+            )));
+        }
+
+        let center = bounds.center();
+        let radii = bounds.size() * 0.5;
+        let ring = |z: f64, scale: f64| -> Vec<Vector3> {
+            (0..sides)
+                .map(|i| {
+                    let angle = 2.0 * PI * f64::from(i) / f64::from(sides);
+                    ellipse_point(center, radii * scale, angle, z)
+                })
+                .collect()
+        };
+        let bottom = ring(bounds.min.z, 1.0);
+        let top = ring(bounds.max.z, top_ratio);
+
+        let mut faces = vec![top.clone(), bottom.clone()];
+        for i in 0..bottom.len() {
+            let j = (i + 1) % bottom.len();
+            faces.push(vec![bottom[i], bottom[j], top[j], top[i]]);
+        }
+        solid_from_faces(&faces, material, ids)
+    }
+
+    /// Creates a sphere inscribed in `bounds`, with `sides` sides around its equator
+    /// and half as many rings from pole to pole.
+    ///
+    /// The sphere is a single convex brush, and an ellipsoid if the bounds aren't a cube.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the brush, or a `VmfError` if the bounds are flat or
+    /// there are fewer than 4 sides.
+    pub fn sphere(
+        bounds: &Aabb,
+        sides: u32,
+        material: &str,
+        ids: &mut IdAllocator,
+    ) -> VmfResult<Solid> {
+        check_bounds(bounds)?;
+        check_sides(sides, 4)?;
+
+        let center = bounds.center();
+        let radii = bounds.size() * 0.5;
+        let rings = sides / 2;
+        let point = |ring: u32, side: u32| {
+            let latitude = PI * f64::from(ring) / f64::from(rings) - PI / 2.0;
+            let longitude = 2.0 * PI * f64::from(side % sides) / f64::from(sides);
+            Vector3::new(
+                center.x + radii.x * latitude.cos() * longitude.cos(),
+                center.y + radii.y * latitude.cos() * longitude.sin(),
+                center.z + radii.z * latitude.sin(),
+            )
+        };
+
+        let mut faces = Vec::new();
+        for ring in 0..rings {
+            for side in 0..sides {
+                faces.push(vec![
+                    point(ring, side),
+                    point(ring, side + 1),
+                    point(ring + 1, side + 1),
+                    point(ring + 1, side),
+                ]);
+            }
+        }
+        solid_from_faces(&faces, material, ids)
+    }
+
+    /// Creates an arch of brushes around the vertical axis of `bounds`.
+    ///
+    /// The outside of the arch is inscribed in `bounds` and each brush spans an equal
+    /// part of the arc.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The box the arch is inscribed in.
+    /// * `options` - The shape of the arch.
+    /// * `material` - The material of every side.
+    /// * `ids` - Where the solid and side IDs come from, usually `VmfFile::id_allocator`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the brushes in order from the start angle, or a
+    /// `VmfError` if the bounds are flat or an option is out of range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut ids = VmfFile::default().id_allocator();
+    /// let bounds = Aabb::new(Vector3::new(-128.0, -128.0, 0.0), Vector3::new(128.0, 128.0, 16.0));
+    /// let options = ArchOptions { arc: 180.0, ..Default::default() };
+    /// let arch = Solid::arch(&bounds, &options, "dev/dev_measuregeneric01", &mut ids)?;
+    /// assert_eq!(arch.len(), 8);
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn arch(
+        bounds: &Aabb,
+        options: &ArchOptions,
+        material: &str,
+        ids: &mut IdAllocator,
+    ) -> VmfResult<Vec<Solid>> {
+        check_bounds(bounds)?;
+        check_ring(
+            bounds,
+            options.sides,
+            options.wall_width,
+            options.arc,
+            options.start_angle,
+        )?;
+
+        let center = bounds.center();
+        let radii = bounds.size() * 0.5;
+        let inner_radii = radii - Vector3::splat(options.wall_width);
+        let angles = ring_angles(options.sides, options.arc, options.start_angle);
+
+        let mut solids = Vec::with_capacity(options.sides as usize);
+        for (i, pair) in angles.windows(2).enumerate() {
+            let lift = options.add_height * i as f64;
+            let (bottom, top) = (bounds.min.z + lift, bounds.max.z + lift);
+            let outer = |angle: f64, z: f64| ellipse_point(center, radii, angle, z);
+            let inner = |angle: f64, z: f64| ellipse_point(center, inner_radii, angle, z);
+            let (a, b) = (pair[0], pair[1]);
+
+            let faces = [
+                vec![outer(a, top), outer(b, top), inner(b, top), inner(a, top)],
+                vec![
+                    outer(a, bottom),
+                    outer(b, bottom),
+                    inner(b, bottom),
+                    inner(a, bottom),
+                ],
+                vec![
+                    outer(a, bottom),
+                    outer(b, bottom),
+                    outer(b, top),
+                    outer(a, top),
+                ],
+                vec![
+                    inner(a, bottom),
+                    inner(b, bottom),
+                    inner(b, top),
+                    inner(a, top),
+                ],
+                vec![
+                    outer(a, bottom),
+                    inner(a, bottom),
+                    inner(a, top),
+                    outer(a, top),
+                ],
+                vec![
+                    outer(b, bottom),
+                    inner(b, bottom),
+                    inner(b, top),
+                    outer(b, top),
+                ],
+            ];
+            solids.push(solid_from_faces(&faces, material, ids)?);
+        }
+        Ok(solids)
+    }
+
+    /// Creates a torus of brushes around the vertical axis of `bounds`.
+    ///
+    /// The outside of the torus is inscribed in `bounds`, and the tube is as tall as
+    /// the bounds and `options.ring_width` wide. Each brush spans an equal part of
+    /// the arc.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the brushes in order from the start angle, or a
+    /// `VmfError` if the bounds are flat or an option is out of range.
+    pub fn torus(
+        bounds: &Aabb,
+        options: &TorusOptions,
+        material: &str,
+        ids: &mut IdAllocator,
+    ) -> VmfResult<Vec<Solid>> {
+        check_bounds(bounds)?;
+        check_ring(
+            bounds,
+            options.sides,
+            options.ring_width,
+            options.arc,
+            options.start_angle,
+        )?;
+        check_sides(options.tube_sides, 3)?;
+
+        let center = bounds.center();
+        let radii = bounds.size() * 0.5;
+        let half_width = options.ring_width / 2.0;
+        let angles = ring_angles(options.sides, options.arc, options.start_angle);
+
+        // Points of the cross-section as (inset from the outside, height above the center).
+        let section: Vec<(f64, f64)> = (0..options.tube_sides)
+            .map(|i| {
+                let angle = 2.0 * PI * f64::from(i) / f64::from(options.tube_sides);
+                (half_width * (1.0 - angle.cos()), radii.z * angle.sin())
+            })
+            .collect();
+
+        let mut solids = Vec::with_capacity(options.sides as usize);
+        for (i, pair) in angles.windows(2).enumerate() {
+            let lift = center.z + options.add_height * i as f64;
+            let cap = |angle: f64| -> Vec<Vector3> {
+                section
+                    .iter()
+                    .map(|&(inset, z)| {
+                        let radii = radii - Vector3::splat(inset);
+                        ellipse_point(center, radii, angle, lift + z)
+                    })
+                    .collect()
+            };
+            let (start, end) = (cap(pair[0]), cap(pair[1]));
+
+            let mut faces = vec![start.clone(), end.clone()];
+            for k in 0..section.len() {
+                let l = (k + 1) % section.len();
+                faces.push(vec![start[k], start[l], end[l], end[k]]);
+            }
+            solids.push(solid_from_faces(&faces, material, ids)?);
+        }
+        Ok(solids)
+    }
+}
+
+/// Checks that a primitive's bounds have a size in every direction.
+fn check_bounds(bounds: &Aabb) -> VmfResult<()> {
+    let size = bounds.size();
+    if size.x > 0.0 && size.y > 0.0 && size.z > 0.0 {
+        Ok(())
+    } else {
+        Err(VmfError::InvalidFormat(format!(
+            "Primitive bounds must not be flat, got size ({} {} {})",
+            size.x, size.y, size.z
+        )))
+    }
+}
+
+/// Checks that a primitive has at least `min` sides.
+fn check_sides(sides: u32, min: u32) -> VmfResult<()> {
+    if sides >= min {
+        Ok(())
+    } else {
+        Err(VmfError::InvalidFormat(format!(
+            "Primitive needs at least {} sides, got {}",
+            min, sides
+        )))
+    }
+}
+
+/// Checks the shared settings of arches and tori.
+fn check_ring(bounds: &Aabb, sides: u32, width: f64, arc: f64, start_angle: f64) -> VmfResult<()> {
+    check_sides(sides, 1)?;
+    let size = bounds.size();
+    let max_width = size.x.min(size.y) / 2.0;
+    if !(width > 0.0 && width <= max_width) {
+        return Err(VmfError::InvalidFormat(format!(
+            "Ring width must be between 0 and {}, got {}",
+            max_width, width
+        )));
+    }
+    // Each brush has to stay convex, so it can't span half a turn or more.
+    let valid_arc = arc > 0.0 && arc <= 360.0 && arc / f64::from(sides) < 180.0;
+    if !valid_arc || !start_angle.is_finite() {
+        return Err(VmfError::InvalidFormat(format!(
+            "Invalid arc of {} degrees in {} sides",
+            arc, sides
+        )));
+    }
+    Ok(())
+}
+
+/// Splits an arc into `sides` parts, returning the `sides + 1` angles in radians.
+fn ring_angles(sides: u32, arc: f64, start_angle: f64) -> Vec<f64> {
+    (0..=sides)
+        .map(|i| (start_angle + arc * f64::from(i) / f64::from(sides)).to_radians())
+        .collect()
+}
+
+/// Returns the point at `angle` radians on the horizontal ellipse with `radii` at height `z`.
+fn ellipse_point(center: Vector3, radii: Vector3, angle: f64, z: f64) -> Vector3 {
+    Vector3::new(
+        center.x + radii.x * angle.cos(),
+        center.y + radii.y * angle.sin(),
+        z,
+    )
+}
+
+/// Builds a convex brush from the corner points of its faces.
+///
+/// Each face only needs to span its plane; the planes are oriented away from the
+/// middle of the brush, and degenerate faces (such as those at a pole) are skipped.
+fn solid_from_faces(
+    faces: &[Vec<Vector3>],
+    material: &str,
+    ids: &mut IdAllocator,
+) -> VmfResult<Solid> {
+    let points = faces.iter().flatten();
+    let count = points.clone().count().max(1) as f64;
+    let inside = points.fold(Vector3::ZERO, |sum, &p| sum + p) * (1.0 / count);
+
+    let planes: Vec<Plane> = faces
+        .iter()
+        .filter_map(|face| {
+            let first = *face.first()?;
+            let normal = face.windows(2).skip(1).fold(Vector3::ZERO, |sum, pair| {
+                sum + (pair[0] - first).cross(pair[1] - first)
+            });
+            if normal.length_squared() < 1e-12 {
+                return None;
+            }
+            let plane = Plane::from_point_normal(first, normal.normalize());
+            Some(if plane.distance_to(inside) > 0.0 {
+                plane.flipped()
+            } else {
+                plane
+            })
+        })
+        .collect();
+    solid_from_planes(&planes, material, ids)
+}
+
+/// Builds a convex brush bounded by `planes`, leaving out planes that don't touch it.
+fn solid_from_planes(planes: &[Plane], material: &str, ids: &mut IdAllocator) -> VmfResult<Solid> {
+    let polygons = polygons_from_planes(planes);
+    if polygons.iter().flatten().count() < 4 {
+        return Err(VmfError::InvalidFormat(
+            "Primitive is too small to build a brush".to_string(),
+        ));
+    }
+
+    let mut solid = Solid {
+        id: ids.next_object_id(),
+        ..Default::default()
+    };
+    for (plane, polygon) in planes.iter().zip(&polygons) {
+        if let Some(polygon) = polygon {
+            solid
+                .sides
+                .push(new_side(ids.next_side_id(), plane, polygon, material)?);
+        }
+    }
+    Ok(solid)
+}
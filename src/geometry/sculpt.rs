@@ -0,0 +1,451 @@
+//! Terrain sculpting and power changes for displacements.
+
+use std::collections::{HashMap, HashSet};
+
+use super::displacement::{all_verts_allowed, check_power, grid, grid_size};
+use super::{Plane, Vector3};
+use crate::errors::{VmfError, VmfResult};
+use crate::prelude::{Side, Solid};
+use crate::vmf::world::{DispInfo, DispRows};
+
+/// Displacement vectors shorter than this keep their previous normal.
+const MIN_DISTANCE: f64 = 1e-6;
+
+/// How the strength of a `SculptBrush` fades from its center to its radius.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Falloff {
+    /// Full strength everywhere inside the radius.
+    Constant,
+    /// Fades linearly to nothing at the radius.
+    Linear,
+    /// Fades along a smoothstep curve, flat at the center and the radius.
+    #[default]
+    Smooth,
+}
+
+impl Falloff {
+    /// Returns the strength at `t`, the distance from the center divided by the radius.
+    fn weight(self, t: f64) -> f64 {
+        if t > 1.0 {
+            return 0.0;
+        }
+        match self {
+            Falloff::Constant => 1.0,
+            Falloff::Linear => 1.0 - t,
+            Falloff::Smooth => 1.0 - t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// The area a sculpting operation affects: the displacement vertices within
+/// `radius` of `center`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SculptBrush {
+    /// The center of the brush, in world space.
+    pub center: Vector3,
+    /// The radius of the brush.
+    pub radius: f64,
+    /// How the strength fades towards the radius.
+    pub falloff: Falloff,
+}
+
+impl SculptBrush {
+    /// Creates a brush with a smooth falloff.
+    pub fn new(center: Vector3, radius: f64) -> Self {
+        Self {
+            center,
+            radius,
+            falloff: Falloff::Smooth,
+        }
+    }
+}
+
+/// A sculpting operation, applied at full strength at the center of the brush.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SculptOp {
+    /// Moves vertices out of the face by this distance.
+    Raise(f64),
+    /// Moves vertices into the face by this distance.
+    Lower(f64),
+    /// Moves vertices along the face normal towards the average height of their
+    /// neighbors; 1 moves them all the way.
+    Smooth(f64),
+    /// Moves vertices randomly along the face normal, by up to `amplitude` either way.
+    /// The same `seed` always gives the same noise at the same place.
+    Noise {
+        /// The largest distance a vertex is moved.
+        amplitude: f64,
+        /// The seed of the noise.
+        seed: u64,
+    },
+    /// Moves vertices along the face normal onto a plane.
+    SetToPlane(Plane),
+    /// Paints the blend alpha towards a value from 0 to 255.
+    PaintAlpha(f64),
+}
+
+impl Solid {
+    /// Sculpts the displacements of this brush.
+    ///
+    /// Vertices are selected by their current position and moved along the face
+    /// normal, so each stays on the line through its undisplaced position that
+    /// `normals` and `distances` describe. Vertices shared by two displacements of
+    /// the brush are moved together, keeping the seam closed. To keep seams with
+    /// displacements on other brushes closed too, use `VmfFile::sculpt_displacements`.
+    ///
+    /// # Arguments
+    ///
+    /// * `brush` - The area to sculpt.
+    /// * `op` - What to do to the vertices in it.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the number of displacements changed, or a `VmfError`
+    /// if the brush or the operation is invalid or the displacement data is malformed.
+    /// The solid is left unchanged on error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("displacements.vmf")?;
+    /// let brush = SculptBrush::new(Vector3::new(0.0, 0.0, 0.0), 128.0);
+    /// vmf.world.solids[0].sculpt(&brush, SculptOp::Raise(32.0))?;
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn sculpt(&mut self, brush: &SculptBrush, op: SculptOp) -> VmfResult<usize> {
+        sculpt_solids(vec![self], brush, op)
+    }
+}
+
+impl DispInfo {
+    /// Changes the power of the displacement, resampling its data onto the new grid.
+    ///
+    /// Displacement vectors, offsets and alphas are interpolated bilinearly, so the
+    /// surface keeps its shape; going down in power drops the vertices in between.
+    /// Triangle tags are recomputed from the slope of the resampled surface, and
+    /// allowed vertices are reset.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - The new power, from 2 to 4.
+    /// * `side` - The side the displacement belongs to.
+    /// * `solid` - The solid containing the side.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if the power is out of range or
+    /// the displacement data is malformed. The displacement is left unchanged on error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("displacements.vmf")?;
+    /// let solid = &mut vmf.world.solids[0];
+    /// if let Some(mut disp) = solid.sides[0].dispinfo.clone() {
+    ///     disp.set_power(4, &solid.sides[0], solid)?;
+    ///     solid.sides[0].dispinfo = Some(disp);
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn set_power(&mut self, power: u8, side: &Side, solid: &Solid) -> VmfResult<()> {
+        check_power(power)?;
+        let old_size = grid_size(self.power);
+        let size = grid_size(power);
+
+        let normals = grid(self.normals.vectors()?, old_size, "normals", true)?;
+        let distances = grid(self.distances.floats()?, old_size, "distances", true)?;
+        let vectors: Vec<Vec<Vector3>> = normals
+            .iter()
+            .zip(&distances)
+            .map(|(n, d)| n.iter().zip(d).map(|(&n, &d)| n * d).collect())
+            .collect();
+        let offsets = grid(self.offsets.vectors()?, old_size, "offsets", false)?;
+        let offset_normals = grid(
+            self.offset_normals.vectors()?,
+            old_size,
+            "offset_normals",
+            false,
+        )?;
+        let alphas = grid(self.alphas.floats()?, old_size, "alphas", false)?;
+
+        let vectors = resample(&vectors, size, |a, b, t| a.lerp(b, t));
+        let fallback = resample(&normals, size, |a, b, t| a.lerp(b, t));
+        let (normals, distances): (Vec<Vec<Vector3>>, Vec<Vec<f64>>) = vectors
+            .iter()
+            .zip(&fallback)
+            .map(|(row, fallback)| row.iter().zip(fallback).map(|(&v, &n)| split(v, n)).unzip())
+            .unzip();
+
+        let mut disp = self.clone();
+        disp.normals = DispRows::from_vectors(&normals);
+        disp.distances = DispRows::from_floats(&distances);
+        if !offsets.is_empty() {
+            disp.offsets =
+                DispRows::from_vectors(&resample(&offsets, size, |a, b, t| a.lerp(b, t)));
+        }
+        if !offset_normals.is_empty() {
+            let resampled = resample(&offset_normals, size, |a, b, t| a.lerp(b, t).normalize());
+            disp.offset_normals = DispRows::from_vectors(&resampled);
+        }
+        if !alphas.is_empty() {
+            disp.alphas =
+                DispRows::from_floats(&resample(&alphas, size, |a, b, t| a + (b - a) * t));
+        }
+        disp.allowed_verts = all_verts_allowed();
+        disp.power = power;
+        disp.update_triangle_tags(side, solid)?;
+        *self = disp;
+        Ok(())
+    }
+}
+
+/// One displacement being sculpted.
+struct Surface {
+    solid: usize,
+    side: usize,
+    /// The position of each vertex without its displacement vector.
+    bases: Vec<Vector3>,
+    /// The current displaced positions.
+    positions: Vec<Vector3>,
+    /// The current normals, kept for vertices that end up undisplaced.
+    normals: Vec<Vector3>,
+    /// The current blend alphas.
+    alphas: Vec<f64>,
+    /// The index of each vertex in the list of welded vertices.
+    welded: Vec<usize>,
+    size: usize,
+}
+
+/// Sculpts the displacements of `solids`, welding vertices shared between them.
+pub(crate) fn sculpt_solids(
+    mut solids: Vec<&mut Solid>,
+    brush: &SculptBrush,
+    op: SculptOp,
+) -> VmfResult<usize> {
+    if !brush.radius.is_finite() || brush.radius <= 0.0 {
+        return Err(VmfError::InvalidFormat(format!(
+            "Invalid sculpt radius {}",
+            brush.radius
+        )));
+    }
+    if let SculptOp::SetToPlane(plane) = op
+        && (plane.normal.length() - 1.0).abs() > 1e-6
+    {
+        return Err(VmfError::InvalidFormat(
+            "Cannot sculpt to a plane without a unit normal".to_string(),
+        ));
+    }
+
+    // Weld vertices by their undisplaced position, so coincident edges of
+    // neighboring displacements are treated as one vertex.
+    let mut keys: HashMap<[i64; 3], usize> = HashMap::new();
+    let mut face_normals: Vec<Vector3> = Vec::new();
+    let mut surfaces = Vec::new();
+    for (solid_index, solid) in solids.iter().enumerate() {
+        let solid: &Solid = solid;
+        let faces = solid.faces()?;
+        for (side_index, side) in solid.sides.iter().enumerate() {
+            let Some(disp) = &side.dispinfo else {
+                continue;
+            };
+            let mesh = disp.build_mesh(side, solid)?;
+            let size = mesh.size();
+            let normals: Vec<Vector3> = disp.normals.vectors()?.into_iter().flatten().collect();
+            let distances: Vec<f64> = disp.distances.floats()?.into_iter().flatten().collect();
+            let face_normal = faces
+                .iter()
+                .find(|face| face.side_index == side_index)
+                .map_or(Vector3::ZERO, |face| face.plane.normal.normalize());
+
+            let mut welded = Vec::with_capacity(mesh.vertices.len());
+            for flat in &mesh.flat_vertices {
+                let key = [flat.x, flat.y, flat.z].map(|c| (c * 100.0).round() as i64);
+                let index = *keys.entry(key).or_insert_with(|| {
+                    face_normals.push(Vector3::ZERO);
+                    face_normals.len() - 1
+                });
+                face_normals[index] += face_normal;
+                welded.push(index);
+            }
+            let bases = mesh
+                .vertices
+                .iter()
+                .zip(normals.iter().zip(&distances))
+                .map(|(&p, (&n, &d))| p - n * d)
+                .collect();
+
+            surfaces.push(Surface {
+                solid: solid_index,
+                side: side_index,
+                bases,
+                positions: mesh.vertices,
+                normals,
+                alphas: mesh.alphas,
+                welded,
+                size,
+            });
+        }
+    }
+
+    // The state of each welded vertex, taken from its first displacement.
+    let count = face_normals.len();
+    let mut positions = vec![Vector3::ZERO; count];
+    let mut alphas = vec![0.0; count];
+    let mut neighbors: Vec<HashSet<usize>> = vec![HashSet::new(); count];
+    for surface in surfaces.iter().rev() {
+        for (i, &w) in surface.welded.iter().enumerate() {
+            positions[w] = surface.positions[i];
+            alphas[w] = surface.alphas[i];
+            let (row, column) = (i / surface.size, i % surface.size);
+            if row > 0 {
+                neighbors[w].insert(surface.welded[i - surface.size]);
+            }
+            if row + 1 < surface.size {
+                neighbors[w].insert(surface.welded[i + surface.size]);
+            }
+            if column > 0 {
+                neighbors[w].insert(surface.welded[i - 1]);
+            }
+            if column + 1 < surface.size {
+                neighbors[w].insert(surface.welded[i + 1]);
+            }
+        }
+    }
+
+    let mut moved = positions.clone();
+    let mut painted = alphas.clone();
+    for w in 0..count {
+        let t = positions[w].distance(brush.center) / brush.radius;
+        let weight = brush.falloff.weight(t);
+        if weight <= 0.0 {
+            continue;
+        }
+        let p = positions[w];
+        let normal = face_normals[w].normalize();
+        match op {
+            SculptOp::Raise(amount) => moved[w] = p + normal * (amount * weight),
+            SculptOp::Lower(amount) => moved[w] = p - normal * (amount * weight),
+            SculptOp::Smooth(strength) => {
+                if neighbors[w].is_empty() {
+                    continue;
+                }
+                let sum = neighbors[w]
+                    .iter()
+                    .fold(Vector3::ZERO, |acc, &n| acc + positions[n]);
+                let average = sum / neighbors[w].len() as f64;
+                // Only the height changes, so the edges of the surface don't shrink inwards.
+                moved[w] = p + normal * (normal.dot(average - p) * strength * weight);
+            }
+            SculptOp::Noise { amplitude, seed } => {
+                let key = [p.x, p.y, p.z].map(|c| (c * 100.0).round() as i64);
+                moved[w] = p + normal * (amplitude * weight * noise(key, seed));
+            }
+            SculptOp::SetToPlane(plane) => {
+                // Faces perpendicular to the plane move straight onto it instead.
+                let direction = if plane.normal.dot(normal).abs() > 1e-6 {
+                    normal
+                } else {
+                    plane.normal
+                };
+                let travel = -plane.distance_to(p) / plane.normal.dot(direction);
+                moved[w] = p + direction * (travel * weight);
+            }
+            SculptOp::PaintAlpha(alpha) => {
+                painted[w] = (alphas[w] + (alpha - alphas[w]) * weight).clamp(0.0, 255.0);
+            }
+        }
+    }
+
+    let mut changed = 0;
+    for surface in surfaces {
+        let unchanged = surface
+            .welded
+            .iter()
+            .all(|&w| moved[w] == positions[w] && painted[w] == alphas[w]);
+        if unchanged {
+            continue;
+        }
+        changed += 1;
+
+        let size = surface.size;
+        let disp = solids[surface.solid].sides[surface.side]
+            .dispinfo
+            .as_mut()
+            .expect("sculpted side has a displacement");
+        if let SculptOp::PaintAlpha(_) = op {
+            let alphas: Vec<Vec<f64>> = surface
+                .welded
+                .chunks(size)
+                .map(|row| row.iter().map(|&w| painted[w]).collect())
+                .collect();
+            disp.alphas = DispRows::from_floats(&alphas);
+            continue;
+        }
+
+        let mut normals = vec![Vec::with_capacity(size); size];
+        let mut distances = vec![Vec::with_capacity(size); size];
+        for (i, &w) in surface.welded.iter().enumerate() {
+            let (normal, distance) = split(moved[w] - surface.bases[i], surface.normals[i]);
+            normals[i / size].push(normal);
+            distances[i / size].push(distance);
+        }
+        disp.normals = DispRows::from_vectors(&normals);
+        disp.distances = DispRows::from_floats(&distances);
+    }
+    Ok(changed)
+}
+
+/// Splits a displacement vector into a unit normal and a distance, keeping
+/// `fallback` as the normal of vectors too short to have a direction.
+pub(super) fn split(vector: Vector3, fallback: Vector3) -> (Vector3, f64) {
+    let distance = vector.length();
+    if distance < MIN_DISTANCE {
+        (fallback, 0.0)
+    } else {
+        (vector / distance, distance)
+    }
+}
+
+/// Resamples a square grid to `size` × `size` with bilinear interpolation.
+fn resample<T: Copy>(rows: &[Vec<T>], size: usize, lerp: impl Fn(T, T, f64) -> T) -> Vec<Vec<T>> {
+    if rows.is_empty() {
+        return Vec::new();
+    }
+    let old = rows.len() - 1;
+    let sample = |i: usize| {
+        let x = i as f64 * old as f64 / (size - 1) as f64;
+        let lo = (x.floor() as usize).min(old);
+        let hi = (lo + 1).min(old);
+        (lo, hi, x - lo as f64)
+    };
+    (0..size)
+        .map(|row| {
+            let (r0, r1, tr) = sample(row);
+            (0..size)
+                .map(|column| {
+                    let (c0, c1, tc) = sample(column);
+                    let top = lerp(rows[r0][c0], rows[r0][c1], tc);
+                    let bottom = lerp(rows[r1][c0], rows[r1][c1], tc);
+                    lerp(top, bottom, tr)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// A deterministic value from -1 to 1 for a grid position, using SplitMix64.
+fn noise(key: [i64; 3], seed: u64) -> f64 {
+    let mut hash = seed;
+    for value in key {
+        hash ^= value as u64;
+        hash = hash.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        hash ^= hash >> 31;
+    }
+    (hash >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+}
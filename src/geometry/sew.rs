@@ -0,0 +1,294 @@
+//! Sewing of neighboring displacements and recomputation of their derived fields.
+
+use std::collections::{HashMap, HashSet};
+
+use super::Vector3;
+use super::displacement::{grid_size, triangle_tag};
+use super::sculpt::split;
+use crate::errors::VmfResult;
+use crate::prelude::Solid;
+use crate::vmf::world::DispRows;
+
+/// Vertices closer than this to a neighboring edge are on it.
+const SEW_EPSILON: f64 = 0.01;
+/// The number of integers in `allowed_verts`, enough bits for a power 4 grid.
+const ALLOWED_VERTS_WORDS: usize = 10;
+
+/// The result of `VmfFile::sew_displacements`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DispSew {
+    /// The number of displacements whose normals or distances changed.
+    pub displacements: usize,
+    /// The number of edge vertices moved to close a seam. A vertex shared by several
+    /// displacements is counted once.
+    pub sewn_vertices: usize,
+    /// The number of vertices removed from `allowed_verts` because a neighbor
+    /// has no vertex to match them.
+    pub disallowed_vertices: usize,
+}
+
+/// One displacement being sewn.
+struct Surface {
+    solid: usize,
+    side: usize,
+    size: usize,
+    /// The position of each vertex without its displacement vector.
+    bases: Vec<Vector3>,
+    /// The undisplaced positions, used to find neighbors.
+    flat: Vec<Vector3>,
+    /// The displaced positions.
+    positions: Vec<Vector3>,
+    /// The current normals, kept for vertices that end up undisplaced.
+    normals: Vec<Vector3>,
+    /// The current triangle tags, or zeros if they don't match the grid.
+    tags: Vec<u32>,
+    triangles: Vec<[u32; 3]>,
+    allowed: Vec<bool>,
+}
+
+impl Surface {
+    /// Returns the vertex indices along each of the four edges, in order.
+    fn edges(&self) -> [Vec<usize>; 4] {
+        let n = self.size;
+        [
+            (0..n).collect(),
+            (0..n).map(|row| row * n + n - 1).collect(),
+            (0..n).map(|column| (n - 1) * n + column).collect(),
+            (0..n).map(|row| row * n).collect(),
+        ]
+    }
+
+    fn is_corner(&self, index: usize) -> bool {
+        let last = self.size - 1;
+        let (row, column) = (index / self.size, index % self.size);
+        (row == 0 || row == last) && (column == 0 || column == last)
+    }
+
+    /// Removes a vertex from the allowed vertices, along with every vertex that can
+    /// only be used in a level of detail that also uses it. Returns how many were removed.
+    fn disallow(&mut self, row: usize, column: usize) -> usize {
+        let index = row * self.size + column;
+        if !self.allowed[index] {
+            return 0;
+        }
+        self.allowed[index] = false;
+        let mut removed = 1;
+
+        // The centers and edge midpoints of the quadtree nodes with this vertex as
+        // a corner are only reachable by splitting those nodes, which needs it.
+        let last = self.size - 1;
+        let mut span = 2;
+        while span <= last && row.is_multiple_of(span) && column.is_multiple_of(span) {
+            let half = span / 2;
+            for node_row in [row.checked_sub(span), Some(row)].into_iter().flatten() {
+                for node_column in [column.checked_sub(span), Some(column)]
+                    .into_iter()
+                    .flatten()
+                {
+                    if node_row + span > last || node_column + span > last {
+                        continue;
+                    }
+                    let dependents = [
+                        (node_row + half, node_column + half),
+                        (node_row, node_column + half),
+                        (node_row + span, node_column + half),
+                        (node_row + half, node_column),
+                        (node_row + half, node_column + span),
+                    ];
+                    for (r, c) in dependents {
+                        removed += self.disallow(r, c);
+                    }
+                }
+            }
+            span *= 2;
+        }
+        removed
+    }
+}
+
+/// Sews the displacements of `solids` together and recomputes their `triangle_tags`
+/// and `allowed_verts`.
+pub(crate) fn sew_solids(mut solids: Vec<&mut Solid>) -> VmfResult<DispSew> {
+    let mut surfaces = Vec::new();
+    for (solid_index, solid) in solids.iter().enumerate() {
+        let solid: &Solid = solid;
+        for (side_index, side) in solid.sides.iter().enumerate() {
+            let Some(disp) = &side.dispinfo else {
+                continue;
+            };
+            let mesh = disp.build_mesh(side, solid)?;
+            let size = grid_size(disp.power);
+            let normals: Vec<Vector3> = disp.normals.vectors()?.into_iter().flatten().collect();
+            let distances: Vec<f64> = disp.distances.floats()?.into_iter().flatten().collect();
+            let bases = mesh
+                .vertices
+                .iter()
+                .zip(normals.iter().zip(&distances))
+                .map(|(&p, (&n, &d))| p - n * d)
+                .collect();
+            let mut tags: Vec<u32> = disp
+                .triangle_tags
+                .floats()?
+                .into_iter()
+                .flatten()
+                .map(|tag| tag as u32)
+                .collect();
+            if tags.len() != mesh.triangles.len() {
+                tags = vec![0; mesh.triangles.len()];
+            }
+
+            surfaces.push(Surface {
+                solid: solid_index,
+                side: side_index,
+                size,
+                bases,
+                flat: mesh.flat_vertices,
+                positions: mesh.vertices,
+                normals,
+                tags,
+                triangles: mesh.triangles,
+                allowed: vec![true; size * size],
+            });
+        }
+    }
+    let original: Vec<Vec<Vector3>> = surfaces.iter().map(|s| s.positions.clone()).collect();
+
+    // Edge vertices at the same undisplaced position move to their average.
+    let mut welds: HashMap<[i64; 3], Vec<(usize, usize)>> = HashMap::new();
+    for (s, surface) in surfaces.iter().enumerate() {
+        for edge in surface.edges() {
+            for i in edge {
+                let members = welds.entry(weld_key(surface.flat[i])).or_default();
+                if !members.contains(&(s, i)) {
+                    members.push((s, i));
+                }
+            }
+        }
+    }
+    for members in welds.values().filter(|members| members.len() > 1) {
+        let sum = members
+            .iter()
+            .fold(Vector3::ZERO, |acc, &(s, i)| acc + surfaces[s].positions[i]);
+        let average = sum / members.len() as f64;
+        for &(s, i) in members {
+            surfaces[s].positions[i] = average;
+        }
+    }
+
+    // Edge vertices lying between the vertices of a neighboring edge, where the
+    // neighbor is coarser or offset, move onto that edge and can't be used alone.
+    let mut junctions = Vec::new();
+    for (a, surface) in surfaces.iter().enumerate() {
+        for edge in surface.edges() {
+            for (b, neighbor) in surfaces.iter().enumerate() {
+                if a == b {
+                    continue;
+                }
+                for other in neighbor.edges() {
+                    let start = neighbor.flat[other[0]];
+                    let end = neighbor.flat[other[other.len() - 1]];
+                    let length = start.distance(end);
+                    if length < SEW_EPSILON {
+                        continue;
+                    }
+                    let direction = (end - start) / length;
+                    let step = length / (other.len() - 1) as f64;
+                    for &i in &edge {
+                        let offset = surface.flat[i] - start;
+                        let along = offset.dot(direction);
+                        let off_line = (offset - direction * along).length();
+                        if off_line > SEW_EPSILON
+                            || along < -SEW_EPSILON
+                            || along > length + SEW_EPSILON
+                        {
+                            continue;
+                        }
+                        let k = (along / step).clamp(0.0, (other.len() - 1) as f64);
+                        let below = k.floor() as usize;
+                        let t = k - below as f64;
+                        if t * step < SEW_EPSILON || (1.0 - t) * step < SEW_EPSILON {
+                            continue;
+                        }
+                        let target = neighbor.positions[other[below]]
+                            .lerp(neighbor.positions[other[below + 1]], t);
+                        junctions.push((a, i, target));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut result = DispSew::default();
+    for &(s, i, target) in &junctions {
+        surfaces[s].positions[i] = target;
+        if !surfaces[s].is_corner(i) {
+            let size = surfaces[s].size;
+            result.disallowed_vertices += surfaces[s].disallow(i / size, i % size);
+        }
+    }
+
+    let mut sewn = HashSet::new();
+    for (surface, original) in surfaces.iter().zip(&original) {
+        let size = surface.size;
+        let disp = solids[surface.solid].sides[surface.side]
+            .dispinfo
+            .as_mut()
+            .expect("sewn side has a displacement");
+
+        let mut moved = false;
+        for (i, (p, o)) in surface.positions.iter().zip(original).enumerate() {
+            if !p.approx_eq(*o, 1e-6) {
+                sewn.insert(weld_key(surface.flat[i]));
+                moved = true;
+            }
+        }
+        if moved {
+            result.displacements += 1;
+            let mut normals = vec![Vec::with_capacity(size); size];
+            let mut distances = vec![Vec::with_capacity(size); size];
+            for (i, &position) in surface.positions.iter().enumerate() {
+                let (normal, distance) = split(position - surface.bases[i], surface.normals[i]);
+                normals[i / size].push(normal);
+                distances[i / size].push(distance);
+            }
+            disp.normals = DispRows::from_vectors(&normals);
+            disp.distances = DispRows::from_floats(&distances);
+        }
+
+        let tags: Vec<f64> = surface
+            .triangles
+            .iter()
+            .zip(&surface.tags)
+            .map(|(&[a, b, c], &previous)| {
+                let [a, b, c] = [a, b, c].map(|i| surface.positions[i as usize]);
+                f64::from(triangle_tag((b - a).cross(c - a).normalize(), previous))
+            })
+            .collect();
+        let tags: Vec<Vec<f64>> = tags.chunks(2 * (size - 1)).map(<[f64]>::to_vec).collect();
+        disp.triangle_tags = DispRows::from_floats(&tags);
+
+        // Bits past the end of the grid are set, as Hammer writes them.
+        let words = (0..ALLOWED_VERTS_WORDS)
+            .map(|word| {
+                (0..32).fold(0u32, |bits, bit| {
+                    let index = word * 32 + bit;
+                    if surface.allowed.get(index).copied().unwrap_or(true) {
+                        bits | 1 << bit
+                    } else {
+                        bits
+                    }
+                }) as i32
+            })
+            .collect();
+        disp.allowed_verts.clear();
+        disp.allowed_verts
+            .insert(ALLOWED_VERTS_WORDS.to_string(), words);
+    }
+    result.sewn_vertices = sewn.len();
+    Ok(result)
+}
+
+/// Rounds an undisplaced position so vertices shared by neighbors get the same key.
+fn weld_key(flat: Vector3) -> [i64; 3] {
+    [flat.x, flat.y, flat.z].map(|c| (c * 100.0).round() as i64)
+}
@@ -0,0 +1,266 @@
+//! Texture axis helpers and the texture alignment tools of Hammer's Face Edit sheet.
+
+use super::{Polygon, Vector3};
+use crate::errors::VmfResult;
+use crate::prelude::Side;
+use crate::vmf::world::TextureAxis;
+
+/// The world-aligned texture axes for each dominant face direction, as used by
+/// Hammer and VBSP: `(face normal, U axis, V axis)`.
+const BASE_AXES: [(Vector3, Vector3, Vector3); 6] = [
+    (Vector3::Z, Vector3::X, Vector3::new(0.0, -1.0, 0.0)), // floor
+    (
+        Vector3::new(0.0, 0.0, -1.0),
+        Vector3::X,
+        Vector3::new(0.0, -1.0, 0.0),
+    ), // ceiling
+    (Vector3::X, Vector3::Y, Vector3::new(0.0, 0.0, -1.0)), // west wall
+    (
+        Vector3::new(-1.0, 0.0, 0.0),
+        Vector3::Y,
+        Vector3::new(0.0, 0.0, -1.0),
+    ), // east wall
+    (Vector3::Y, Vector3::X, Vector3::new(0.0, 0.0, -1.0)), // south wall
+    (
+        Vector3::new(0.0, -1.0, 0.0),
+        Vector3::X,
+        Vector3::new(0.0, 0.0, -1.0),
+    ), // north wall
+];
+
+/// Returns the world-aligned U and V axes for a face with the given normal.
+pub(crate) fn world_axes(normal: Vector3) -> [Vector3; 2] {
+    let mut best = 0;
+    let mut best_dot = 0.0;
+    for (i, (axis_normal, _, _)) in BASE_AXES.iter().enumerate() {
+        let dot = normal.dot(*axis_normal);
+        if dot > best_dot {
+            best_dot = dot;
+            best = i;
+        }
+    }
+    let (_, u, v) = BASE_AXES[best];
+    [u, v]
+}
+
+/// Which edge or point of a face a texture is justified to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Justify {
+    /// The left edge of the texture starts at the left edge of the face.
+    Left,
+    /// The right edge of the texture ends at the right edge of the face.
+    Right,
+    /// The top edge of the texture starts at the top edge of the face.
+    Top,
+    /// The bottom edge of the texture ends at the bottom edge of the face.
+    Bottom,
+    /// The center of the texture sits at the center of the face.
+    Center,
+}
+
+/// Returns the texture coordinate of `point` along `axis`, before the shift is added.
+fn project(axis: &TextureAxis, point: Vector3) -> f64 {
+    point.dot(axis.axis) / axis.scale
+}
+
+/// Returns the smallest and largest unshifted texture coordinates of a polygon along `axis`.
+fn extent(axis: &TextureAxis, polygon: &Polygon) -> (f64, f64) {
+    polygon
+        .vertices
+        .iter()
+        .map(|&v| project(axis, v))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), c| {
+            (min.min(c), max.max(c))
+        })
+}
+
+/// Wraps a shift into `[0, size)`, the way Hammer normalizes texture shifts.
+fn wrap_shift(shift: f64, size: u32) -> f64 {
+    if size == 0 {
+        return shift;
+    }
+    let wrapped = shift.rem_euclid(f64::from(size));
+    // Round away floating point noise so shifts stay readable.
+    let rounded = (wrapped * 1e6).round() / 1e6;
+    if rounded >= f64::from(size) {
+        0.0
+    } else {
+        rounded
+    }
+}
+
+impl Side {
+    /// Returns the texture coordinates of `point` on this side, in texels.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the `[u, v]` coordinates, or a `VmfError` if the axes are malformed.
+    pub fn texture_coords(&self, point: Vector3) -> VmfResult<[f64; 2]> {
+        let [u, v] = self.texture_axes()?;
+        Ok([project(&u, point) + u.shift, project(&v, point) + v.shift])
+    }
+
+    /// Aligns the texture to the world, like Hammer's "World" alignment.
+    ///
+    /// The axes are picked from the face's dominant direction; scale and shift are
+    /// kept and the rotation is reset.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if the plane or axes are malformed.
+    pub fn align_to_world(&mut self) -> VmfResult<()> {
+        let normal = self.to_plane()?.normal;
+        let [u_dir, v_dir] = world_axes(normal);
+        self.set_axis_directions(u_dir, v_dir)
+    }
+
+    /// Aligns the texture to the face, like Hammer's "Face" alignment.
+    ///
+    /// The world-aligned axes are projected onto the face plane, so the texture lies
+    /// flat on sloped faces without being skewed. Scale and shift are kept and the
+    /// rotation is reset.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if the plane or axes are malformed.
+    pub fn align_to_face(&mut self) -> VmfResult<()> {
+        let normal = self.to_plane()?.normal;
+        let [world_u, world_v] = world_axes(normal);
+        let u_dir = (world_u - normal * world_u.dot(normal)).normalize();
+        // V is perpendicular to U on the face, pointing the same way as the world V axis.
+        let mut v_dir = normal.cross(u_dir);
+        if v_dir.dot(world_v) < 0.0 {
+            v_dir = -v_dir;
+        }
+        self.set_axis_directions(u_dir, v_dir)
+    }
+
+    /// Scales and shifts the texture so it repeats a whole number of times across
+    /// the face, like Hammer's "Fit" button.
+    ///
+    /// # Arguments
+    ///
+    /// * `polygon` - The polygon of this side, for example from `Solid::faces`.
+    /// * `material_size` - The `[width, height]` of the material in texels.
+    /// * `repeat` - How many times the texture repeats along U and V.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if the axes are malformed.
+    pub fn fit_texture(
+        &mut self,
+        polygon: &Polygon,
+        material_size: [u32; 2],
+        repeat: [f64; 2],
+    ) -> VmfResult<()> {
+        let mut axes = self.texture_axes()?;
+        for (i, axis) in axes.iter_mut().enumerate() {
+            let (min, max) = extent(axis, polygon);
+            let texels = f64::from(material_size[i]) * repeat[i];
+            if texels == 0.0 || max <= min {
+                continue;
+            }
+            // The face spans `max - min` texels at the current scale; stretch it to `texels`.
+            axis.scale *= (max - min) / texels;
+            let (min, _) = extent(axis, polygon);
+            axis.shift = wrap_shift(-min, material_size[i]);
+        }
+        self.set_texture_axes(axes);
+        Ok(())
+    }
+
+    /// Shifts the texture so it lines up with an edge or the center of the face,
+    /// like Hammer's justify buttons. The scale is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `polygon` - The polygon of this side, for example from `Solid::faces`.
+    /// * `material_size` - The `[width, height]` of the material in texels.
+    /// * `justify` - Where to line the texture up.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if the axes are malformed.
+    pub fn justify_texture(
+        &mut self,
+        polygon: &Polygon,
+        material_size: [u32; 2],
+        justify: Justify,
+    ) -> VmfResult<()> {
+        let [mut u, mut v] = self.texture_axes()?;
+        let [width, height] = material_size;
+        let (u_min, u_max) = extent(&u, polygon);
+        let (v_min, v_max) = extent(&v, polygon);
+
+        match justify {
+            Justify::Left => u.shift = wrap_shift(-u_min, width),
+            Justify::Right => u.shift = wrap_shift(f64::from(width) - u_max, width),
+            Justify::Top => v.shift = wrap_shift(-v_min, height),
+            Justify::Bottom => v.shift = wrap_shift(f64::from(height) - v_max, height),
+            Justify::Center => {
+                u.shift = wrap_shift(f64::from(width) / 2.0 - (u_min + u_max) / 2.0, width);
+                v.shift = wrap_shift(f64::from(height) / 2.0 - (v_min + v_max) / 2.0, height);
+            }
+        }
+        self.set_texture_axes([u, v]);
+        Ok(())
+    }
+
+    /// Sets the texture scale, in world units per texel, keeping the shift.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - The new `[u, v]` scale. Negative values flip the texture.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if the axes are malformed.
+    pub fn set_texture_scale(&mut self, scale: [f64; 2]) -> VmfResult<()> {
+        let [mut u, mut v] = self.texture_axes()?;
+        u.scale = scale[0];
+        v.scale = scale[1];
+        self.set_texture_axes([u, v]);
+        Ok(())
+    }
+
+    /// Sets the texture shift in texels, wrapped into the size of the material.
+    ///
+    /// # Arguments
+    ///
+    /// * `shift` - The new `[u, v]` shift in texels.
+    /// * `material_size` - The `[width, height]` of the material in texels.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if the axes are malformed.
+    pub fn set_texture_shift(&mut self, shift: [f64; 2], material_size: [u32; 2]) -> VmfResult<()> {
+        let [mut u, mut v] = self.texture_axes()?;
+        u.shift = wrap_shift(shift[0], material_size[0]);
+        v.shift = wrap_shift(shift[1], material_size[1]);
+        self.set_texture_axes([u, v]);
+        Ok(())
+    }
+
+    /// Copies the texture projection of `source` onto this side.
+    ///
+    /// For coplanar faces this makes the texture continue seamlessly from one face to the other.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The side to copy the axes, shift, scale and rotation from.
+    pub fn copy_alignment_from(&mut self, source: &Side) {
+        self.u_axis = source.u_axis.clone();
+        self.v_axis = source.v_axis.clone();
+        self.rotation = source.rotation;
+    }
+
+    /// Replaces the axis directions, keeping scale and shift, and resets the rotation.
+    fn set_axis_directions(&mut self, u_dir: Vector3, v_dir: Vector3) -> VmfResult<()> {
+        let [mut u, mut v] = self.texture_axes()?;
+        u.axis = u_dir;
+        v.axis = v_dir;
+        self.set_texture_axes([u, v]);
+        self.rotation = Some(0.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,426 @@
+//! Affine transforms and their application to brushes and entities.
+
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+
+use super::texture::world_axes;
+use super::{Matrix3, Plane, Vector3};
+use crate::errors::VmfResult;
+use crate::prelude::{Entity, Side, Solid};
+use crate::utils::format_float;
+use crate::vmf::world::{DispInfo, DispRows, TextureAxis};
+
+/// An affine transform: a linear part followed by a translation.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Transform {
+    /// The linear part of the transform (rotation, scale, mirror).
+    pub matrix: Matrix3,
+    /// The translation applied after the linear part.
+    pub translation: Vector3,
+}
+
+impl Transform {
+    /// The identity transform.
+    pub const IDENTITY: Transform = Transform {
+        matrix: Matrix3::IDENTITY,
+        translation: Vector3::ZERO,
+    };
+
+    /// Creates a transform from a linear part and a translation.
+    pub fn new(matrix: Matrix3, translation: Vector3) -> Self {
+        Self {
+            matrix,
+            translation,
+        }
+    }
+
+    /// Creates the transform that places local geometry at `origin` with the given `angles`,
+    /// the same way the engine positions an entity or a `func_instance`.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The world position of the local origin.
+    /// * `angles` - The rotation as `(pitch, yaw, roll)` in degrees.
+    pub fn from_origin_angles(origin: Vector3, angles: Vector3) -> Self {
+        Self::new(Matrix3::from_angles(angles), origin)
+    }
+
+    /// Creates a translation by `offset`.
+    pub fn translation(offset: Vector3) -> Self {
+        Self::new(Matrix3::IDENTITY, offset)
+    }
+
+    /// Creates a rotation around the world origin from Source engine angles.
+    ///
+    /// # Arguments
+    ///
+    /// * `angles` - The rotation as `(pitch, yaw, roll)` in degrees.
+    pub fn rotation(angles: Vector3) -> Self {
+        Self::new(Matrix3::from_angles(angles), Vector3::ZERO)
+    }
+
+    /// Creates a rotation of `degrees` around `axis`, passing through `pivot`.
+    ///
+    /// # Arguments
+    ///
+    /// * `axis` - The rotation axis (right-handed).
+    /// * `degrees` - The rotation angle in degrees.
+    /// * `pivot` - A point on the rotation axis.
+    pub fn rotation_around(axis: Vector3, degrees: f64, pivot: Vector3) -> Self {
+        Self::new(Matrix3::from_axis_angle(axis, degrees), Vector3::ZERO).around(pivot)
+    }
+
+    /// Creates a scale by `factors` along each axis, keeping `pivot` fixed.
+    pub fn scale(factors: Vector3, pivot: Vector3) -> Self {
+        Self::new(Matrix3::from_scale(factors), Vector3::ZERO).around(pivot)
+    }
+
+    /// Creates a reflection across `plane`.
+    pub fn mirror(plane: &Plane) -> Self {
+        let n = plane.normal;
+        // Householder reflection: I - 2 n n^T, moved so the plane stays in place.
+        let matrix = Matrix3::from_columns(
+            Vector3::X - n * (2.0 * n.x),
+            Vector3::Y - n * (2.0 * n.y),
+            Vector3::Z - n * (2.0 * n.z),
+        );
+        Self::new(matrix, n * (2.0 * plane.dist))
+    }
+
+    /// Returns the same transform, applied relative to `pivot` instead of the world origin.
+    pub fn around(&self, pivot: Vector3) -> Transform {
+        Transform::translation(-pivot)
+            .then(self)
+            .then(&Transform::translation(pivot))
+    }
+
+    /// Transforms a point.
+    pub fn transform_point(&self, point: Vector3) -> Vector3 {
+        self.matrix * point + self.translation
+    }
+
+    /// Transforms a direction, ignoring the translation.
+    pub fn transform_vector(&self, vector: Vector3) -> Vector3 {
+        self.matrix * vector
+    }
+
+    /// Returns `true` if the transform flips handedness (an odd number of mirrors).
+    pub fn is_mirroring(&self) -> bool {
+        self.matrix.determinant() < 0.0
+    }
+
+    /// Returns the transform that applies `self` first and then `next`.
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform::new(
+            next.matrix * self.matrix,
+            next.matrix * self.translation + next.translation,
+        )
+    }
+
+    /// Returns the inverse transform, or `None` if the linear part is singular.
+    pub fn inverse(&self) -> Option<Transform> {
+        let inv = self.matrix.inverse()?;
+        Some(Transform::new(inv, -(inv * self.translation)))
+    }
+}
+
+/// How texture axes react when a side is transformed, matching Hammer's toolbar toggles.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TextureLock {
+    /// Textures stay fixed in world space and slide across moved faces.
+    /// World-aligned faces stay world-aligned and face-aligned faces stay
+    /// aligned to their face (Hammer with texture lock off).
+    Off,
+    /// Textures follow moves, rotations and mirrors but keep their scale when
+    /// the geometry is stretched (Hammer's texture lock).
+    #[default]
+    On,
+    /// Textures follow every change, including scaling (Hammer's texture lock
+    /// together with texture scale lock).
+    WithScale,
+}
+
+impl Solid {
+    /// Transforms every side of the solid.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The transform to apply.
+    /// * `lock` - How the texture axes follow the geometry.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if a side has malformed data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut solid = Solid::default();
+    /// let turn = Transform::rotation_around(Vector3::Z, 90.0, Vector3::new(64.0, 64.0, 0.0));
+    /// solid.apply_transform(&turn, TextureLock::On)?;
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn apply_transform(&mut self, transform: &Transform, lock: TextureLock) -> VmfResult<()> {
+        for side in &mut self.sides {
+            side.apply_transform(transform, lock)?;
+        }
+        Ok(())
+    }
+}
+
+impl Side {
+    /// Transforms the plane points, texture axes and displacement data of the side.
+    ///
+    /// When the transform mirrors, the plane points are rewound so the plane keeps facing out of the solid.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The transform to apply.
+    /// * `lock` - How the texture axes follow the geometry.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if the plane, axes or displacement data are malformed.
+    pub fn apply_transform(&mut self, transform: &Transform, lock: TextureLock) -> VmfResult<()> {
+        let old_points = self.plane_points()?;
+        let old_normal = self.to_plane().map(|p| p.normal).ok();
+
+        let mut points = old_points.map(|p| transform.transform_point(p));
+        if transform.is_mirroring() {
+            // Mirroring reverses the winding, which would flip the plane inwards.
+            points.swap(0, 2);
+        }
+        self.set_plane_points(points);
+
+        let [u, v] = self.texture_axes()?;
+        let axes = match lock {
+            TextureLock::WithScale => [
+                lock_texture_axis(&u, transform),
+                lock_texture_axis(&v, transform),
+            ],
+            TextureLock::On => [
+                lock_texture_position(&u, transform, old_points[0]),
+                lock_texture_position(&v, transform, old_points[0]),
+            ],
+            TextureLock::Off => {
+                let new_normal = self.to_plane().map(|p| p.normal).ok();
+                unlocked_axes(&u, &v, transform, old_normal, new_normal)
+            }
+        };
+        self.set_texture_axes(axes);
+
+        if let Some(dispinfo) = &mut self.dispinfo {
+            dispinfo.apply_transform(transform)?;
+        }
+        Ok(())
+    }
+}
+
+/// Moves a texture axis along with the geometry, so texture coordinates stay
+/// attached to the same points on the transformed face.
+fn lock_texture_axis(axis: &TextureAxis, transform: &Transform) -> TextureAxis {
+    // u = dot(p, a) / s + shift. For p' = M p + t this becomes
+    // u = dot(p' - t, M^-T a) / s + shift.
+    let Some(inverse) = transform.matrix.inverse() else {
+        return axis.clone();
+    };
+    let direction = inverse.transpose() * axis.axis;
+    let length = direction.length();
+    if length == 0.0 {
+        return axis.clone();
+    }
+
+    TextureAxis {
+        axis: direction / length,
+        shift: axis.shift - transform.translation.dot(direction) / axis.scale,
+        scale: axis.scale / length,
+    }
+}
+
+/// Turns a texture axis with the geometry while keeping its scale, so the
+/// texture stays pinned at `anchor` but isn't stretched.
+fn lock_texture_position(
+    axis: &TextureAxis,
+    transform: &Transform,
+    anchor: Vector3,
+) -> TextureAxis {
+    let locked = lock_texture_axis(axis, transform);
+    let moved = transform.transform_point(anchor);
+    // Keep the texture coordinate at the anchor unchanged with the original scale.
+    let shift = axis.shift + (anchor.dot(axis.axis) - moved.dot(locked.axis)) / axis.scale;
+
+    TextureAxis {
+        axis: locked.axis,
+        shift,
+        scale: axis.scale,
+    }
+}
+
+/// Computes the texture axes of a transformed side when texture lock is off.
+fn unlocked_axes(
+    u: &TextureAxis,
+    v: &TextureAxis,
+    transform: &Transform,
+    old_normal: Option<Vector3>,
+    new_normal: Option<Vector3>,
+) -> [TextureAxis; 2] {
+    let (Some(old_normal), Some(new_normal)) = (old_normal, new_normal) else {
+        return [u.clone(), v.clone()];
+    };
+    let with_axis = |axis: &TextureAxis, direction: Vector3| TextureAxis {
+        axis: direction,
+        ..axis.clone()
+    };
+
+    let [world_u, world_v] = world_axes(old_normal);
+    if u.axis.approx_eq(world_u, 1e-3) && v.axis.approx_eq(world_v, 1e-3) {
+        let [new_u, new_v] = world_axes(new_normal);
+        return [with_axis(u, new_u), with_axis(v, new_v)];
+    }
+
+    let face_aligned = u.axis.dot(old_normal).abs() < 1e-3 && v.axis.dot(old_normal).abs() < 1e-3;
+    if face_aligned {
+        let turn =
+            |axis: &TextureAxis| with_axis(axis, transform.transform_vector(axis.axis).normalize());
+        return [turn(u), turn(v)];
+    }
+
+    [u.clone(), v.clone()]
+}
+
+impl DispInfo {
+    /// Transforms the displacement's start position and per-vertex vector data.
+    ///
+    /// A mirroring transform reverses the winding of the owning face, which swaps
+    /// the displacement's row and column directions, so the per-vertex grids are
+    /// transposed to keep every vertex in place.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The transform to apply. It should be the same one applied to the owning side.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if the displacement data is malformed.
+    pub fn apply_transform(&mut self, transform: &Transform) -> VmfResult<()> {
+        let start = transform.transform_point(self.start_position.parse()?);
+        self.start_position = format!("[{}]", start);
+
+        // Normals and distances describe a single offset vector, so a scaled
+        // transform changes the distance as well as the direction.
+        let mut normals = self.normals.vectors()?;
+        let mut distances = self.distances.floats()?;
+        for (row_index, row) in normals.iter_mut().enumerate() {
+            for (col_index, normal) in row.iter_mut().enumerate() {
+                let transformed = transform.transform_vector(*normal);
+                let length = transformed.length();
+                *normal = transformed.normalize();
+                if let Some(distance) = distances
+                    .get_mut(row_index)
+                    .and_then(|r| r.get_mut(col_index))
+                {
+                    *distance *= length;
+                }
+            }
+        }
+        self.normals = DispRows::from_vectors(&normals);
+        self.distances = DispRows::from_floats(&distances);
+
+        let mut offsets = self.offsets.vectors()?;
+        offsets
+            .iter_mut()
+            .flatten()
+            .for_each(|o| *o = transform.transform_vector(*o));
+        self.offsets = DispRows::from_vectors(&offsets);
+
+        let mut offset_normals = self.offset_normals.vectors()?;
+        offset_normals
+            .iter_mut()
+            .flatten()
+            .for_each(|n| *n = transform.transform_vector(*n).normalize());
+        self.offset_normals = DispRows::from_vectors(&offset_normals);
+
+        if transform.is_mirroring() {
+            self.normals = DispRows::from_vectors(&transpose(normals));
+            self.distances = DispRows::from_floats(&transpose(distances));
+            self.offsets = DispRows::from_vectors(&transpose(offsets));
+            self.offset_normals = DispRows::from_vectors(&transpose(offset_normals));
+            self.alphas = DispRows::from_floats(&transpose(self.alphas.floats()?));
+        }
+
+        Ok(())
+    }
+}
+
+/// Swaps the rows and columns of a square grid of displacement values.
+fn transpose<T: Copy>(rows: Vec<Vec<T>>) -> Vec<Vec<T>> {
+    let columns = rows.first().map_or(0, Vec::len);
+    if rows.iter().any(|row| row.len() != columns) {
+        return rows;
+    }
+    (0..columns)
+        .map(|c| rows.iter().map(|row| row[c]).collect())
+        .collect()
+}
+
+impl Entity {
+    /// Transforms the entity's `origin`, `angles` and brush solids.
+    ///
+    /// A yaw-only `angle` key is kept as such when the result has no pitch or roll.
+    /// Under a mirroring transform the entity keeps a proper rotation whose
+    /// forward and up directions follow the mirror.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The transform to apply.
+    /// * `lock` - How the texture axes of brush solids follow the geometry.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if a solid has malformed data.
+    pub fn apply_transform(&mut self, transform: &Transform, lock: TextureLock) -> VmfResult<()> {
+        if let Some(origin) = self.origin() {
+            self.set_origin(transform.transform_point(origin));
+        }
+
+        let angles = self.angles().or_else(|| {
+            // Some point entities only store a yaw in the `angle` key,
+            // with -1 and -2 meaning straight up and straight down.
+            self.get("angle")
+                .and_then(|yaw| yaw.trim().parse::<f64>().ok())
+                .map(|yaw| match yaw {
+                    -1.0 => Vector3::new(-90.0, 0.0, 0.0),
+                    -2.0 => Vector3::new(90.0, 0.0, 0.0),
+                    _ => Vector3::new(0.0, yaw, 0.0),
+                })
+        });
+        if let Some(angles) = angles {
+            let rotated = transform.matrix * Matrix3::from_angles(angles);
+            let forward = rotated.column(0).normalize();
+            let up = rotated.column(2);
+            let up = (up - forward * up.dot(forward)).normalize();
+            // Rebuild the left axis so the result stays a proper rotation after a mirror.
+            let orthonormal = Matrix3::from_columns(forward, up.cross(forward), up);
+            let new_angles = orthonormal.to_angles();
+            if self.key_values.contains_key("angles")
+                || new_angles.x.abs() > 1e-6
+                || new_angles.z.abs() > 1e-6
+            {
+                self.swap_remove_key("angle");
+                self.set_angles(new_angles);
+            } else {
+                self.set("angle".to_string(), format_float(new_angles.y));
+            }
+        }
+
+        if let Some(solids) = &mut self.solids {
+            for solid in solids {
+                solid.apply_transform(transform, lock)?;
+            }
+        }
+        Ok(())
+    }
+}
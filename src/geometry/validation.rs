@@ -0,0 +1,203 @@
+//! Validity checks for brushes, similar to Hammer's "Check for Problems".
+
+use std::fmt;
+
+use super::{ON_EPSILON, Plane, Polygon, Vector3, polygons_from_planes};
+use crate::prelude::Solid;
+
+/// Two plane normals closer than this per component are considered the same (VBSP's `NORMAL_EPSILON`).
+const NORMAL_EPSILON: f64 = 0.00001;
+/// Two plane distances closer than this are considered the same (VBSP's `DIST_EPSILON`).
+const DIST_EPSILON: f64 = 0.01;
+/// The distance below which two face vertices are considered the same point.
+const VERTEX_EPSILON: f64 = 0.01;
+
+/// The reason a solid is invalid.
+///
+/// A brush is the intersection of the half-spaces behind its side planes, so
+/// it is convex by construction; planes that don't describe a proper convex
+/// volume show up as an open or empty brush instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolidProblemKind {
+    /// The solid has fewer than four sides and can't enclose a volume.
+    TooFewSides,
+    /// A `plane` string is malformed or its three points are collinear.
+    InvalidPlane,
+    /// Two sides share the same plane.
+    DuplicatePlane,
+    /// Two sides lie on the same plane facing opposite ways, giving the brush no thickness.
+    CoplanarPlanes,
+    /// A side is clipped away by the other sides and contributes no area.
+    ZeroAreaFace,
+    /// The faces don't form a closed hull, for example because a side is missing or faces inwards.
+    Open,
+    /// The side planes don't enclose any volume.
+    Empty,
+}
+
+impl fmt::Display for SolidProblemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            SolidProblemKind::TooFewSides => "fewer than four sides",
+            SolidProblemKind::InvalidPlane => "invalid plane",
+            SolidProblemKind::DuplicatePlane => "duplicate plane",
+            SolidProblemKind::CoplanarPlanes => "coplanar opposing planes",
+            SolidProblemKind::ZeroAreaFace => "zero-area face",
+            SolidProblemKind::Open => "open or non-convex hull",
+            SolidProblemKind::Empty => "planes enclose no volume",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// A problem found on a solid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolidProblem {
+    /// The ID of the invalid solid.
+    pub solid_id: u64,
+    /// The ID of the entity owning the solid, or `None` for world brushes.
+    pub entity_id: Option<u64>,
+    /// What is wrong with the solid.
+    pub kind: SolidProblemKind,
+    /// The IDs of the sides involved.
+    pub side_ids: Vec<u32>,
+}
+
+impl fmt::Display for SolidProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "solid {}", self.solid_id)?;
+        if let Some(entity_id) = self.entity_id {
+            write!(f, " (entity {})", entity_id)?;
+        }
+        write!(f, ": {}", self.kind)?;
+        if !self.side_ids.is_empty() {
+            let ids: Vec<String> = self.side_ids.iter().map(u32::to_string).collect();
+            write!(f, " (sides {})", ids.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Solid {
+    /// Checks the solid for problems that make VBSP reject or mangle it.
+    ///
+    /// # Returns
+    ///
+    /// A vector of the problems found, empty if the solid is valid.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// for problem in vmf.world.solids[0].validate() {
+    ///     println!("{}", problem);
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn validate(&self) -> Vec<SolidProblem> {
+        let mut problems = Vec::new();
+        let mut report = |kind: SolidProblemKind, side_ids: Vec<u32>| {
+            problems.push(SolidProblem {
+                solid_id: self.id,
+                entity_id: None,
+                kind,
+                side_ids,
+            })
+        };
+
+        if self.sides.len() < 4 {
+            report(
+                SolidProblemKind::TooFewSides,
+                self.sides.iter().map(|s| s.id).collect(),
+            );
+            return problems;
+        }
+
+        let mut planes = Vec::with_capacity(self.sides.len());
+        for side in &self.sides {
+            match side.to_plane() {
+                Ok(plane) => planes.push(plane),
+                Err(_) => report(SolidProblemKind::InvalidPlane, vec![side.id]),
+            }
+        }
+        // Without every plane, the remaining checks would only report noise.
+        if planes.len() != self.sides.len() {
+            return problems;
+        }
+
+        for i in 0..planes.len() {
+            for j in (i + 1)..planes.len() {
+                let ids = vec![self.sides[i].id, self.sides[j].id];
+                if planes[i].approx_eq(&planes[j], NORMAL_EPSILON, DIST_EPSILON) {
+                    report(SolidProblemKind::DuplicatePlane, ids);
+                } else if planes[i].approx_eq(&planes[j].flipped(), NORMAL_EPSILON, DIST_EPSILON) {
+                    report(SolidProblemKind::CoplanarPlanes, ids);
+                }
+            }
+        }
+
+        let polygons = polygons_from_planes(&planes);
+        if polygons.iter().all(Option::is_none) {
+            report(SolidProblemKind::Empty, Vec::new());
+            return problems;
+        }
+
+        let zero_area: Vec<u32> = polygons
+            .iter()
+            .zip(&self.sides)
+            .filter(|(polygon, _)| polygon.is_none())
+            .map(|(_, side)| side.id)
+            .collect();
+        if !zero_area.is_empty() {
+            report(SolidProblemKind::ZeroAreaFace, zero_area);
+        }
+
+        let open = open_faces(&planes, &polygons);
+        if !open.is_empty() {
+            report(
+                SolidProblemKind::Open,
+                open.into_iter().map(|i| self.sides[i].id).collect(),
+            );
+        }
+
+        problems
+    }
+
+    /// Returns `true` if `validate` finds no problems.
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_empty()
+    }
+}
+
+/// Returns the indices of faces that have an edge no other face shares in the
+/// opposite direction, or that stick out of another side's plane.
+fn open_faces(planes: &[Plane], polygons: &[Option<Polygon>]) -> Vec<usize> {
+    let same = |a: Vector3, b: Vector3| a.approx_eq(b, VERTEX_EPSILON);
+
+    let mut open = Vec::new();
+    for (i, polygon) in polygons.iter().enumerate() {
+        let Some(polygon) = polygon else { continue };
+
+        let outside = polygon.vertices.iter().any(|&v| {
+            planes
+                .iter()
+                .enumerate()
+                .any(|(j, plane)| i != j && plane.distance_to(v) > ON_EPSILON)
+        });
+        let unmatched = polygon.edges().any(|(a, b)| {
+            !polygons.iter().enumerate().any(|(j, other)| {
+                i != j
+                    && other
+                        .as_ref()
+                        .is_some_and(|o| o.edges().any(|(c, d)| same(a, d) && same(b, c)))
+            })
+        });
+
+        if outside || unmatched {
+            open.push(i);
+        }
+    }
+    open
+}
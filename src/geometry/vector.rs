@@ -0,0 +1,270 @@
+//! A three-component vector used for points, directions and angles in VMF data.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+#[cfg(feature = "serialization")]
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{VmfError, VmfResult};
+use crate::utils::format_float;
+
+/// A vector in 3D space, using the same axis conventions as Hammer (Z up).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct Vector3 {
+    /// The X component.
+    pub x: f64,
+    /// The Y component.
+    pub y: f64,
+    /// The Z component.
+    pub z: f64,
+}
+
+impl Vector3 {
+    /// The zero vector.
+    pub const ZERO: Vector3 = Vector3::new(0.0, 0.0, 0.0);
+    /// The unit vector along the X axis.
+    pub const X: Vector3 = Vector3::new(1.0, 0.0, 0.0);
+    /// The unit vector along the Y axis.
+    pub const Y: Vector3 = Vector3::new(0.0, 1.0, 0.0);
+    /// The unit vector along the Z axis.
+    pub const Z: Vector3 = Vector3::new(0.0, 0.0, 1.0);
+
+    /// Creates a new vector from its components.
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Creates a vector with all three components set to `value`.
+    pub const fn splat(value: f64) -> Self {
+        Self::new(value, value, value)
+    }
+
+    /// Returns the dot product of `self` and `other`.
+    pub fn dot(self, other: Vector3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Returns the cross product of `self` and `other`.
+    pub fn cross(self, other: Vector3) -> Vector3 {
+        Vector3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// Returns the length of the vector.
+    pub fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns the squared length of the vector.
+    pub fn length_squared(self) -> f64 {
+        self.dot(self)
+    }
+
+    /// Returns a unit-length copy of the vector, or the zero vector if its length is zero.
+    pub fn normalize(self) -> Vector3 {
+        let len = self.length();
+        if len > 0.0 { self / len } else { Vector3::ZERO }
+    }
+
+    /// Returns the component-wise minimum of `self` and `other`.
+    pub fn min(self, other: Vector3) -> Vector3 {
+        Vector3::new(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.z.min(other.z),
+        )
+    }
+
+    /// Returns the component-wise maximum of `self` and `other`.
+    pub fn max(self, other: Vector3) -> Vector3 {
+        Vector3::new(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.z.max(other.z),
+        )
+    }
+
+    /// Returns the component-wise absolute value of the vector.
+    pub fn abs(self) -> Vector3 {
+        Vector3::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+
+    /// Linearly interpolates between `self` and `other` by `t`.
+    pub fn lerp(self, other: Vector3, t: f64) -> Vector3 {
+        self + (other - self) * t
+    }
+
+    /// Returns the distance between `self` and `other`.
+    pub fn distance(self, other: Vector3) -> f64 {
+        (other - self).length()
+    }
+
+    /// Returns `true` if every component of `self` is within `epsilon` of `other`.
+    pub fn approx_eq(self, other: Vector3, epsilon: f64) -> bool {
+        (self.x - other.x).abs() <= epsilon
+            && (self.y - other.y).abs() <= epsilon
+            && (self.z - other.z).abs() <= epsilon
+    }
+
+    /// Returns the components as an array.
+    pub fn to_array(self) -> [f64; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    /// Parses every whitespace-separated triple of numbers in `s` into a vector.
+    ///
+    /// Brackets and parentheses are ignored, so this accepts the formats used by
+    /// plane strings (`"(0 0 0) (1 0 0) (1 1 0)"`) as well as displacement rows
+    /// (`"0 0 1 0 0 1"`).
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to parse.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the parsed vectors, or a `VmfError` if a number is invalid
+    /// or the number count isn't a multiple of three.
+    pub fn parse_list(s: &str) -> VmfResult<Vec<Vector3>> {
+        let numbers = parse_numbers(s)?;
+        if numbers.len() % 3 != 0 {
+            return Err(VmfError::InvalidFormat(format!(
+                "Expected a multiple of 3 numbers, got {} in '{}'",
+                numbers.len(),
+                s
+            )));
+        }
+
+        Ok(numbers
+            .chunks_exact(3)
+            .map(|c| Vector3::new(c[0], c[1], c[2]))
+            .collect())
+    }
+}
+
+/// Parses all numbers in a string, ignoring brackets and parentheses.
+pub(crate) fn parse_numbers(s: &str) -> VmfResult<Vec<f64>> {
+    s.split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | '{' | '}'))
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse::<f64>()
+                .map_err(|e| VmfError::from((e, part.to_string())))
+        })
+        .collect()
+}
+
+impl FromStr for Vector3 {
+    type Err = VmfError;
+
+    /// Parses a vector from `"x y z"`, `"[x y z]"` or `"(x y z)"`.
+    fn from_str(s: &str) -> VmfResult<Self> {
+        match parse_numbers(s)?.as_slice() {
+            [x, y, z] => Ok(Vector3::new(*x, *y, *z)),
+            _ => Err(VmfError::InvalidFormat(format!(
+                "Expected 3 numbers for a vector, got '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Vector3 {
+    /// Formats the vector as `"x y z"`, the way Hammer writes origins.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            format_float(self.x),
+            format_float(self.y),
+            format_float(self.z)
+        )
+    }
+}
+
+impl From<[f64; 3]> for Vector3 {
+    fn from(value: [f64; 3]) -> Self {
+        Vector3::new(value[0], value[1], value[2])
+    }
+}
+
+impl Index<usize> for Vector3 {
+    type Output = f64;
+
+    fn index(&self, index: usize) -> &f64 {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("Vector3 index out of range: {}", index),
+        }
+    }
+}
+
+impl IndexMut<usize> for Vector3 {
+    fn index_mut(&mut self, index: usize) -> &mut f64 {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("Vector3 index out of range: {}", index),
+        }
+    }
+}
+
+impl Add for Vector3 {
+    type Output = Vector3;
+
+    fn add(self, rhs: Vector3) -> Vector3 {
+        Vector3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl AddAssign for Vector3 {
+    fn add_assign(&mut self, rhs: Vector3) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Vector3 {
+    type Output = Vector3;
+
+    fn sub(self, rhs: Vector3) -> Vector3 {
+        Vector3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl SubAssign for Vector3 {
+    fn sub_assign(&mut self, rhs: Vector3) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<f64> for Vector3 {
+    type Output = Vector3;
+
+    fn mul(self, rhs: f64) -> Vector3 {
+        Vector3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Div<f64> for Vector3 {
+    type Output = Vector3;
+
+    fn div(self, rhs: f64) -> Vector3 {
+        Vector3::new(self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+
+impl Neg for Vector3 {
+    type Output = Vector3;
+
+    fn neg(self) -> Vector3 {
+        Vector3::new(-self.x, -self.y, -self.z)
+    }
+}
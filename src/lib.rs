@@ -23,6 +23,8 @@ use indexmap::IndexMap;
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
 
+pub mod export;
+pub mod geometry;
 pub mod parser;
 pub(crate) mod utils;
 pub mod vmf;
@@ -14,13 +14,26 @@
 //! ```
 
 pub use crate::VmfFile;
+pub use crate::vmf_file::{
+    CordonOptions, IdAllocator, InstanceGraph, InstanceResolver, IoGraph, LeakOptions,
+    LightmapRule, SpatialIndex, SpatialKey,
+};
 
 pub use crate::errors::{VmfError, VmfResult};
 
+pub use crate::export::{ExportOptions, GltfFormat};
+
+pub use crate::geometry::{
+    Aabb, ArchOptions, BoundsOptions, ClipMode, Face, Justify, Matrix3, Mesh, OffGrid, Plane,
+    Polygon, SculptBrush, SculptOp, SolidProblem, SolidProblemKind, TextureLock, TorusOptions,
+    Transform, Vector3,
+};
+
 pub use crate::vmf::{
     common::Editor,
     entities::{Entities, Entity},
     metadata::{VersionInfo, ViewSettings, VisGroup, VisGroups},
+    overlays::Overlay,
     regions::{Camera, Cameras, Cordon, Cordons},
     world::{Side, Solid, World},
 };
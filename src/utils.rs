@@ -2,6 +2,7 @@
 
 use crate::{VmfError, VmfResult};
 use indexmap::IndexMap;
+use std::fmt::Write;
 
 /// A trait for converting a boolean value to a "0" or "1" string.
 pub trait To01String {
@@ -23,6 +24,21 @@ impl To01String for bool {
     }
 }
 
+/// Formats a float the way Hammer writes numbers: rounded to six decimal places,
+/// without trailing zeros and without a negative sign on zero.
+pub(crate) fn format_float(value: f64) -> String {
+    let rounded = (value * 1e6).round() / 1e6;
+    if rounded == 0.0 {
+        return "0".to_string();
+    }
+
+    let formatted = format!("{:.6}", rounded);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
 /// Gets a borrowed string slice (`&str`) for a key. Returns error if key not found.
 /// Use this when you only need to read/compare the value without taking ownership.
 #[inline(always)]
@@ -65,6 +81,60 @@ pub(crate) fn take_key_or_default(
     map.swap_remove(key).unwrap_or(default)
 }
 
+/// Matches `text` against a wildcard `pattern` case-insensitively, where `*` matches
+/// any run of characters and `?` any single character.
+pub(crate) fn matches_wildcard(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+
+    // Greedy matching that backtracks to the last `*` on a mismatch.
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Splits a connection value into its fields, returning the separator that was used.
+///
+/// Newer VMFs separate fields with `\x1B`, older ones with commas.
+pub(crate) fn split_connection(value: &str) -> (char, Vec<String>) {
+    let separator = if value.contains('\x1B') { '\x1B' } else { ',' };
+    (
+        separator,
+        value.split(separator).map(str::to_string).collect(),
+    )
+}
+
+/// Escapes a string for use inside a JSON string literal.
+pub(crate) fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,4 +261,23 @@ mod tests {
     fn to_01_string_false() {
         assert_eq!(false.to_01_string(), "0");
     }
+
+    #[test]
+    fn format_float_trims_and_rounds() {
+        assert_eq!(format_float(8.0), "8");
+        assert_eq!(format_float(-351.766), "-351.766");
+        assert_eq!(format_float(0.25), "0.25");
+        assert_eq!(format_float(239.99999999997), "240");
+        assert_eq!(format_float(-0.0000001), "0");
+    }
+
+    #[test]
+    fn matches_wildcard_patterns() {
+        assert!(matches_wildcard("TOOLS/*", "tools/toolsnodraw"));
+        assert!(matches_wildcard("*/concrete?a", "metal/CONCRETE1a"));
+        assert!(matches_wildcard("*wall*", "dev/dev_measurewall01a"));
+        assert!(matches_wildcard("*", ""));
+        assert!(!matches_wildcard("tools/*", "dev/tools"));
+        assert!(!matches_wildcard("dev/?", "dev/ab"));
+    }
 }
@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 
 use super::common::Editor;
 use super::world::Solid;
+use crate::geometry::Vector3;
 use std::mem;
 
 /// Represents an entity in a VMF file.
@@ -169,6 +170,44 @@ impl Entity {
         self.key_values.get("model").map(|s| s.as_str())
     }
 
+    /// Returns the parsed `origin` of the entity.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the origin, if the key exists and is a valid vector.
+    pub fn origin(&self) -> Option<Vector3> {
+        self.key_values.get("origin").and_then(|s| s.parse().ok())
+    }
+
+    /// Sets the `origin` of the entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The new origin.
+    pub fn set_origin(&mut self, origin: Vector3) {
+        self.key_values
+            .insert("origin".to_string(), origin.to_string());
+    }
+
+    /// Returns the parsed `angles` of the entity as `(pitch, yaw, roll)` in degrees.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the angles, if the key exists and is a valid vector.
+    pub fn angles(&self) -> Option<Vector3> {
+        self.key_values.get("angles").and_then(|s| s.parse().ok())
+    }
+
+    /// Sets the `angles` of the entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `angles` - The new angles as `(pitch, yaw, roll)` in degrees.
+    pub fn set_angles(&mut self, angles: Vector3) {
+        self.key_values
+            .insert("angles".to_string(), angles.to_string());
+    }
+
     /// Adds an output connection to the entity.
     ///
     /// # Arguments
@@ -1,9 +1,10 @@
 //! This module contains the core data structures for representing VMF files,
 //! including the `World`, `Entity`, `Solid`, and other related types.
-//! It also re-exports the submodules `common`, `entities`, `metadata`, `regions`, and `world`.
+//! It also re-exports the submodules `common`, `entities`, `metadata`, `overlays`, `regions`, and `world`.
 
 pub mod common;
 pub mod entities;
 pub mod metadata;
+pub mod overlays;
 pub mod regions;
 pub mod world;
@@ -0,0 +1,210 @@
+//! This module provides a typed view of `info_overlay` entities.
+
+use std::collections::HashMap;
+
+use super::entities::Entity;
+use crate::errors::{VmfError, VmfResult};
+use crate::geometry::Vector3;
+use crate::utils::format_float;
+
+/// The classname of overlay entities.
+pub const OVERLAY_CLASSNAME: &str = "info_overlay";
+
+/// The keys of entities that list side IDs, by classname.
+const SIDE_KEYS: [(&str, &[&str]); 3] = [
+    ("info_overlay", &["sides"]),
+    ("info_overlay_transition", &["sides", "sides2"]),
+    ("info_decal", &["sides"]),
+];
+
+/// The typed key values of an `info_overlay` entity.
+///
+/// An overlay is a material projected onto a set of brush faces. Its corners are
+/// given in the basis of its origin: `uv0` to `uv3` are offsets along `basis_u`
+/// and `basis_v`, and the material is mapped from `StartU`/`StartV` at the first
+/// corner to `EndU`/`EndV` at the third.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overlay {
+    /// The ID of the entity.
+    pub id: u64,
+    /// The material of the overlay.
+    pub material: String,
+    /// The IDs of the sides the overlay is projected onto.
+    pub sides: Vec<u32>,
+    /// The center of the overlay.
+    pub basis_origin: Vector3,
+    /// The direction of the U texture axis.
+    pub basis_u: Vector3,
+    /// The direction of the V texture axis.
+    pub basis_v: Vector3,
+    /// The direction the overlay faces, away from the faces it's projected onto.
+    pub basis_normal: Vector3,
+    /// The four corners, as `(u, v, 0)` offsets from the origin along the basis.
+    pub uv: [Vector3; 4],
+    /// The texture U coordinate at the first corner.
+    pub start_u: f64,
+    /// The texture V coordinate at the first corner.
+    pub start_v: f64,
+    /// The texture U coordinate at the third corner.
+    pub end_u: f64,
+    /// The texture V coordinate at the third corner.
+    pub end_v: f64,
+}
+
+impl TryFrom<&Entity> for Overlay {
+    type Error = VmfError;
+
+    fn try_from(entity: &Entity) -> VmfResult<Self> {
+        let is_overlay = entity
+            .classname()
+            .is_some_and(|name| name.eq_ignore_ascii_case(OVERLAY_CLASSNAME));
+        if !is_overlay {
+            return Err(VmfError::InvalidFormat(format!(
+                "Entity {} is not an {}",
+                entity.id(),
+                OVERLAY_CLASSNAME
+            )));
+        }
+        let get = |key: &str| {
+            entity.get(key).map(String::as_str).ok_or_else(|| {
+                VmfError::InvalidFormat(format!("Overlay {} has no '{}' key", entity.id(), key))
+            })
+        };
+        let vector = |key: &str| get(key)?.parse::<Vector3>();
+        let float = |key: &str| {
+            get(key)?.trim().parse::<f64>().map_err(|_| {
+                VmfError::InvalidFormat(format!(
+                    "Overlay {} has an invalid '{}' key",
+                    entity.id(),
+                    key
+                ))
+            })
+        };
+
+        Ok(Overlay {
+            id: entity.id(),
+            material: get("material")?.to_string(),
+            sides: parse_side_ids(entity.get("sides").map_or("", String::as_str))?,
+            basis_origin: vector("BasisOrigin")?,
+            basis_u: vector("BasisU")?,
+            basis_v: vector("BasisV")?,
+            basis_normal: vector("BasisNormal")?,
+            uv: [
+                vector("uv0")?,
+                vector("uv1")?,
+                vector("uv2")?,
+                vector("uv3")?,
+            ],
+            start_u: float("StartU")?,
+            start_v: float("StartV")?,
+            end_u: float("EndU")?,
+            end_v: float("EndV")?,
+        })
+    }
+}
+
+impl Overlay {
+    /// Writes the typed key values into an entity, leaving its other keys alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `entity` - The entity to update, usually the one the overlay was read from.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("your_map.vmf")?;
+    /// let entity = vmf.entities.find_by_classname_mut("info_overlay").next().unwrap();
+    /// let mut overlay = Overlay::try_from(&*entity)?;
+    /// overlay.material = "decals/rollermine_crater".to_string();
+    /// overlay.apply(entity);
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn apply(&self, entity: &mut Entity) {
+        let side_ids: Vec<String> = self.sides.iter().map(u32::to_string).collect();
+        let values = [
+            ("classname", OVERLAY_CLASSNAME.to_string()),
+            ("id", self.id.to_string()),
+            ("material", self.material.clone()),
+            ("sides", side_ids.join(" ")),
+            ("BasisOrigin", self.basis_origin.to_string()),
+            ("BasisU", self.basis_u.to_string()),
+            ("BasisV", self.basis_v.to_string()),
+            ("BasisNormal", self.basis_normal.to_string()),
+            ("uv0", self.uv[0].to_string()),
+            ("uv1", self.uv[1].to_string()),
+            ("uv2", self.uv[2].to_string()),
+            ("uv3", self.uv[3].to_string()),
+            ("StartU", format_float(self.start_u)),
+            ("StartV", format_float(self.start_v)),
+            ("EndU", format_float(self.end_u)),
+            ("EndV", format_float(self.end_v)),
+        ];
+        for (key, value) in values {
+            entity.key_values.insert(key.to_string(), value);
+        }
+    }
+
+    /// Returns the corners of the overlay in world space.
+    pub fn corners(&self) -> [Vector3; 4] {
+        self.uv
+            .map(|uv| self.basis_origin + self.basis_u * uv.x + self.basis_v * uv.y)
+    }
+}
+
+/// Parses a space-separated list of side IDs.
+pub(crate) fn parse_side_ids(value: &str) -> VmfResult<Vec<u32>> {
+    value
+        .split_whitespace()
+        .map(|id| {
+            id.parse::<u32>()
+                .map_err(|_| VmfError::InvalidFormat(format!("Invalid side ID '{}'", id)))
+        })
+        .collect()
+}
+
+/// Returns the keys of `entity` that list side IDs, if it is an overlay or decal.
+pub(crate) fn side_keys(entity: &Entity) -> &'static [&'static str] {
+    let classname = entity.classname().unwrap_or_default();
+    SIDE_KEYS
+        .iter()
+        .find(|(name, _)| classname.eq_ignore_ascii_case(name))
+        .map_or(&[], |(_, keys)| keys)
+}
+
+/// Replaces the side IDs an overlay or decal refers to, where `remap` gives the
+/// sides that took the place of a side: none if it was removed, one if it was
+/// renumbered and several if it was split. IDs missing from `remap` and IDs that
+/// don't parse are kept.
+///
+/// Returns whether any key changed.
+pub(crate) fn remap_side_ids(entity: &mut Entity, remap: &HashMap<u32, Vec<u32>>) -> bool {
+    let mut changed = false;
+    for key in side_keys(entity) {
+        let Some(value) = entity.get_mut(key) else {
+            continue;
+        };
+        let mut ids: Vec<String> = Vec::new();
+        for id in value.split_whitespace() {
+            match id.parse::<u32>().ok().and_then(|id| remap.get(&id)) {
+                Some(new_ids) => {
+                    for new_id in new_ids {
+                        let new_id = new_id.to_string();
+                        if !ids.contains(&new_id) {
+                            ids.push(new_id);
+                        }
+                    }
+                }
+                None => ids.push(id.to_string()),
+            }
+        }
+        let remapped = ids.join(" ");
+        if *value != remapped {
+            *value = remapped;
+            changed = true;
+        }
+    }
+    changed
+}
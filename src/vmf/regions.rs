@@ -5,6 +5,7 @@ use indexmap::IndexMap;
 #[cfg(feature = "serialization")]
 use serde::{Deserialize, Serialize};
 
+use crate::geometry::Aabb;
 use crate::utils::{To01String, get_key_ref, take_and_parse_key, take_key_owned};
 use crate::{
     VmfBlock, VmfSerializable,
@@ -285,6 +286,45 @@ impl From<Cordon> for VmfBlock {
     }
 }
 
+impl Cordon {
+    /// Parses the `mins` and `maxs` of the cordon into a bounding box.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the box, or a `VmfError` if either corner is malformed.
+    pub fn bounds(&self) -> VmfResult<Aabb> {
+        Ok(Aabb::new(self.min.parse()?, self.max.parse()?))
+    }
+
+    /// Checks whether `aabb` lies entirely inside the cordon.
+    ///
+    /// # Arguments
+    ///
+    /// * `aabb` - The box to test, for example from `Solid::bounds` or `VmfFile::bounds`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing `true` if the box is inside, or a `VmfError` if the
+    /// cordon's corners are malformed.
+    pub fn contains(&self, aabb: &Aabb) -> VmfResult<bool> {
+        Ok(self.bounds()?.contains(aabb))
+    }
+
+    /// Checks whether `aabb` overlaps the cordon at all.
+    ///
+    /// # Arguments
+    ///
+    /// * `aabb` - The box to test.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing `true` if the boxes overlap, or a `VmfError` if the
+    /// cordon's corners are malformed.
+    pub fn intersects(&self, aabb: &Aabb) -> VmfResult<bool> {
+        Ok(self.bounds()?.intersects(aabb))
+    }
+}
+
 impl VmfSerializable for Cordon {
     fn to_vmf_string(&self, indent_level: usize) -> String {
         let indent: String = "\t".repeat(indent_level);
@@ -5,12 +5,15 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 use super::common::Editor;
-use crate::utils::{To01String, get_key_ref, take_and_parse_key, take_key_owned};
+use crate::geometry::{Plane, Vector3, parse_numbers};
+use crate::utils::{To01String, format_float, get_key_ref, take_and_parse_key, take_key_owned};
 use crate::{
     VmfBlock, VmfSerializable,
     errors::{VmfError, VmfResult},
 };
+use std::fmt;
 use std::mem;
+use std::str::FromStr;
 
 /// Represents the world block in a VMF file.
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -379,6 +382,121 @@ impl VmfSerializable for Side {
     }
 }
 
+impl Side {
+    /// Parses the three points of the `plane` string.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the points in the order they appear in the plane string,
+    /// or a `VmfError` if the string isn't made of exactly three points.
+    pub fn plane_points(&self) -> VmfResult<[Vector3; 3]> {
+        match Vector3::parse_list(&self.plane)?.as_slice() {
+            [a, b, c] => Ok([*a, *b, *c]),
+            _ => Err(VmfError::InvalidFormat(format!(
+                "Expected 3 points in plane '{}'",
+                self.plane
+            ))),
+        }
+    }
+
+    /// Sets the `plane` string from three points, in `"(x y z) (x y z) (x y z)"` format.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - The plane points, wound clockwise when viewed from outside the solid.
+    pub fn set_plane_points(&mut self, points: [Vector3; 3]) {
+        self.plane = format!("({}) ({}) ({})", points[0], points[1], points[2]);
+    }
+
+    /// Parses the `plane` string into a plane whose normal points out of the solid.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the plane, or a `VmfError` if the string is malformed
+    /// or its points are collinear.
+    pub fn to_plane(&self) -> VmfResult<Plane> {
+        let [a, b, c] = self.plane_points()?;
+        Plane::from_points(a, b, c).ok_or_else(|| {
+            VmfError::InvalidFormat(format!(
+                "Degenerate plane '{}' on side {}",
+                self.plane, self.id
+            ))
+        })
+    }
+
+    /// Parses the `uaxis` and `vaxis` strings.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the U and V texture axes, or a `VmfError` if either is malformed.
+    pub fn texture_axes(&self) -> VmfResult<[TextureAxis; 2]> {
+        Ok([self.u_axis.parse()?, self.v_axis.parse()?])
+    }
+
+    /// Sets the `uaxis` and `vaxis` strings from typed texture axes.
+    ///
+    /// # Arguments
+    ///
+    /// * `axes` - The U and V texture axes.
+    pub fn set_texture_axes(&mut self, axes: [TextureAxis; 2]) {
+        let [u, v] = axes;
+        self.u_axis = u.to_string();
+        self.v_axis = v.to_string();
+    }
+}
+
+/// A texture projection axis of a side, stored as `"[x y z shift] scale"` in a VMF.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
+pub struct TextureAxis {
+    /// The direction of the axis in world space.
+    pub axis: Vector3,
+    /// The texture shift along the axis, in texels.
+    pub shift: f64,
+    /// The texture scale, in world units per texel.
+    pub scale: f64,
+}
+
+impl Default for TextureAxis {
+    fn default() -> Self {
+        Self {
+            axis: Vector3::X,
+            shift: 0.0,
+            scale: 0.25,
+        }
+    }
+}
+
+impl FromStr for TextureAxis {
+    type Err = VmfError;
+
+    fn from_str(s: &str) -> VmfResult<Self> {
+        match parse_numbers(s)?.as_slice() {
+            [x, y, z, shift, scale] => Ok(Self {
+                axis: Vector3::new(*x, *y, *z),
+                shift: *shift,
+                scale: *scale,
+            }),
+            _ => Err(VmfError::InvalidFormat(format!(
+                "Expected '[x y z shift] scale' for a texture axis, got '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for TextureAxis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{} {}] {}",
+            self.axis,
+            format_float(self.shift),
+            format_float(self.scale)
+        )
+    }
+}
+
 /// Finds a block with the specified name in a vector of `VmfBlock`s,
 /// removes it from the vector, and returns ownership.
 /// Uses swap_remove for O(1) removal, but changes the order of remaining blocks.
@@ -707,6 +825,65 @@ impl TryFrom<VmfBlock> for DispRows {
 }
 
 impl DispRows {
+    /// Parses each row as a list of vectors (used by `normals`, `offsets` and `offset_normals`).
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing one `Vec<Vector3>` per row, or a `VmfError` if a row is malformed.
+    pub fn vectors(&self) -> VmfResult<Vec<Vec<Vector3>>> {
+        self.rows
+            .iter()
+            .map(|row| Vector3::parse_list(row))
+            .collect()
+    }
+
+    /// Parses each row as a list of numbers (used by `distances`, `alphas` and `triangle_tags`).
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing one `Vec<f64>` per row, or a `VmfError` if a row is malformed.
+    pub fn floats(&self) -> VmfResult<Vec<Vec<f64>>> {
+        self.rows.iter().map(|row| parse_numbers(row)).collect()
+    }
+
+    /// Creates rows from a grid of vectors.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The vectors of each row.
+    pub fn from_vectors(rows: &[Vec<Vector3>]) -> Self {
+        Self {
+            rows: rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                })
+                .collect(),
+        }
+    }
+
+    /// Creates rows from a grid of numbers.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The numbers of each row.
+    pub fn from_floats(rows: &[Vec<f64>]) -> Self {
+        Self {
+            rows: rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|v| format_float(*v))
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                })
+                .collect(),
+        }
+    }
+
     /// Converts the `DispRows` data into a `VmfBlock` with the specified name.
     ///
     /// # Arguments
@@ -0,0 +1,47 @@
+use super::VmfFile;
+use crate::errors::VmfResult;
+use crate::geometry::{Aabb, BoundsOptions, union_bounds};
+
+impl VmfFile {
+    /// Computes the bounding box of the whole map: world brushes and entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Which objects to include.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the box, or `None` if the map is empty,
+    /// or a `VmfError` if a plane is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// let bounds = vmf.bounds(&BoundsOptions::default())?;
+    /// for cordon in vmf.cordons.iter() {
+    ///     if let Some(bounds) = bounds {
+    ///         println!("{} covers the map: {}", cordon.name, cordon.contains(&bounds)?);
+    ///     }
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn bounds(&self, options: &BoundsOptions) -> VmfResult<Option<Aabb>> {
+        let hidden = if options.include_hidden {
+            self.hiddens.as_slice()
+        } else {
+            &[]
+        };
+
+        let mut bounds = self.world.bounds(options)?;
+        for ent in self.entities.iter().chain(hidden) {
+            if ent.is_hidden && !options.include_hidden {
+                continue;
+            }
+            bounds = union_bounds(bounds, ent.bounds(options)?);
+        }
+        Ok(bounds)
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use super::VmfFile;
+use crate::errors::VmfResult;
+use crate::prelude::Solid;
+
+impl VmfFile {
+    /// Carves a convex brush out of the brushes it overlaps, like Hammer's Carve.
+    ///
+    /// Each overlapping target is replaced by the convex pieces left after subtracting
+    /// `cutter` (see `Solid::subtract`). Brushes with the same ID as the cutter are
+    /// skipped, so the cutter can be a brush of this map. Overlays on the carved faces
+    /// are moved to the faces left over. Nothing is changed if any target can't be carved.
+    ///
+    /// # Arguments
+    ///
+    /// * `cutter` - The convex brush to subtract.
+    /// * `targets` - The IDs of the brushes to carve, or `None` for every world and
+    ///   entity brush that isn't hidden.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the number of brushes that were carved, or a `VmfError`
+    /// if a plane is malformed or an overlapping target has a displacement.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("your_map.vmf")?;
+    /// // Cut a doorway with the brush that marks it, then remove the marker.
+    /// let doorway = vmf.world.solids.pop().unwrap();
+    /// let carved = vmf.carve(&doorway, None)?;
+    /// println!("carved {} brushes", carved);
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn carve(&mut self, cutter: &Solid, targets: Option<&[u64]>) -> VmfResult<usize> {
+        let cutter_bounds = cutter.bounds()?;
+        let is_target = |solid: &Solid| -> VmfResult<bool> {
+            if solid.id == cutter.id || targets.is_some_and(|ids| !ids.contains(&solid.id)) {
+                return Ok(false);
+            }
+            // A cheap rejection before clipping.
+            Ok(match (cutter_bounds, solid.bounds()?) {
+                (Some(a), Some(b)) => a.intersects(&b),
+                _ => false,
+            })
+        };
+
+        // Compute every replacement first so a failure leaves the map untouched.
+        let mut ids = self.id_allocator();
+        let mut replacements: Vec<Option<Vec<Solid>>> = Vec::new();
+        let mut side_ids = HashMap::new();
+        for solid in self.solids(false) {
+            let pieces = if is_target(solid)? {
+                solid.subtract(cutter, &mut ids)?
+            } else {
+                None
+            };
+            if let Some(pieces) = &pieces {
+                side_ids.extend(solid.derived_sides(pieces));
+            }
+            replacements.push(pieces);
+        }
+
+        let carved = replacements.iter().filter(|r| r.is_some()).count();
+        let mut replacements = replacements.into_iter();
+        replace_solids(&mut self.world.solids, &mut replacements);
+        for ent in self.entities.iter_mut() {
+            if let Some(solids) = ent.solids.as_mut() {
+                replace_solids(solids, &mut replacements);
+            }
+        }
+        self.remap_overlay_sides(&side_ids);
+        Ok(carved)
+    }
+}
+
+/// Replaces each solid in `solids` with its pieces, taking one entry per solid from `replacements`.
+fn replace_solids(
+    solids: &mut Vec<Solid>,
+    replacements: &mut impl Iterator<Item = Option<Vec<Solid>>>,
+) {
+    let mut result = Vec::with_capacity(solids.len());
+    for solid in solids.drain(..) {
+        match replacements.next().flatten() {
+            Some(pieces) => result.extend(pieces),
+            None => result.push(solid),
+        }
+    }
+    *solids = result;
+}
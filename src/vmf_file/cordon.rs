@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use super::{IdAllocator, VmfFile};
+use crate::errors::VmfResult;
+use crate::geometry::{Aabb, ClipMode, Plane, Vector3};
+use crate::prelude::{Entity, Solid};
+use crate::vmf::regions::Cordon;
+
+/// How `VmfFile::apply_cordons` cuts the map down to its active cordons.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CordonOptions {
+    /// The material of the sealing brushes and of faces created by clipping.
+    /// Defaults to `TOOLS/TOOLSSKYBOX`, like Hammer's cordon texture.
+    pub material: String,
+    /// The thickness of the sealing brushes. Defaults to 16.
+    pub thickness: f64,
+    /// Clip brushes crossing a cordon to the parts inside it. Brushes with
+    /// displacements are always kept whole. Defaults to `false`, as in Hammer.
+    pub clip_brushes: bool,
+}
+
+impl Default for CordonOptions {
+    fn default() -> Self {
+        Self {
+            material: "TOOLS/TOOLSSKYBOX".to_string(),
+            thickness: 16.0,
+            clip_brushes: false,
+        }
+    }
+}
+
+impl VmfFile {
+    /// Cuts the map down to its active cordons, the way Hammer compiles with cordons on.
+    ///
+    /// Brushes and point entities entirely outside every active cordon are removed,
+    /// as are brush entities left without brushes. Each active cordon is then sealed
+    /// with six brushes around its box, and the cordons are deactivated so the result
+    /// is a standalone map. Every cordon with `Cordon::active` set is used, whether
+    /// or not `Cordons::active` is. Overlays follow the faces that were clipped and
+    /// drop the faces that were removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The sealing material and thickness, and whether crossing brushes are clipped.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the number of cordons applied, or a `VmfError` if a
+    /// cordon or a plane is malformed. The map is left unchanged on error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("your_map.vmf")?;
+    /// if vmf.apply_cordons(&CordonOptions::default())? > 0 {
+    ///     vmf.save("your_map_cordoned.vmf")?;
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn apply_cordons(&mut self, options: &CordonOptions) -> VmfResult<usize> {
+        let boxes = self
+            .cordons
+            .iter()
+            .filter(|c| c.active)
+            .map(Cordon::bounds)
+            .collect::<VmfResult<Vec<Aabb>>>()?;
+        if boxes.is_empty() {
+            return Ok(0);
+        }
+
+        // Build everything first so a failure leaves the map untouched.
+        let mut ids = self.id_allocator();
+        let mut side_ids = HashMap::new();
+        let mut solids = keep_solids(&self.world.solids, &boxes, options, &mut ids, &mut side_ids)?;
+        let hidden = keep_solids(&self.world.hidden, &boxes, options, &mut ids, &mut side_ids)?;
+        let entities = keep_entities(&self.entities, &boxes, options, &mut ids, &mut side_ids)?;
+        let hiddens = keep_entities(&self.hiddens, &boxes, options, &mut ids, &mut side_ids)?;
+        for bounds in &boxes {
+            let block = Solid::block(bounds, &options.material, &mut ids)?;
+            solids.extend(block.hollow(-options.thickness, &mut ids)?);
+        }
+
+        self.world.solids = solids;
+        self.world.hidden = hidden;
+        self.entities.0 = entities;
+        self.hiddens.0 = hiddens;
+        self.cordons.active = 0;
+        for cordon in self.cordons.iter_mut() {
+            cordon.active = false;
+        }
+        self.remap_overlay_sides(&side_ids);
+        Ok(boxes.len())
+    }
+}
+
+/// Keeps the solids touching any of `boxes`, clipped to them if requested, and
+/// records in `side_ids` what became of the sides of solids removed or clipped.
+fn keep_solids(
+    solids: &[Solid],
+    boxes: &[Aabb],
+    options: &CordonOptions,
+    ids: &mut IdAllocator,
+    side_ids: &mut HashMap<u32, Vec<u32>>,
+) -> VmfResult<Vec<Solid>> {
+    let mut kept = Vec::with_capacity(solids.len());
+    for solid in solids {
+        let Some(bounds) = solid.bounds()? else {
+            kept.push(solid.clone());
+            continue;
+        };
+        if boxes.iter().any(|b| b.contains(&bounds)) {
+            kept.push(solid.clone());
+            continue;
+        }
+
+        let touching: Vec<&Aabb> = boxes.iter().filter(|b| b.intersects(&bounds)).collect();
+        let has_displacement = solid.sides.iter().any(|s| s.dispinfo.is_some());
+        if touching.is_empty() {
+            side_ids.extend(solid.derived_sides(&[]));
+            continue;
+        }
+        if !options.clip_brushes || has_displacement {
+            kept.push(solid.clone());
+            continue;
+        }
+
+        // The piece in the first box keeps the brush's IDs; pieces in overlapping
+        // boxes are copies and need their own.
+        let mut first = true;
+        let mut pieces = Vec::new();
+        for cordon in touching {
+            let Some(mut piece) = clip_to_box(solid, cordon, &options.material, ids)? else {
+                continue;
+            };
+            if !first {
+                piece.id = ids.next_object_id();
+                for side in &mut piece.sides {
+                    side.id = ids.next_side_id();
+                }
+            }
+            first = false;
+            pieces.push(piece);
+        }
+        side_ids.extend(solid.derived_sides(&pieces));
+        kept.extend(pieces);
+    }
+    Ok(kept)
+}
+
+/// Keeps the entities inside any of `boxes`, and brush entities with brushes left.
+fn keep_entities(
+    entities: &[Entity],
+    boxes: &[Aabb],
+    options: &CordonOptions,
+    ids: &mut IdAllocator,
+    side_ids: &mut HashMap<u32, Vec<u32>>,
+) -> VmfResult<Vec<Entity>> {
+    let mut kept = Vec::with_capacity(entities.len());
+    for ent in entities {
+        match &ent.solids {
+            Some(solids) if !solids.is_empty() => {
+                let solids = keep_solids(solids, boxes, options, ids, side_ids)?;
+                if !solids.is_empty() {
+                    let mut ent = ent.clone();
+                    ent.solids = Some(solids);
+                    kept.push(ent);
+                }
+            }
+            _ => {
+                // Entities without an origin have no place to cut them by.
+                let inside = ent
+                    .origin()
+                    .is_none_or(|origin| boxes.iter().any(|b| b.contains_point(origin)));
+                if inside {
+                    kept.push(ent.clone());
+                }
+            }
+        }
+    }
+    Ok(kept)
+}
+
+/// Clips a brush to the inside of a box, or returns `None` if nothing is left.
+fn clip_to_box(
+    solid: &Solid,
+    bounds: &Aabb,
+    material: &str,
+    ids: &mut IdAllocator,
+) -> VmfResult<Option<Solid>> {
+    let planes = [
+        Plane::new(Vector3::Z, bounds.max.z),
+        Plane::new(-Vector3::Z, -bounds.min.z),
+        Plane::new(-Vector3::X, -bounds.min.x),
+        Plane::new(Vector3::X, bounds.max.x),
+        Plane::new(Vector3::Y, bounds.max.y),
+        Plane::new(-Vector3::Y, -bounds.min.y),
+    ];
+    let mut piece = solid.clone();
+    for plane in &planes {
+        match piece.clip(plane, ClipMode::KeepBack, material, ids)?.back {
+            Some(back) => piece = back,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(piece))
+}
@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use super::VmfFile;
+use crate::errors::VmfResult;
+use crate::export::{ExportOptions, GltfFormat};
+use crate::geometry::Mesh;
+use crate::prelude::{Editor, Solid};
+
+impl VmfFile {
+    /// Triangulates the world brushes and brush entities into a single mesh.
+    ///
+    /// Faces are grouped by material and get UVs from their texture axes. Hidden
+    /// objects, visgroup-filtered objects and skipped materials are left out, and the
+    /// unit scale and up axis of `options` are applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - What to export and how to convert it.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the mesh, or a `VmfError` if a plane or texture axis is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    /// use vmf_forge::export::{ExportOptions, HAMMER_UNITS_TO_METERS};
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// let options = ExportOptions {
+    ///     unit_scale: HAMMER_UNITS_TO_METERS,
+    ///     ..Default::default()
+    /// };
+    /// let mesh = vmf.to_mesh(&options)?;
+    /// println!("{} triangles", mesh.triangle_count());
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn to_mesh(&self, options: &ExportOptions) -> VmfResult<Mesh> {
+        let excluded = self.excluded_visgroup_ids(options);
+        let visible = |editor: &Editor| {
+            (options.include_hidden || editor.visgroup_shown)
+                && !editor.visgroup_id.is_some_and(|id| excluded.contains(&id))
+        };
+
+        let hidden: &[Solid] = if options.include_hidden {
+            &self.world.hidden
+        } else {
+            &[]
+        };
+        let mut solids: Vec<&Solid> = self.world.solids.iter().chain(hidden).collect();
+
+        if options.include_entity_brushes {
+            let hidden = if options.include_hidden {
+                self.hiddens.as_slice()
+            } else {
+                &[]
+            };
+            for ent in self.entities.iter().chain(hidden) {
+                if (ent.is_hidden && !options.include_hidden) || !visible(&ent.editor) {
+                    continue;
+                }
+                solids.extend(ent.solids.iter().flatten());
+            }
+        }
+
+        let mut mesh = Mesh::new();
+        for solid in solids.into_iter().filter(|s| visible(&s.editor)) {
+            mesh.append(&solid.to_mesh(options)?);
+        }
+
+        if options.unit_scale != 1.0 {
+            mesh.scale(options.unit_scale);
+        }
+        if options.y_up {
+            mesh.z_up_to_y_up();
+        }
+        Ok(mesh)
+    }
+
+    /// Exports the map as a Wavefront OBJ file with an MTL material library next to it.
+    ///
+    /// The material library gets the same file name as `path` with an `.mtl` extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the `.obj` file to write.
+    /// * `options` - What to export and how to convert it.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if the geometry is malformed
+    /// or a file can't be written.
+    pub fn export_obj(&self, path: impl AsRef<Path>, options: &ExportOptions) -> VmfResult<()> {
+        let path = path.as_ref();
+        let mesh = self.to_mesh(options)?;
+        let mtl_path = path.with_extension("mtl");
+        let mtl_name = mtl_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+
+        fs::write(&mtl_path, mesh.to_mtl())?;
+        fs::write(path, mesh.to_obj(mtl_name.as_deref()))?;
+        Ok(())
+    }
+
+    /// Exports the map as a glTF 2.0 file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the `.gltf` or `.glb` file to write.
+    /// * `options` - What to export and how to convert it.
+    /// * `format` - Whether a JSON document or a binary file is written.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if the geometry is malformed
+    /// or the file can't be written.
+    pub fn export_gltf(
+        &self,
+        path: impl AsRef<Path>,
+        options: &ExportOptions,
+        format: GltfFormat,
+    ) -> VmfResult<()> {
+        let mesh = self.to_mesh(options)?;
+        fs::write(path, mesh.to_gltf(format))?;
+        Ok(())
+    }
+
+    /// Collects the IDs of the excluded visgroups and all of their children.
+    fn excluded_visgroup_ids(&self, options: &ExportOptions) -> HashSet<i32> {
+        let mut ids = HashSet::new();
+        for &id in &options.exclude_visgroups {
+            if let Some(group) = self.visgroups.find_by_id(id) {
+                super::visgroup_ops::collect_child_visgroup_ids(group, &mut ids);
+            } else {
+                ids.insert(id);
+            }
+        }
+        ids
+    }
+}
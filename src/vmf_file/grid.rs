@@ -0,0 +1,137 @@
+use super::VmfFile;
+use crate::errors::VmfResult;
+use crate::geometry::{OffGrid, SolidProblem};
+use crate::prelude::Solid;
+
+/// The outcome of `VmfFile::snap_to_grid`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GridSnap {
+    /// The IDs of the solids that were snapped.
+    pub snapped: Vec<u64>,
+    /// The problems that kept the remaining off-grid solids from being snapped.
+    /// Those solids are left unchanged.
+    pub failed: Vec<SolidProblem>,
+}
+
+impl VmfFile {
+    /// Finds the solids with plane points or vertices off a grid, including hidden
+    /// ones and those owned by entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid size, or `None` to use `ViewSettings::grid_spacing`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the off-grid solids in document order, or a
+    /// `VmfError` if the grid size is not positive or a plane is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// for off_grid in vmf.find_off_grid(None)? {
+    ///     eprintln!("{}", off_grid);
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn find_off_grid(&self, grid: Option<f64>) -> VmfResult<Vec<OffGrid>> {
+        let grid = grid.unwrap_or(self.viewsettings.grid_spacing as f64);
+        let mut found = Vec::new();
+        for solid in self.world.solids.iter().chain(&self.world.hidden) {
+            found.extend(solid.off_grid(grid)?);
+        }
+        for ent in self.entities.iter().chain(self.hiddens.iter()) {
+            for solid in ent.solids.iter().flatten() {
+                if let Some(off_grid) = solid.off_grid(grid)? {
+                    found.push(OffGrid {
+                        entity_id: Some(ent.id()),
+                        ..off_grid
+                    });
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Snaps the plane points of every off-grid solid to a grid, like Hammer's
+    /// "Snap selected to grid" on the whole map.
+    ///
+    /// Solids whose plane points are already on the grid are left alone. A solid
+    /// that would become invalid once snapped is left unchanged and reported instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid size, or `None` to use `ViewSettings::grid_spacing`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the snapped and the failed solids, or a `VmfError`
+    /// if the grid size is not positive or a plane is malformed. The map is left
+    /// unchanged on error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("your_map.vmf")?;
+    /// let result = vmf.snap_to_grid(None)?;
+    /// for problem in &result.failed {
+    ///     eprintln!("could not snap {}", problem);
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn snap_to_grid(&mut self, grid: Option<f64>) -> VmfResult<GridSnap> {
+        let grid = grid.unwrap_or(self.viewsettings.grid_spacing as f64);
+
+        // Snap copies first so a malformed plane leaves the map untouched.
+        let mut result = GridSnap::default();
+        let mut world = self.world.clone();
+        for solid in world.solids.iter_mut().chain(&mut world.hidden) {
+            snap_solid(solid, grid, None, &mut result)?;
+        }
+        let mut entities = self.entities.clone();
+        let mut hiddens = self.hiddens.clone();
+        for ent in entities.iter_mut().chain(hiddens.iter_mut()) {
+            let entity_id = ent.id();
+            for solid in ent.solids.iter_mut().flatten() {
+                snap_solid(solid, grid, Some(entity_id), &mut result)?;
+            }
+        }
+
+        self.world = world;
+        self.entities = entities;
+        self.hiddens = hiddens;
+        Ok(result)
+    }
+}
+
+/// Snaps one solid if any of its plane points is off the grid, recording the outcome.
+fn snap_solid(
+    solid: &mut Solid,
+    grid: f64,
+    entity_id: Option<u64>,
+    result: &mut GridSnap,
+) -> VmfResult<()> {
+    let off_grid = solid
+        .off_grid(grid)?
+        .is_some_and(|off_grid| !off_grid.side_ids.is_empty());
+    if !off_grid {
+        return Ok(());
+    }
+
+    let problems = solid.snap_to_grid(grid)?;
+    if problems.is_empty() {
+        result.snapped.push(solid.id);
+    }
+    result
+        .failed
+        .extend(problems.into_iter().map(|problem| SolidProblem {
+            entity_id,
+            ..problem
+        }));
+    Ok(())
+}
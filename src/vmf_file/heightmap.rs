@@ -0,0 +1,322 @@
+use super::{VmfFile, png};
+use crate::errors::{VmfError, VmfResult};
+use crate::geometry::{Aabb, Vector3, check_power, grid_size};
+use crate::prelude::Solid;
+use crate::vmf::world::DispRows;
+
+/// A grid of height samples from 0 to 1, such as a grayscale image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heightmap {
+    /// The number of samples per row.
+    pub width: usize,
+    /// The number of rows.
+    pub height: usize,
+    /// The samples row by row, starting with the top row of the image.
+    pub samples: Vec<f64>,
+}
+
+impl Heightmap {
+    /// Creates a heightmap from samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The number of samples per row.
+    /// * `height` - The number of rows.
+    /// * `samples` - The samples from 0 to 1, row by row from the top.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the heightmap, or a `VmfError` if it is smaller than
+    /// 2 by 2 or the number of samples doesn't match its size.
+    pub fn new(width: usize, height: usize, samples: Vec<f64>) -> VmfResult<Self> {
+        if width < 2 || height < 2 {
+            return Err(VmfError::InvalidFormat(format!(
+                "A heightmap must be at least 2x2, got {}x{}",
+                width, height
+            )));
+        }
+        if width.checked_mul(height) != Some(samples.len()) {
+            return Err(VmfError::InvalidFormat(format!(
+                "A {}x{} heightmap needs {} samples, got {}",
+                width,
+                height,
+                width.saturating_mul(height),
+                samples.len()
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            samples,
+        })
+    }
+
+    /// Reads a PNG image.
+    ///
+    /// Only non-interlaced grayscale and RGB images, with or without alpha, at 8 or 16
+    /// bits per channel are supported; color channels are averaged and alpha is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The contents of the PNG file.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the heightmap, or a `VmfError` if the image is malformed
+    /// or uses an unsupported format.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::vmf_file::Heightmap;
+    ///
+    /// let heightmap = Heightmap::from_png(&std::fs::read("terrain.png")?)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_png(data: &[u8]) -> VmfResult<Self> {
+        let (width, height, samples) = png::decode_gray(data)?;
+        Self::new(width, height, samples)
+    }
+
+    /// Reads raw little-endian 16-bit samples, as written by most terrain generators
+    /// (`.r16` and `.raw` files).
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The contents of the file.
+    /// * `width` - The number of samples per row.
+    /// * `height` - The number of rows.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the heightmap, or a `VmfError` if the data doesn't hold
+    /// exactly `width` × `height` samples.
+    pub fn from_raw16(data: &[u8], width: usize, height: usize) -> VmfResult<Self> {
+        if !data.len().is_multiple_of(2) {
+            return Err(VmfError::InvalidFormat(
+                "Raw 16-bit heightmap has an odd number of bytes".to_string(),
+            ));
+        }
+        let samples = data
+            .chunks_exact(2)
+            .map(|pair| f64::from(u16::from_le_bytes([pair[0], pair[1]])) / 65535.0)
+            .collect();
+        Self::new(width, height, samples)
+    }
+
+    /// Samples the heightmap with bilinear interpolation.
+    ///
+    /// # Arguments
+    ///
+    /// * `u` - The horizontal position, from 0 at the left edge to 1 at the right edge.
+    /// * `v` - The vertical position, from 0 at the top edge to 1 at the bottom edge.
+    ///
+    /// # Returns
+    ///
+    /// The interpolated sample. Positions outside the heightmap are clamped to its edges.
+    pub fn sample(&self, u: f64, v: f64) -> f64 {
+        let x = u.clamp(0.0, 1.0) * (self.width - 1) as f64;
+        let y = v.clamp(0.0, 1.0) * (self.height - 1) as f64;
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (tx, ty) = (x - x0 as f64, y - y0 as f64);
+
+        let at = |x: usize, y: usize| self.samples[y * self.width + x];
+        let top = at(x0, y0) + (at(x1, y0) - at(x0, y0)) * tx;
+        let bottom = at(x0, y1) + (at(x1, y1) - at(x0, y1)) * tx;
+        top + (bottom - top) * ty
+    }
+}
+
+/// How `VmfFile::import_heightmap` lays terrain out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeightmapOptions {
+    /// The area covered by the terrain brushes. The displacements sit on their top
+    /// faces at `bounds.max.z`; the top row of the image is at `bounds.max.y`.
+    pub bounds: Aabb,
+    /// The number of brushes along X and Y.
+    pub tiles: [usize; 2],
+    /// The height of a white sample above the top faces; negative heights go below them.
+    pub height: f64,
+    /// The power of the displacements, from 2 to 4. Defaults to 3.
+    pub power: u8,
+    /// The material of new brushes. Defaults to `DEV/DEV_BLENDMEASURE`.
+    pub material: String,
+    /// A second map for the blend alphas, white being fully blended. Defaults to `None`,
+    /// which leaves alphas at 0.
+    pub alphas: Option<Heightmap>,
+}
+
+impl HeightmapOptions {
+    /// Creates options with power 3 displacements and no alpha map.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The area covered by the terrain brushes.
+    /// * `tiles` - The number of brushes along X and Y.
+    /// * `height` - The height of a white sample above the top faces.
+    pub fn new(bounds: Aabb, tiles: [usize; 2], height: f64) -> Self {
+        Self {
+            bounds,
+            tiles,
+            height,
+            power: 3,
+            material: "DEV/DEV_BLENDMEASURE".to_string(),
+            alphas: None,
+        }
+    }
+}
+
+impl VmfFile {
+    /// Builds displacement terrain from a heightmap.
+    ///
+    /// `options.bounds` is split into a grid of brushes, and the top face of each gets
+    /// a displacement raised by the heightmap. A world brush that already fills a tile
+    /// exactly is updated in place; otherwise a new brush is added. Each vertex samples
+    /// the heightmap at its own position, so neighboring tiles share their edge heights.
+    /// A heightmap of `tiles × 2^power + 1` samples along each axis maps one sample to
+    /// each vertex. Triangle tags follow the slope of the resulting terrain.
+    ///
+    /// # Arguments
+    ///
+    /// * `heightmap` - The heights, from 0 at the top faces to 1 at `options.height` above them.
+    /// * `options` - The layout of the terrain.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the IDs of the terrain brushes, row by row along X from
+    /// the minimum corner, or a `VmfError` if the options are invalid or an existing
+    /// brush is malformed. The map is left unchanged on error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    /// use vmf_forge::vmf_file::{Heightmap, HeightmapOptions};
+    ///
+    /// let mut vmf = VmfFile::open("your_map.vmf")?;
+    /// let heightmap = Heightmap::from_raw16(&std::fs::read("terrain.r16")?, 129, 129)?;
+    /// let bounds = Aabb::new(Vector3::new(0.0, 0.0, -16.0), Vector3::new(4096.0, 4096.0, 0.0));
+    /// vmf.import_heightmap(&heightmap, &HeightmapOptions::new(bounds, [8, 8], 1024.0))?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn import_heightmap(
+        &mut self,
+        heightmap: &Heightmap,
+        options: &HeightmapOptions,
+    ) -> VmfResult<Vec<u64>> {
+        let [columns, rows] = options.tiles;
+        let size = options.bounds.size();
+        if columns == 0 || rows == 0 {
+            return Err(VmfError::InvalidFormat(
+                "A heightmap needs at least one tile".to_string(),
+            ));
+        }
+        check_power(options.power)?;
+        let valid = options.height.is_finite() && size.x > 0.0 && size.y > 0.0 && size.z > 0.0;
+        if !valid {
+            return Err(VmfError::InvalidFormat(
+                "Heightmap bounds must have a size and the height must be finite".to_string(),
+            ));
+        }
+
+        let bounds = &options.bounds;
+        let edge = |axis: usize, i: usize, count: usize| {
+            bounds.min[axis] + size[axis] * i as f64 / count as f64
+        };
+        let mut ids = self.id_allocator();
+        let mut solids = self.world.solids.clone();
+        let mut terrain = Vec::with_capacity(rows * columns);
+        for row in 0..rows {
+            for column in 0..columns {
+                let tile = Aabb::new(
+                    Vector3::new(edge(0, column, columns), edge(1, row, rows), bounds.min.z),
+                    Vector3::new(
+                        edge(0, column + 1, columns),
+                        edge(1, row + 1, rows),
+                        bounds.max.z,
+                    ),
+                );
+                let index = match find_tile(&solids, &tile)? {
+                    Some(index) => index,
+                    None => {
+                        solids.push(Solid::block(&tile, &options.material, &mut ids)?);
+                        solids.len() - 1
+                    }
+                };
+                displace_top(&mut solids[index], heightmap, options)?;
+                terrain.push(solids[index].id);
+            }
+        }
+
+        self.world.solids = solids;
+        Ok(terrain)
+    }
+}
+
+/// Returns the index of the solid filling `tile` exactly, if any.
+fn find_tile(solids: &[Solid], tile: &Aabb) -> VmfResult<Option<usize>> {
+    for (index, solid) in solids.iter().enumerate() {
+        if let Some(bounds) = solid.bounds()?
+            && bounds.min.approx_eq(tile.min, 0.01)
+            && bounds.max.approx_eq(tile.max, 0.01)
+        {
+            return Ok(Some(index));
+        }
+    }
+    Ok(None)
+}
+
+/// Puts a displacement raised by the heightmap on the upward face of a solid.
+fn displace_top(
+    solid: &mut Solid,
+    heightmap: &Heightmap,
+    options: &HeightmapOptions,
+) -> VmfResult<()> {
+    let side_index = solid
+        .faces()?
+        .into_iter()
+        .find(|face| face.plane.normal.approx_eq(Vector3::Z, 1e-6))
+        .map(|face| face.side_index)
+        .ok_or_else(|| {
+            VmfError::InvalidFormat(format!("Solid {} has no top face to displace", solid.id))
+        })?;
+    let mut disp = solid.sides[side_index].make_displacement(solid, options.power)?;
+
+    // Terrain below the faces points the normals down, keeping the distances positive.
+    let size = grid_size(options.power);
+    if options.height < 0.0 {
+        disp.normals = DispRows::from_vectors(&vec![vec![-Vector3::Z; size]; size]);
+    }
+
+    // Sample at each vertex's position on the face, whatever corner the grid starts from.
+    let flat = disp
+        .build_mesh(&solid.sides[side_index], solid)?
+        .flat_vertices;
+    let bounds = &options.bounds;
+    let uv = |v: &Vector3| {
+        let size = bounds.size();
+        ((v.x - bounds.min.x) / size.x, (bounds.max.y - v.y) / size.y)
+    };
+    let rows = |f: &dyn Fn(f64, f64) -> f64| -> Vec<Vec<f64>> {
+        flat.chunks(size)
+            .map(|row| {
+                row.iter()
+                    .map(|v| {
+                        let (u, v) = uv(v);
+                        f(u, v)
+                    })
+                    .collect()
+            })
+            .collect()
+    };
+    disp.distances =
+        DispRows::from_floats(&rows(&|u, v| heightmap.sample(u, v) * options.height.abs()));
+    if let Some(alphas) = &options.alphas {
+        disp.alphas = DispRows::from_floats(&rows(&|u, v| alphas.sample(u, v) * 255.0));
+    }
+    disp.update_triangle_tags(&solid.sides[side_index], solid)?;
+
+    solid.sides[side_index].dispinfo = Some(disp);
+    Ok(())
+}
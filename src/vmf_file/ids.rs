@@ -0,0 +1,84 @@
+use super::VmfFile;
+
+/// Hands out unused IDs for objects added to a `VmfFile`.
+///
+/// Hammer uses one ID space for solids, entities and groups, and a separate one for sides.
+/// The allocator starts after the highest ID currently in use in each space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdAllocator {
+    next_object_id: u64,
+    next_side_id: u32,
+}
+
+impl IdAllocator {
+    /// Returns a fresh ID for a solid, entity or group.
+    pub fn next_object_id(&mut self) -> u64 {
+        let id = self.next_object_id;
+        self.next_object_id += 1;
+        id
+    }
+
+    /// Returns a fresh ID for a side.
+    pub fn next_side_id(&mut self) -> u32 {
+        let id = self.next_side_id;
+        self.next_side_id += 1;
+        id
+    }
+}
+
+impl VmfFile {
+    /// Creates an `IdAllocator` that won't collide with any ID currently used in this file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::default();
+    /// let mut ids = vmf.id_allocator();
+    /// assert_eq!(ids.next_object_id(), 2); // ID 1 is reserved for the world
+    /// assert_eq!(ids.next_side_id(), 1);
+    /// ```
+    pub fn id_allocator(&self) -> IdAllocator {
+        // The world itself always takes object ID 1.
+        let mut max_object_id = self
+            .world
+            .key_values
+            .get("id")
+            .and_then(|id| id.parse::<u64>().ok())
+            .unwrap_or(1)
+            .max(1);
+        let mut max_side_id = 0;
+
+        if let Some(group) = &self.world.group {
+            max_object_id = max_object_id.max(u64::from(group.id));
+        }
+
+        let entity_solids = self
+            .entities
+            .iter()
+            .chain(self.hiddens.iter())
+            .inspect(|ent| max_object_id = max_object_id.max(ent.id()))
+            .filter_map(|ent| ent.solids.as_ref())
+            .flatten()
+            .collect::<Vec<_>>();
+
+        for solid in self
+            .world
+            .solids
+            .iter()
+            .chain(self.world.hidden.iter())
+            .chain(entity_solids)
+        {
+            max_object_id = max_object_id.max(solid.id);
+            for side in &solid.sides {
+                max_side_id = max_side_id.max(side.id);
+            }
+        }
+
+        IdAllocator {
+            next_object_id: max_object_id + 1,
+            next_side_id: max_side_id + 1,
+        }
+    }
+}
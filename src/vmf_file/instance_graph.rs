@@ -0,0 +1,348 @@
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+
+use super::{InstanceResolver, VmfFile};
+use crate::errors::VmfResult;
+
+/// A parameter declared by a `func_instance_parms` entity inside an instance file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceParameter {
+    /// The parameter name, including the leading `$`.
+    pub name: String,
+    /// The declared type, such as `integer`, `string` or `target_destination`.
+    pub kind: String,
+    /// The default value, if one is declared.
+    pub default: Option<String>,
+}
+
+impl InstanceParameter {
+    /// Parses a `parmN` value of the form `"$name type [default]"`.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the parameter, or `None` if the value has no `$name`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.trim().splitn(3, char::is_whitespace);
+        let name = parts.next().filter(|name| name.starts_with('$'))?;
+        let kind = parts.next().unwrap_or("string").trim();
+        let default = parts
+            .next()
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(str::to_string);
+
+        Some(Self {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            default,
+        })
+    }
+}
+
+impl VmfFile {
+    /// Returns the parameters declared by the `func_instance_parms` entities of this file.
+    ///
+    /// # Returns
+    ///
+    /// A vector of the declared parameters, in declaration order.
+    pub fn instance_parameters(&self) -> Vec<InstanceParameter> {
+        self.entities
+            .find_by_classname("func_instance_parms")
+            .flat_map(|ent| {
+                ent.key_values
+                    .iter()
+                    .filter(|(key, _)| key.to_ascii_lowercase().starts_with("parm"))
+                    .filter_map(|(_, value)| InstanceParameter::parse(value))
+            })
+            .collect()
+    }
+
+    /// Builds the instance dependency graph rooted at this file.
+    ///
+    /// The file's own location is used to resolve relative instance paths, so it
+    /// should have been loaded with `VmfFile::open`.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolver` - Controls where instance files are searched for.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the graph, or a `VmfError` if an instance file fails to load.
+    pub fn instance_graph(&self, resolver: &InstanceResolver) -> VmfResult<InstanceGraph> {
+        let root = self
+            .path
+            .as_ref()
+            .map(|p| canonical(Path::new(p)))
+            .unwrap_or_default();
+        let mut builder = GraphBuilder::new(resolver, &root);
+        builder.visit_loaded(root, self)?;
+        Ok(builder.graph)
+    }
+}
+
+/// A `func_instance` reference from one file to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceEdge {
+    /// The file containing the `func_instance`.
+    pub from: PathBuf,
+    /// The resolved instance file.
+    pub to: PathBuf,
+    /// The ID of the `func_instance` entity.
+    pub entity_id: u64,
+}
+
+/// A `func_instance` whose `file` could not be found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingInstance {
+    /// The file containing the `func_instance`.
+    pub from: PathBuf,
+    /// The ID of the `func_instance` entity.
+    pub entity_id: u64,
+    /// The unresolved value of the `file` key.
+    pub file: String,
+}
+
+/// A `replaceNN` key that sets a parameter the instance doesn't declare.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndeclaredParameter {
+    /// The file containing the `func_instance`.
+    pub from: PathBuf,
+    /// The ID of the `func_instance` entity.
+    pub entity_id: u64,
+    /// The instance file that should declare the parameter.
+    pub instance: PathBuf,
+    /// The `replaceNN` key.
+    pub key: String,
+    /// The parameter name used by the key, including the leading `$`.
+    pub parameter: String,
+}
+
+/// The graph of instance files reachable from a root VMF through `func_instance` entities.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstanceGraph {
+    /// The root file.
+    pub root: PathBuf,
+    /// Every loaded file (the root and all reachable instances) with its declared parameters.
+    pub files: IndexMap<PathBuf, Vec<InstanceParameter>>,
+    /// Every resolved `func_instance` reference.
+    pub edges: Vec<InstanceEdge>,
+    /// References to files that couldn't be found.
+    pub missing: Vec<MissingInstance>,
+    /// Reference cycles, each listed as the chain of files from the first to its repetition.
+    pub cycles: Vec<Vec<PathBuf>>,
+    /// `replaceNN` keys that set parameters the instance doesn't declare.
+    pub undeclared_parameters: Vec<UndeclaredParameter>,
+}
+
+impl InstanceGraph {
+    /// Builds the instance dependency graph rooted at the VMF file at `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The root VMF file.
+    /// * `resolver` - Controls where instance files are searched for.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the graph, or a `VmfError` if a file fails to load.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let graph = InstanceGraph::build("maps/your_map.vmf", &InstanceResolver::new())?;
+    /// for user in graph.dependents_of("instances/door_indicator_lights.vmf") {
+    ///     println!("Used by {}", user.display());
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn build(path: impl AsRef<Path>, resolver: &InstanceResolver) -> VmfResult<Self> {
+        VmfFile::open(path)?.instance_graph(resolver)
+    }
+
+    /// Returns `true` if every reference resolved, there are no cycles and every
+    /// replacement targets a declared parameter.
+    pub fn is_valid(&self) -> bool {
+        self.missing.is_empty() && self.cycles.is_empty() && self.undeclared_parameters.is_empty()
+    }
+
+    /// Finds the loaded file matching `path`, either exactly or by its trailing components.
+    ///
+    /// This allows queries with the value of a `file` key such as `instances/door.vmf`.
+    pub fn find_file(&self, path: impl AsRef<Path>) -> Option<&Path> {
+        let path = path.as_ref();
+        let canonical_path = canonical(path);
+        self.files
+            .keys()
+            .find(|file| **file == canonical_path || file.ends_with(path))
+            .map(PathBuf::as_path)
+    }
+
+    /// Returns the files that directly reference `path`.
+    pub fn direct_dependents_of(&self, path: impl AsRef<Path>) -> Vec<&Path> {
+        let Some(target) = self.find_file(path) else {
+            return Vec::new();
+        };
+        let mut result: Vec<&Path> = Vec::new();
+        for edge in self.edges.iter().filter(|e| e.to == target) {
+            if !result.contains(&edge.from.as_path()) {
+                result.push(&edge.from);
+            }
+        }
+        result
+    }
+
+    /// Returns every file that uses `path`, directly or through other instances.
+    pub fn dependents_of(&self, path: impl AsRef<Path>) -> Vec<&Path> {
+        let Some(target) = self.find_file(path) else {
+            return Vec::new();
+        };
+        self.walk(target, |edge| (&edge.to, &edge.from))
+    }
+
+    /// Returns every file that `path` uses, directly or through other instances.
+    pub fn dependencies_of(&self, path: impl AsRef<Path>) -> Vec<&Path> {
+        let Some(source) = self.find_file(path) else {
+            return Vec::new();
+        };
+        self.walk(source, |edge| (&edge.from, &edge.to))
+    }
+
+    /// Returns the parameters declared by the file matching `path`.
+    pub fn parameters(&self, path: impl AsRef<Path>) -> Option<&[InstanceParameter]> {
+        let file = self.find_file(path)?;
+        self.files.get(file).map(Vec::as_slice)
+    }
+
+    /// Collects every node reachable from `start` along edges oriented by `direction`,
+    /// which maps an edge to its `(from, to)` pair for the walk.
+    fn walk<'a>(
+        &'a self,
+        start: &'a Path,
+        direction: impl Fn(&'a InstanceEdge) -> (&'a PathBuf, &'a PathBuf),
+    ) -> Vec<&'a Path> {
+        let mut result: Vec<&Path> = Vec::new();
+        let mut queue = vec![start];
+        while let Some(current) = queue.pop() {
+            for edge in &self.edges {
+                let (from, to) = direction(edge);
+                if from == current && to != start && !result.contains(&to.as_path()) {
+                    result.push(to);
+                    queue.push(to);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Canonicalizes a path, falling back to the path itself if it doesn't exist.
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Depth-first traversal state for building an `InstanceGraph`.
+struct GraphBuilder<'a> {
+    resolver: &'a InstanceResolver,
+    root_dir: PathBuf,
+    stack: Vec<PathBuf>,
+    graph: InstanceGraph,
+}
+
+impl<'a> GraphBuilder<'a> {
+    fn new(resolver: &'a InstanceResolver, root: &Path) -> Self {
+        Self {
+            resolver,
+            root_dir: root.parent().map(Path::to_path_buf).unwrap_or_default(),
+            stack: Vec::new(),
+            graph: InstanceGraph {
+                root: root.to_path_buf(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Visits the file at `path`, loading it if it hasn't been seen yet.
+    fn visit(&mut self, path: PathBuf) -> VmfResult<()> {
+        if let Some(start) = self.stack.iter().position(|p| *p == path) {
+            let mut cycle = self.stack[start..].to_vec();
+            cycle.push(path);
+            self.graph.cycles.push(cycle);
+            return Ok(());
+        }
+        if self.graph.files.contains_key(&path) {
+            return Ok(());
+        }
+
+        let vmf = VmfFile::open(&path)?;
+        self.visit_loaded(path, &vmf)
+    }
+
+    /// Records `vmf` as the file at `path` and visits every instance it references.
+    fn visit_loaded(&mut self, path: PathBuf, vmf: &VmfFile) -> VmfResult<()> {
+        self.graph
+            .files
+            .insert(path.clone(), vmf.instance_parameters());
+        self.stack.push(path.clone());
+
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        for inst in vmf.entities.find_by_classname("func_instance") {
+            let file = inst.get("file").map(|f| f.trim()).unwrap_or_default();
+            if file.is_empty() {
+                continue;
+            }
+
+            let Some(resolved) = self
+                .resolver
+                .resolve(file, &[dir.as_path(), self.root_dir.as_path()])
+            else {
+                self.graph.missing.push(MissingInstance {
+                    from: path.clone(),
+                    entity_id: inst.id(),
+                    file: file.to_string(),
+                });
+                continue;
+            };
+
+            let resolved = canonical(&resolved);
+            self.graph.edges.push(InstanceEdge {
+                from: path.clone(),
+                to: resolved.clone(),
+                entity_id: inst.id(),
+            });
+            self.visit(resolved.clone())?;
+
+            let declared = &self.graph.files[&resolved];
+            for (key, value) in &inst.key_values {
+                if !key.to_ascii_lowercase().starts_with("replace") {
+                    continue;
+                }
+                let Some(parameter) = value.split_whitespace().next() else {
+                    continue;
+                };
+                // `#material` replacements don't need a declaration.
+                if !parameter.starts_with('$') {
+                    continue;
+                }
+                if !declared
+                    .iter()
+                    .any(|p| p.name.eq_ignore_ascii_case(parameter))
+                {
+                    self.graph.undeclared_parameters.push(UndeclaredParameter {
+                        from: path.clone(),
+                        entity_id: inst.id(),
+                        instance: resolved.clone(),
+                        key: key.clone(),
+                        parameter: parameter.to_string(),
+                    });
+                }
+            }
+        }
+
+        self.stack.pop();
+        Ok(())
+    }
+}
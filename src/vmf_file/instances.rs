@@ -0,0 +1,570 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::{IdAllocator, VmfFile};
+use crate::errors::{VmfError, VmfResult};
+use crate::geometry::{TextureLock, Transform};
+use crate::prelude::{Entity, Solid};
+use crate::utils::{format_float, split_connection};
+use crate::vmf::overlays::remap_side_ids;
+
+/// Keys whose values are entity names and get the instance's name fixup applied.
+///
+/// The compiler decides this from the FGD; without one, these are the keys that
+/// reference other entities by name in the stock entities.
+const DEFAULT_FIXUP_KEYS: &[&str] = &[
+    "targetname",
+    "parentname",
+    "target",
+    "filtername",
+    "damagefilter",
+    "landmark",
+    "lightingorigin",
+    "texturename",
+    "measuretarget",
+    "measurereference",
+    "targetreference",
+];
+
+/// How the names of entities inside an instance are made unique.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FixupStyle {
+    /// Names are prefixed with the instance name: `inst-door`.
+    #[default]
+    Prefix,
+    /// Names are suffixed with the instance name: `door-inst`.
+    Postfix,
+    /// Names are left unchanged.
+    None,
+}
+
+impl FixupStyle {
+    /// Parses the value of a `func_instance`'s `fixup_style` key (`0`, `1` or `2`).
+    pub fn from_key(value: &str) -> Self {
+        match value.trim() {
+            "1" => FixupStyle::Postfix,
+            "2" => FixupStyle::None,
+            _ => FixupStyle::Prefix,
+        }
+    }
+
+    /// Applies the fixup to `name`, using `fixup_name` as the instance name.
+    ///
+    /// Names starting with `@` or `!` are global or special names and are never changed.
+    pub fn apply(self, name: &str, fixup_name: &str) -> String {
+        if name.is_empty() || name.starts_with('@') || name.starts_with('!') {
+            return name.to_string();
+        }
+
+        match self {
+            FixupStyle::Prefix => format!("{}-{}", fixup_name, name),
+            FixupStyle::Postfix => format!("{}-{}", name, fixup_name),
+            FixupStyle::None => name.to_string(),
+        }
+    }
+}
+
+/// Locates the VMF files referenced by `func_instance` entities and controls how they are merged.
+///
+/// A `file` key is first looked up relative to the map containing the `func_instance`,
+/// then relative to the top-level map, then in each search path in order.
+#[derive(Debug, Clone, Default)]
+pub struct InstanceResolver {
+    /// Extra directories to search for instance files.
+    pub search_paths: Vec<PathBuf>,
+    /// Extra keys, on top of the built-in ones, whose values are entity names to fix up.
+    pub fixup_keys: Vec<String>,
+}
+
+impl InstanceResolver {
+    /// Creates a resolver that only looks relative to the maps themselves.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a directory to search for instance files.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The directory, usually the game's `sdk_content/maps` folder.
+    pub fn with_search_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.search_paths.push(path.into());
+        self
+    }
+
+    /// Adds a key whose value should be treated as an entity name during name fixup.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key name, compared case-insensitively.
+    pub fn with_fixup_key(mut self, key: impl Into<String>) -> Self {
+        self.fixup_keys.push(key.into());
+        self
+    }
+
+    /// Resolves the `file` key of a `func_instance` to an existing path.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The value of the `file` key.
+    /// * `base_dirs` - Directories to try before the search paths.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the first existing path, or `None` if the file can't be found.
+    pub fn resolve(&self, file: &str, base_dirs: &[&Path]) -> Option<PathBuf> {
+        let file = file.replace('\\', "/");
+        base_dirs
+            .iter()
+            .copied()
+            .chain(self.search_paths.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(&file))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// Returns `true` if `key` holds an entity name that should be fixed up.
+    fn is_fixup_key(&self, key: &str) -> bool {
+        DEFAULT_FIXUP_KEYS
+            .iter()
+            .copied()
+            .chain(self.fixup_keys.iter().map(String::as_str))
+            .any(|k| k.eq_ignore_ascii_case(key))
+    }
+}
+
+impl VmfFile {
+    /// Collapses every `func_instance` into the map, the way VBSP does before compiling.
+    ///
+    /// Each referenced file is loaded, its own instances are collapsed recursively, and its
+    /// world solids and entities are moved by the instance's `origin` and `angles` and
+    /// merged into this file with fresh IDs. Entity names and connection targets get the
+    /// instance's name fixup, `$parameter` values from `replaceNN` keys are substituted, and
+    /// `#material` replacements are applied to brush faces. Inputs sent to
+    /// `instance:name;Input` and outputs relayed through `func_instance_io_proxy` are
+    /// rewired to the real entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolver` - Controls where instance files are searched for.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the number of top-level instances collapsed, or a `VmfError`
+    /// if a file can't be found, fails to parse, or includes itself. On error the map is
+    /// left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("maps/your_map.vmf")?;
+    /// let resolver = InstanceResolver::new().with_search_path("sdk_content/maps");
+    /// let collapsed = vmf.collapse_instances(&resolver)?;
+    /// println!("Collapsed {} instances", collapsed);
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn collapse_instances(&mut self, resolver: &InstanceResolver) -> VmfResult<usize> {
+        let root_dir = self
+            .path
+            .as_ref()
+            .and_then(|p| Path::new(p).parent())
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let mut collapser = Collapser {
+            resolver,
+            root_dir: root_dir.clone(),
+            cache: HashMap::new(),
+            stack: Vec::new(),
+            auto_names: 0,
+        };
+
+        // Work on a copy so a failure leaves the map untouched.
+        let mut collapsed_map = self.clone();
+        let collapsed = collapser.collapse(&mut collapsed_map, &root_dir)?;
+        *self = collapsed_map;
+        Ok(collapsed)
+    }
+}
+
+/// State shared across one `collapse_instances` call.
+struct Collapser<'a> {
+    resolver: &'a InstanceResolver,
+    root_dir: PathBuf,
+    /// Instance files that were already loaded and had their own instances collapsed.
+    cache: HashMap<PathBuf, VmfFile>,
+    /// The chain of files currently being collapsed, used for cycle detection.
+    stack: Vec<PathBuf>,
+    /// Counter for the names given to unnamed instances.
+    auto_names: usize,
+}
+
+/// The per-instance settings read from a `func_instance`.
+struct InstanceParams {
+    fixup_name: String,
+    style: FixupStyle,
+    transform: Transform,
+    /// `$parameter` replacements, longest name first.
+    replacements: Vec<(String, String)>,
+    /// `#material` replacements.
+    materials: Vec<(String, String)>,
+    /// The connections of the `func_instance` itself, used for `func_instance_io_proxy` relays.
+    proxy_outputs: Vec<(String, String)>,
+}
+
+impl Collapser<'_> {
+    /// Collapses all instances in `map`, whose file lives in `map_dir`.
+    fn collapse(&mut self, map: &mut VmfFile, map_dir: &Path) -> VmfResult<usize> {
+        let mut ids = map.id_allocator();
+        let (instances, others): (Vec<Entity>, Vec<Entity>) = map
+            .entities
+            .drain(..)
+            .partition(|ent| ent.classname() == Some("func_instance"));
+        map.entities.extend(others);
+
+        let mut fixups = HashMap::new();
+        let mut collapsed = 0;
+        for inst in &instances {
+            let file = inst.get("file").map(|f| f.trim()).unwrap_or_default();
+            if file.is_empty() {
+                // VBSP silently drops instances that don't reference a file.
+                continue;
+            }
+
+            let params = self.read_params(inst);
+            if let Some(name) = inst.targetname() {
+                // Entity names are case-insensitive, so key the fixups by lowercase name.
+                let name = name.to_ascii_lowercase();
+                fixups.insert(name, (params.fixup_name.clone(), params.style));
+            }
+
+            let mut instance = self.load(file, map_dir)?;
+            merge_instance(map, &mut instance, &params, self.resolver, &mut ids)?;
+            collapsed += 1;
+        }
+
+        rewire_instance_inputs(map, &fixups);
+        Ok(collapsed)
+    }
+
+    /// Loads an instance file and collapses its own nested instances.
+    fn load(&mut self, file: &str, map_dir: &Path) -> VmfResult<VmfFile> {
+        let path = self
+            .resolver
+            .resolve(file, &[map_dir, &self.root_dir])
+            .ok_or_else(|| VmfError::InstanceNotFound(file.to_string()))?;
+        let path = path.canonicalize().unwrap_or(path);
+
+        if self.stack.contains(&path) {
+            let chain = self
+                .stack
+                .iter()
+                .chain(std::iter::once(&path))
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(VmfError::InstanceCycle(chain));
+        }
+
+        if let Some(cached) = self.cache.get(&path) {
+            return Ok(cached.clone());
+        }
+
+        let mut instance = VmfFile::open(&path)?;
+        let instance_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        self.stack.push(path.clone());
+        let result = self.collapse(&mut instance, &instance_dir);
+        self.stack.pop();
+        result?;
+
+        self.cache.insert(path, instance.clone());
+        Ok(instance)
+    }
+
+    /// Reads the fixup, transform and replacement settings of a `func_instance`.
+    fn read_params(&mut self, inst: &Entity) -> InstanceParams {
+        let style = inst
+            .get("fixup_style")
+            .map(|s| FixupStyle::from_key(s))
+            .unwrap_or_default();
+
+        let fixup_name = match inst.targetname().filter(|name| !name.is_empty()) {
+            Some(name) => name.to_string(),
+            None => {
+                self.auto_names += 1;
+                format!("InstanceAuto{}", self.auto_names)
+            }
+        };
+
+        let transform = Transform::from_origin_angles(
+            inst.origin().unwrap_or_default(),
+            inst.angles().unwrap_or_default(),
+        );
+
+        let mut replacements = Vec::new();
+        let mut materials = Vec::new();
+        for (key, value) in &inst.key_values {
+            if !key.to_ascii_lowercase().starts_with("replace") {
+                continue;
+            }
+            let Some((name, replacement)) = value.trim().split_once(char::is_whitespace) else {
+                continue;
+            };
+            let replacement = replacement.trim().to_string();
+            if let Some(material) = name.strip_prefix('#') {
+                materials.push((material.to_string(), replacement));
+            } else if name.starts_with('$') {
+                replacements.push((name.to_string(), replacement));
+            }
+        }
+        // Substitute longer names first so `$door` doesn't clobber `$door_speed`.
+        replacements.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+
+        InstanceParams {
+            fixup_name,
+            style,
+            transform,
+            replacements,
+            materials,
+            proxy_outputs: inst.connections.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Merges a loaded (and already collapsed) instance into `map`.
+fn merge_instance(
+    map: &mut VmfFile,
+    instance: &mut VmfFile,
+    params: &InstanceParams,
+    resolver: &InstanceResolver,
+    ids: &mut IdAllocator,
+) -> VmfResult<()> {
+    let mut side_ids = HashMap::new();
+
+    let proxies: Vec<String> = instance
+        .entities
+        .iter()
+        .filter(|ent| ent.classname() == Some("func_instance_io_proxy"))
+        .filter_map(|ent| ent.targetname().map(str::to_string))
+        .collect();
+
+    for solid in instance
+        .world
+        .solids
+        .iter_mut()
+        .chain(instance.world.hidden.iter_mut())
+    {
+        prepare_solid(solid, params, ids, &mut side_ids)?;
+    }
+    map.world.solids.append(&mut instance.world.solids);
+    map.world.hidden.append(&mut instance.world.hidden);
+
+    let entities = instance.entities.drain(..).map(|ent| (ent, false));
+    let hiddens = instance.hiddens.drain(..).map(|ent| (ent, true));
+    let mut merged = Vec::new();
+    for (mut ent, hidden) in entities.chain(hiddens) {
+        if matches!(
+            ent.classname(),
+            Some("func_instance_parms" | "func_instance_io_proxy")
+        ) {
+            continue;
+        }
+
+        relay_proxy_outputs(&mut ent, &proxies, params);
+        substitute_parameters(&mut ent, params);
+        fix_up_names(&mut ent, params, resolver);
+        ent.apply_transform(&params.transform, TextureLock::WithScale)?;
+
+        ent.key_values
+            .insert("id".to_string(), ids.next_object_id().to_string());
+        ent.editor.visgroup_id = None;
+        ent.editor.group_id = None;
+        if let Some(solids) = &mut ent.solids {
+            for solid in solids {
+                // The entity transform already moved these solids.
+                prepare_solid_ids(solid, params, ids, &mut side_ids);
+            }
+        }
+        merged.push((ent, hidden));
+    }
+
+    for (mut ent, hidden) in merged {
+        remap_side_ids(&mut ent, &side_ids);
+        if hidden {
+            map.hiddens.push(ent);
+        } else {
+            map.entities.push(ent);
+        }
+    }
+    Ok(())
+}
+
+/// Transforms a world solid of the instance and gives it fresh IDs.
+fn prepare_solid(
+    solid: &mut Solid,
+    params: &InstanceParams,
+    ids: &mut IdAllocator,
+    side_ids: &mut HashMap<u32, Vec<u32>>,
+) -> VmfResult<()> {
+    solid.apply_transform(&params.transform, TextureLock::WithScale)?;
+    prepare_solid_ids(solid, params, ids, side_ids);
+    Ok(())
+}
+
+/// Gives a solid and its sides fresh IDs and applies material replacements.
+fn prepare_solid_ids(
+    solid: &mut Solid,
+    params: &InstanceParams,
+    ids: &mut IdAllocator,
+    side_ids: &mut HashMap<u32, Vec<u32>>,
+) {
+    solid.id = ids.next_object_id();
+    solid.editor.visgroup_id = None;
+    solid.editor.group_id = None;
+    for side in &mut solid.sides {
+        let new_id = ids.next_side_id();
+        side_ids.insert(side.id, vec![new_id]);
+        side.id = new_id;
+
+        if let Some((_, replacement)) = params
+            .materials
+            .iter()
+            .find(|(from, _)| from.eq_ignore_ascii_case(&side.material))
+        {
+            side.material = replacement.clone();
+        }
+    }
+}
+
+/// Replaces `$parameter` occurrences in key values and connections.
+fn substitute_parameters(ent: &mut Entity, params: &InstanceParams) {
+    if params.replacements.is_empty() {
+        return;
+    }
+
+    for value in ent.key_values.values_mut() {
+        *value = replace_parameters(value, &params.replacements);
+    }
+    if let Some(connections) = &mut ent.connections {
+        for (_, value) in connections {
+            *value = replace_parameters(value, &params.replacements);
+        }
+    }
+}
+
+/// Replaces each parameter name in `value`, ignoring ASCII case like the compiler does.
+fn replace_parameters(value: &str, replacements: &[(String, String)]) -> String {
+    let mut result = value.to_string();
+    for (name, replacement) in replacements {
+        let mut search_from = 0;
+        while let Some(pos) = result[search_from..]
+            .to_ascii_lowercase()
+            .find(&name.to_ascii_lowercase())
+        {
+            let start = search_from + pos;
+            result.replace_range(start..start + name.len(), replacement);
+            search_from = start + replacement.len();
+        }
+    }
+    result
+}
+
+/// Applies the instance's name fixup to name keys and connection targets.
+fn fix_up_names(ent: &mut Entity, params: &InstanceParams, resolver: &InstanceResolver) {
+    if params.style == FixupStyle::None {
+        return;
+    }
+
+    for (key, value) in ent.key_values.iter_mut() {
+        if resolver.is_fixup_key(key) {
+            *value = params.style.apply(value, &params.fixup_name);
+        }
+    }
+
+    if let Some(connections) = &mut ent.connections {
+        for (_, value) in connections {
+            let (separator, mut fields) = split_connection(value);
+            if let Some(target) = fields.first_mut() {
+                *target = params.style.apply(target, &params.fixup_name);
+            }
+            *value = fields.join(&separator.to_string());
+        }
+    }
+}
+
+/// Replaces connections that target a `func_instance_io_proxy` with the connections
+/// the parent map attached to the matching `instance:proxy;Relay` output.
+fn relay_proxy_outputs(ent: &mut Entity, proxies: &[String], params: &InstanceParams) {
+    let Some(connections) = ent.connections.take() else {
+        return;
+    };
+
+    let mut relayed = Vec::with_capacity(connections.len());
+    for (output, value) in connections {
+        let (separator, fields) = split_connection(&value);
+        let target = fields.first().map(String::as_str).unwrap_or_default();
+        if !proxies.iter().any(|p| p.eq_ignore_ascii_case(target)) {
+            relayed.push((output, value));
+            continue;
+        }
+
+        let relay = fields.get(1).map(String::as_str).unwrap_or_default();
+        let proxy_output = format!("instance:{};{}", target, relay);
+        let delay = fields
+            .get(3)
+            .and_then(|d| d.trim().parse::<f64>().ok())
+            .unwrap_or(0.0);
+
+        for (parent_output, parent_value) in &params.proxy_outputs {
+            if !parent_output.eq_ignore_ascii_case(&proxy_output) {
+                continue;
+            }
+            // The parent's targets are already in the parent's namespace, so they must
+            // not be fixed up again: mark them global and strip the marker afterwards.
+            let (_, mut parent_fields) = split_connection(parent_value);
+            parent_fields.resize(5, String::new());
+            let parent_delay = parent_fields[3].trim().parse::<f64>().unwrap_or(0.0);
+            parent_fields[3] = format_float(delay + parent_delay);
+            parent_fields[0] = format!("{}{}", PARENT_NAME_MARKER, parent_fields[0]);
+            relayed.push((output.clone(), parent_fields.join(&separator.to_string())));
+        }
+    }
+
+    ent.connections = (!relayed.is_empty()).then_some(relayed);
+}
+
+/// Prefix used to protect relayed parent targets from name fixup.
+const PARENT_NAME_MARKER: &str = "!__parent__:";
+
+/// Rewrites inputs sent to `instance:name;Input` on a `func_instance` so they reach the
+/// fixed-up entity inside the collapsed instance, and strips the markers left by
+/// relayed proxy outputs.
+fn rewire_instance_inputs(map: &mut VmfFile, fixups: &HashMap<String, (String, FixupStyle)>) {
+    for ent in map.entities.iter_mut().chain(map.hiddens.iter_mut()) {
+        let Some(connections) = &mut ent.connections else {
+            continue;
+        };
+
+        for (_, value) in connections.iter_mut() {
+            let (separator, mut fields) = split_connection(value);
+            if fields.len() < 2 {
+                continue;
+            }
+
+            if let Some(target) = fields[0].strip_prefix(PARENT_NAME_MARKER) {
+                fields[0] = target.to_string();
+            } else if let Some((fixup_name, style)) = fixups.get(&fields[0].to_ascii_lowercase())
+                && let Some(rest) = fields[1].strip_prefix("instance:")
+                && let Some((inner_name, input)) = rest.split_once(';')
+            {
+                let (target, input) = (style.apply(inner_name, fixup_name), input.to_string());
+                fields[0] = target;
+                fields[1] = input;
+            } else {
+                continue;
+            }
+            *value = fields.join(&separator.to_string());
+        }
+    }
+}
@@ -0,0 +1,418 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use super::VmfFile;
+use crate::errors::{VmfError, VmfResult};
+use crate::prelude::Entity;
+use crate::utils::{format_float, json_escape, split_connection};
+
+/// An entity in an `IoGraph`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoNode {
+    /// The ID of the entity.
+    pub entity_id: u64,
+    /// The classname of the entity.
+    pub classname: String,
+    /// The targetname of the entity, if it has one.
+    pub targetname: Option<String>,
+}
+
+/// What the target of an output resolved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IoTarget {
+    /// The IDs of the entities the output fires at, matched by targetname, or by
+    /// classname if no targetname matches. `!self` resolves to the entity itself.
+    Entities(Vec<u64>),
+    /// A target only known while the map runs, such as `!activator` or `!caller`.
+    Runtime(String),
+    /// No entity matches the target.
+    Missing,
+}
+
+/// One output connection, from an output of one entity to an input of its targets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IoEdge {
+    /// The ID of the entity firing the output.
+    pub from: u64,
+    /// The name of the output, such as `OnTrigger`.
+    pub output: String,
+    /// The target as written, which may contain wildcards.
+    pub target: String,
+    /// The name of the input, such as `Open`.
+    pub input: String,
+    /// The parameter passed to the input, empty for none.
+    pub param: String,
+    /// The delay before the input fires, in seconds.
+    pub delay: f32,
+    /// How many times the output can fire, or -1 for no limit.
+    pub fire_limit: i32,
+    /// The entities the target resolved to.
+    pub to: IoTarget,
+}
+
+/// The input/output logic of a map, as built by `VmfFile::io_graph`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IoGraph {
+    /// Every entity, in document order.
+    pub nodes: Vec<IoNode>,
+    /// Every output connection, in document order.
+    pub edges: Vec<IoEdge>,
+}
+
+impl VmfFile {
+    /// Builds the graph of output connections between entities, including hidden ones.
+    ///
+    /// Targets are resolved the way the engine does: by targetname, case-insensitively
+    /// and with a trailing `*` matching any suffix, then by classname if no targetname
+    /// matches. `!self`
+    /// resolves to the firing entity, and other names starting with `!`, such as
+    /// `!activator` and `!caller`, are only known at runtime.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the graph, or a `VmfError` if a connection doesn't
+    /// have five fields, its delay isn't a finite number or its fire limit isn't a number.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// let graph = vmf.io_graph()?;
+    /// for edge in graph.dangling() {
+    ///     eprintln!("entity {}: {} has no target '{}'", edge.from, edge.output, edge.target);
+    /// }
+    /// std::fs::write("io.dot", graph.to_dot())?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn io_graph(&self) -> VmfResult<IoGraph> {
+        let entities: Vec<&Entity> = self.entities.iter().chain(self.hiddens.iter()).collect();
+        let nodes: Vec<IoNode> = entities
+            .iter()
+            .map(|ent| IoNode {
+                entity_id: ent.id(),
+                classname: ent.classname().unwrap_or_default().to_string(),
+                targetname: ent
+                    .targetname()
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string),
+            })
+            .collect();
+
+        let mut edges = Vec::new();
+        for ent in &entities {
+            for (output, value) in ent.connections.iter().flatten() {
+                let (_, fields) = split_connection(value);
+                let [target, input, param, delay, fire_limit] = fields.as_slice() else {
+                    return Err(VmfError::InvalidFormat(format!(
+                        "Connection '{}' of entity {} doesn't have 5 fields",
+                        output,
+                        ent.id()
+                    )));
+                };
+                let invalid = |field: &str| {
+                    VmfError::InvalidFormat(format!(
+                        "Connection '{}' of entity {} has an invalid {}",
+                        output,
+                        ent.id(),
+                        field
+                    ))
+                };
+                edges.push(IoEdge {
+                    from: ent.id(),
+                    output: output.clone(),
+                    target: target.clone(),
+                    input: input.clone(),
+                    param: param.clone(),
+                    delay: delay
+                        .trim()
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|delay| delay.is_finite())
+                        .ok_or_else(|| invalid("delay"))?,
+                    fire_limit: fire_limit
+                        .trim()
+                        .parse()
+                        .map_err(|_| invalid("fire limit"))?,
+                    to: resolve(target, ent.id(), &nodes),
+                });
+            }
+        }
+        Ok(IoGraph { nodes, edges })
+    }
+}
+
+/// Resolves the target of an output fired by the entity `from`.
+fn resolve(target: &str, from: u64, nodes: &[IoNode]) -> IoTarget {
+    let target = target.trim();
+    if target.eq_ignore_ascii_case("!self") {
+        return IoTarget::Entities(vec![from]);
+    }
+    if target.starts_with('!') {
+        return IoTarget::Runtime(target.to_string());
+    }
+
+    let by_name: Vec<u64> = nodes
+        .iter()
+        .filter(|node| {
+            node.targetname
+                .as_deref()
+                .is_some_and(|name| matches_target(target, name))
+        })
+        .map(|node| node.entity_id)
+        .collect();
+    let ids = if by_name.is_empty() {
+        nodes
+            .iter()
+            .filter(|node| matches_target(target, &node.classname))
+            .map(|node| node.entity_id)
+            .collect()
+    } else {
+        by_name
+    };
+    if ids.is_empty() {
+        IoTarget::Missing
+    } else {
+        IoTarget::Entities(ids)
+    }
+}
+
+/// Matches a name against an output target case-insensitively. Like the engine,
+/// only a trailing `*` is a wildcard; `*` and `?` anywhere else are literal.
+fn matches_target(target: &str, name: &str) -> bool {
+    match target.strip_suffix('*') {
+        Some(prefix) => name
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix)),
+        None => name.eq_ignore_ascii_case(target),
+    }
+}
+
+impl IoGraph {
+    /// Returns the node of an entity.
+    pub fn node(&self, entity_id: u64) -> Option<&IoNode> {
+        self.nodes.iter().find(|node| node.entity_id == entity_id)
+    }
+
+    /// Returns the outputs fired by an entity.
+    pub fn outputs_of(&self, entity_id: u64) -> impl Iterator<Item = &IoEdge> + '_ {
+        self.edges.iter().filter(move |edge| edge.from == entity_id)
+    }
+
+    /// Returns the outputs that fire at an entity, not counting runtime targets.
+    pub fn inputs_of(&self, entity_id: u64) -> impl Iterator<Item = &IoEdge> + '_ {
+        self.edges.iter().filter(move |edge| match &edge.to {
+            IoTarget::Entities(ids) => ids.contains(&entity_id),
+            _ => false,
+        })
+    }
+
+    /// Returns the outputs whose target matches no entity.
+    pub fn dangling(&self) -> impl Iterator<Item = &IoEdge> + '_ {
+        self.edges
+            .iter()
+            .filter(|edge| edge.to == IoTarget::Missing)
+    }
+
+    /// Returns the named entities that no output fires at.
+    ///
+    /// Outputs fired at `!self` don't count, and neither do runtime targets, which
+    /// can't be resolved ahead of time.
+    pub fn untargeted(&self) -> Vec<&IoNode> {
+        let targeted: HashSet<u64> = self
+            .edges
+            .iter()
+            .filter(|edge| !edge.target.trim().eq_ignore_ascii_case("!self"))
+            .filter_map(|edge| match &edge.to {
+                IoTarget::Entities(ids) => Some(ids),
+                _ => None,
+            })
+            .flatten()
+            .copied()
+            .collect();
+        self.nodes
+            .iter()
+            .filter(|node| node.targetname.is_some() && !targeted.contains(&node.entity_id))
+            .collect()
+    }
+
+    /// Writes the graph in Graphviz DOT format.
+    ///
+    /// Only entities with connections, in or out, are drawn. Each entity is labeled
+    /// with its classname and targetname, and each edge with its output, input,
+    /// parameter, delay and fire limit. Missing and runtime targets get a node of
+    /// their own, drawn dashed.
+    ///
+    /// # Returns
+    ///
+    /// The DOT source, for example to render with `dot -Tsvg`.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph io {\n    rankdir=LR;\n    node [shape=box];\n");
+
+        let mut connected: HashSet<u64> = HashSet::new();
+        for edge in &self.edges {
+            connected.insert(edge.from);
+            if let IoTarget::Entities(ids) = &edge.to {
+                connected.extend(ids);
+            }
+        }
+        for node in self
+            .nodes
+            .iter()
+            .filter(|n| connected.contains(&n.entity_id))
+        {
+            let label = match &node.targetname {
+                Some(name) => format!("{}\n{}", name, node.classname),
+                None => node.classname.clone(),
+            };
+            let _ = writeln!(
+                dot,
+                "    \"e{}\" [label=\"{}\"];",
+                node.entity_id,
+                dot_escape(&label)
+            );
+        }
+
+        let mut unresolved: Vec<String> = Vec::new();
+        for edge in &self.edges {
+            let label = dot_escape(&edge_label(edge));
+            let targets = match &edge.to {
+                IoTarget::Entities(ids) => ids.iter().map(|id| format!("e{}", id)).collect(),
+                IoTarget::Runtime(name) => {
+                    vec![unresolved_node(&mut dot, &mut unresolved, "runtime", name)]
+                }
+                IoTarget::Missing => vec![unresolved_node(
+                    &mut dot,
+                    &mut unresolved,
+                    "missing",
+                    &edge.target,
+                )],
+            };
+            for target in targets {
+                let _ = writeln!(
+                    dot,
+                    "    \"e{}\" -> \"{}\" [label=\"{}\"];",
+                    edge.from,
+                    dot_escape(&target),
+                    label
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Writes the graph as JSON.
+    ///
+    /// The document has a `nodes` array of entities, an `edges` array of connections
+    /// whose `status` is `resolved`, `runtime` or `missing`, and the `dangling` edge
+    /// indices and `untargeted` entity IDs reported by `dangling` and `untargeted`.
+    ///
+    /// # Returns
+    ///
+    /// The JSON document.
+    pub fn to_json(&self) -> String {
+        let nodes: Vec<String> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let targetname = node.targetname.as_ref().map_or("null".to_string(), |name| {
+                    format!("\"{}\"", json_escape(name))
+                });
+                format!(
+                    "{{\"id\":{},\"classname\":\"{}\",\"targetname\":{}}}",
+                    node.entity_id,
+                    json_escape(&node.classname),
+                    targetname
+                )
+            })
+            .collect();
+        let edges: Vec<String> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                let (status, to) = match &edge.to {
+                    IoTarget::Entities(ids) => (
+                        "resolved",
+                        ids.iter().map(u64::to_string).collect::<Vec<_>>(),
+                    ),
+                    IoTarget::Runtime(_) => ("runtime", Vec::new()),
+                    IoTarget::Missing => ("missing", Vec::new()),
+                };
+                format!(
+                    "{{\"from\":{},\"output\":\"{}\",\"target\":\"{}\",\"input\":\"{}\",\
+                     \"param\":\"{}\",\"delay\":{},\"fire_limit\":{},\"status\":\"{}\",\"to\":[{}]}}",
+                    edge.from,
+                    json_escape(&edge.output),
+                    json_escape(&edge.target),
+                    json_escape(&edge.input),
+                    json_escape(&edge.param),
+                    format_float(f64::from(edge.delay)),
+                    edge.fire_limit,
+                    status,
+                    to.join(",")
+                )
+            })
+            .collect();
+        let dangling: Vec<String> = self
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|(_, edge)| edge.to == IoTarget::Missing)
+            .map(|(i, _)| i.to_string())
+            .collect();
+        let untargeted: Vec<String> = self
+            .untargeted()
+            .iter()
+            .map(|node| node.entity_id.to_string())
+            .collect();
+        format!(
+            "{{\"nodes\":[{}],\"edges\":[{}],\"dangling\":[{}],\"untargeted\":[{}]}}",
+            nodes.join(","),
+            edges.join(","),
+            dangling.join(","),
+            untargeted.join(",")
+        )
+    }
+}
+
+/// Adds a dashed node for a `kind` of unresolved target the first time it is seen,
+/// returning its DOT identifier.
+fn unresolved_node(dot: &mut String, seen: &mut Vec<String>, kind: &str, name: &str) -> String {
+    let key = format!("{}:{}", kind, name);
+    if !seen.contains(&key) {
+        let color = if kind == "missing" { "red" } else { "gray" };
+        let _ = writeln!(
+            dot,
+            "    \"{}\" [label=\"{}\", style=dashed, color={}];",
+            dot_escape(&key),
+            dot_escape(name),
+            color
+        );
+        seen.push(key.clone());
+    }
+    key
+}
+
+/// Describes a connection as `Output -> Input(param) +delay xlimit`.
+fn edge_label(edge: &IoEdge) -> String {
+    let mut label = format!("{} -> {}", edge.output, edge.input);
+    if !edge.param.is_empty() {
+        let _ = write!(label, "({})", edge.param);
+    }
+    if edge.delay != 0.0 {
+        let _ = write!(label, " +{}s", format_float(f64::from(edge.delay)));
+    }
+    if edge.fire_limit >= 0 {
+        let _ = write!(label, " x{}", edge.fire_limit);
+    }
+    label
+}
+
+/// Escapes a string for use inside a quoted DOT identifier or label.
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,348 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+
+use super::VmfFile;
+use crate::errors::{VmfError, VmfResult};
+use crate::geometry::{Aabb, ON_EPSILON, Plane, Vector3};
+use crate::utils::format_float;
+
+/// Materials whose brushes don't seal the map, matched case-insensitively.
+pub const NON_SEALING_MATERIALS: [&str; 10] = [
+    "TOOLS/TOOLSTRIGGER",
+    "TOOLS/TOOLSCLIP",
+    "TOOLS/TOOLSPLAYERCLIP",
+    "TOOLS/TOOLSNPCCLIP",
+    "TOOLS/TOOLSHINT",
+    "TOOLS/TOOLSSKIP",
+    "TOOLS/TOOLSAREAPORTAL",
+    "TOOLS/TOOLSOCCLUDER",
+    "TOOLS/TOOLSINVISIBLE",
+    "TOOLS/TOOLSFOG",
+];
+
+/// The largest grid `VmfFile::find_leaks` builds, in cells. The flood takes about
+/// four bytes per cell, so this keeps it around 128 MB.
+const MAX_CELLS: usize = 1 << 25;
+
+/// A cell the flood fill hasn't reached.
+const UNREACHED: u32 = u32::MAX;
+
+/// Controls how `VmfFile::find_leaks` looks for leaks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeakOptions {
+    /// The size of the voxels the world is sampled with. Gaps narrower than this can
+    /// be missed, and walls thinner than it can be reported as leaks. Defaults to 16.
+    pub voxel_size: f64,
+    /// Brushes with any side in one of these materials don't seal.
+    /// Defaults to `NON_SEALING_MATERIALS`.
+    pub non_sealing_materials: Vec<String>,
+}
+
+impl Default for LeakOptions {
+    fn default() -> Self {
+        Self {
+            voxel_size: 16.0,
+            non_sealing_materials: NON_SEALING_MATERIALS
+                .iter()
+                .map(|m| m.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// A point entity that can reach the void.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leak {
+    /// The ID of the entity.
+    pub entity_id: u64,
+    /// The class name of the entity.
+    pub classname: Option<String>,
+    /// The origin of the entity.
+    pub origin: Vector3,
+    /// A path from the entity out of the map, starting at its origin.
+    pub path: Vec<Vector3>,
+}
+
+impl Leak {
+    /// Formats the path as a pointfile, one `x y z` point per line, which Hammer
+    /// loads with Map > Load Pointfile.
+    pub fn to_pointfile(&self) -> String {
+        let mut output = String::new();
+        for point in &self.path {
+            output.push_str(&format!(
+                "{} {} {}\n",
+                format_float(point.x),
+                format_float(point.y),
+                format_float(point.z)
+            ));
+        }
+        output
+    }
+
+    /// Writes the path as a pointfile, usually next to the map as `<map>.lin`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if the file can't be written.
+    pub fn write_pointfile(&self, path: impl AsRef<Path>) -> VmfResult<()> {
+        fs::write(path, self.to_pointfile())?;
+        Ok(())
+    }
+}
+
+impl VmfFile {
+    /// Looks for leaks without compiling the map.
+    ///
+    /// The sealing world brushes are sampled on a voxel grid and the void around them
+    /// is flood-filled. Every point entity the void reaches leaks, like the entity
+    /// VBSP reports. Brush entities (including `func_detail`), hidden objects,
+    /// displacement brushes and brushes with a non-sealing material don't seal.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The voxel size and the non-sealing materials.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing a leak for every point entity that can reach the void,
+    /// in entity order and empty if the map is sealed, or a `VmfError` if a plane is
+    /// malformed or the grid would be too large.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// if let Some(leak) = vmf.find_leaks(&LeakOptions::default())?.first() {
+    ///     println!("entity {} leaks", leak.entity_id);
+    ///     leak.write_pointfile("your_map.lin")?;
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn find_leaks(&self, options: &LeakOptions) -> VmfResult<Vec<Leak>> {
+        if options.voxel_size.is_nan() || options.voxel_size <= 0.0 {
+            return Err(VmfError::InvalidFormat(format!(
+                "Invalid voxel size {}",
+                options.voxel_size
+            )));
+        }
+
+        let mut brushes: Vec<(Vec<Plane>, Aabb)> = Vec::new();
+        for solid in &self.world.solids {
+            let seals = solid.sides.iter().all(|side| {
+                side.dispinfo.is_none()
+                    && !options
+                        .non_sealing_materials
+                        .iter()
+                        .any(|m| m.eq_ignore_ascii_case(&side.material))
+            });
+            if !seals {
+                continue;
+            }
+            if let Some(bounds) = solid.bounds()? {
+                brushes.push((solid.planes()?, bounds));
+            }
+        }
+
+        let grid = match brushes.iter().map(|(_, b)| *b).reduce(|a, b| a.union(&b)) {
+            Some(bounds) => Some(Grid::new(&bounds, options.voxel_size)?),
+            None => None,
+        };
+        let reached = match &grid {
+            Some(grid) => grid.flood(&brushes),
+            None => Vec::new(),
+        };
+
+        let mut leaks = Vec::new();
+        for ent in self.entities.iter() {
+            if ent.is_hidden || ent.solids.as_ref().is_some_and(|s| !s.is_empty()) {
+                continue;
+            }
+            let Some(origin) = ent.origin() else {
+                continue;
+            };
+            let path = match &grid {
+                Some(grid) => match grid.cell_at(origin) {
+                    Some(cell) => match grid.path(&reached, cell, origin) {
+                        Some(path) => path,
+                        None => continue,
+                    },
+                    // Outside the grid is the void already.
+                    None => vec![origin],
+                },
+                None => vec![origin],
+            };
+            leaks.push(Leak {
+                entity_id: ent.id(),
+                classname: ent.classname().map(str::to_string),
+                origin,
+                path,
+            });
+        }
+        Ok(leaks)
+    }
+}
+
+/// A voxel grid around the sealing brushes, with a margin of one empty cell.
+struct Grid {
+    origin: Vector3,
+    size: f64,
+    dims: [usize; 3],
+}
+
+impl Grid {
+    fn new(bounds: &Aabb, size: f64) -> VmfResult<Self> {
+        let min = Vector3::new(
+            (bounds.min.x / size).floor() - 1.0,
+            (bounds.min.y / size).floor() - 1.0,
+            (bounds.min.z / size).floor() - 1.0,
+        );
+        let max = Vector3::new(
+            (bounds.max.x / size).ceil() + 1.0,
+            (bounds.max.y / size).ceil() + 1.0,
+            (bounds.max.z / size).ceil() + 1.0,
+        );
+        let dims = [
+            (max.x - min.x) as usize,
+            (max.y - min.y) as usize,
+            (max.z - min.z) as usize,
+        ];
+        let cells = dims.iter().try_fold(1usize, |acc, &d| acc.checked_mul(d));
+        if cells.is_none_or(|cells| cells > MAX_CELLS) {
+            return Err(VmfError::InvalidFormat(format!(
+                "A leak grid of {} by {} by {} cells is too large; use a larger voxel size",
+                dims[0], dims[1], dims[2]
+            )));
+        }
+        Ok(Self {
+            origin: min * size,
+            size,
+            dims,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.dims[0] * self.dims[1] * self.dims[2]
+    }
+
+    fn index(&self, [x, y, z]: [usize; 3]) -> usize {
+        (z * self.dims[1] + y) * self.dims[0] + x
+    }
+
+    fn coords(&self, index: usize) -> [usize; 3] {
+        let x = index % self.dims[0];
+        let y = index / self.dims[0] % self.dims[1];
+        let z = index / (self.dims[0] * self.dims[1]);
+        [x, y, z]
+    }
+
+    fn center(&self, index: usize) -> Vector3 {
+        let [x, y, z] = self.coords(index);
+        self.origin + Vector3::new(x as f64 + 0.5, y as f64 + 0.5, z as f64 + 0.5) * self.size
+    }
+
+    /// Returns the cell containing `point`, or `None` if it is outside the grid.
+    fn cell_at(&self, point: Vector3) -> Option<usize> {
+        let local = (point - self.origin) / self.size;
+        let mut coords = [0; 3];
+        for axis in 0..3 {
+            let c = local[axis].floor();
+            if c < 0.0 || c >= self.dims[axis] as f64 {
+                return None;
+            }
+            coords[axis] = c as usize;
+        }
+        Some(self.index(coords))
+    }
+
+    /// Marks the cells whose center is inside a brush, then floods the rest from the
+    /// border. Returns the cell each reached cell was reached from, with border cells
+    /// pointing at themselves.
+    fn flood(&self, brushes: &[(Vec<Plane>, Aabb)]) -> Vec<u32> {
+        // One bit per cell, set for cells inside a brush.
+        let mut solid = vec![0u64; self.len().div_ceil(64)];
+        for (planes, bounds) in brushes {
+            let first = |value: f64, axis: usize| {
+                ((value - self.origin[axis]) / self.size - 0.5)
+                    .ceil()
+                    .max(0.0) as usize
+            };
+            let last = |value: f64, axis: usize| {
+                let c = ((value - self.origin[axis]) / self.size - 0.5).floor();
+                (c.max(-1.0) as isize).min(self.dims[axis] as isize - 1)
+            };
+            for z in first(bounds.min.z, 2) as isize..=last(bounds.max.z, 2) {
+                for y in first(bounds.min.y, 1) as isize..=last(bounds.max.y, 1) {
+                    for x in first(bounds.min.x, 0) as isize..=last(bounds.max.x, 0) {
+                        let index = self.index([x as usize, y as usize, z as usize]);
+                        let center = self.center(index);
+                        if planes.iter().all(|p| p.distance_to(center) <= ON_EPSILON) {
+                            solid[index / 64] |= 1 << (index % 64);
+                        }
+                    }
+                }
+            }
+        }
+
+        let is_solid = |index: usize| solid[index / 64] & (1 << (index % 64)) != 0;
+        let mut reached = vec![UNREACHED; self.len()];
+        let mut queue = VecDeque::new();
+        for (index, reached) in reached.iter_mut().enumerate() {
+            let [x, y, z] = self.coords(index);
+            let border = [x, y, z]
+                .iter()
+                .zip(self.dims)
+                .any(|(&c, d)| c == 0 || c == d - 1);
+            if border && !is_solid(index) {
+                *reached = index as u32;
+                queue.push_back(index);
+            }
+        }
+
+        while let Some(index) = queue.pop_front() {
+            let coords = self.coords(index);
+            for axis in 0..3 {
+                for step in [-1isize, 1] {
+                    let c = coords[axis] as isize + step;
+                    if c < 0 || c >= self.dims[axis] as isize {
+                        continue;
+                    }
+                    let mut next = coords;
+                    next[axis] = c as usize;
+                    let next = self.index(next);
+                    if !is_solid(next) && reached[next] == UNREACHED {
+                        reached[next] = index as u32;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        reached
+    }
+
+    /// Follows the flood back from `cell` to the border, or returns `None` if the
+    /// void never reached it. Points in a straight line are merged.
+    fn path(&self, reached: &[u32], mut cell: usize, origin: Vector3) -> Option<Vec<Vector3>> {
+        if reached[cell] == UNREACHED {
+            return None;
+        }
+        let mut path = vec![origin];
+        loop {
+            let next = reached[cell] as usize;
+            if next == cell {
+                break;
+            }
+            cell = next;
+            let point = self.center(cell);
+            if let [.., a, b] = path[..]
+                && (b - a).cross(point - b).length_squared() < 1e-9
+            {
+                path.pop();
+            }
+            path.push(point);
+        }
+        Some(path)
+    }
+}
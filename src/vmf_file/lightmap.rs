@@ -0,0 +1,314 @@
+use std::collections::BTreeMap;
+
+use super::VmfFile;
+use crate::errors::{VmfError, VmfResult};
+use crate::geometry::Vector3;
+use crate::prelude::Solid;
+use crate::utils::matches_wildcard;
+
+/// The most luxels a brush face lightmap spans along either axis before VBSP
+/// subdivides the face (`MAX_BRUSH_LIGHTMAP_DIM_WITHOUT_BORDER`).
+const MAX_BRUSH_LUXELS: f64 = 32.0;
+/// The most luxels a displacement lightmap spans along either axis
+/// (`MAX_DISP_LIGHTMAP_DIM_WITHOUT_BORDER`).
+const MAX_DISP_LUXELS: f64 = 125.0;
+/// Faces with materials matching this get no lightmap.
+const UNLIT_MATERIALS: &str = "tools/*";
+
+/// The estimated lightmap of one face.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaceLightmap {
+    /// The ID of the solid.
+    pub solid_id: u64,
+    /// The ID of the side.
+    pub side_id: u32,
+    /// The ID of the entity owning the solid, or `None` for world brushes.
+    pub entity_id: Option<u64>,
+    /// The material of the side.
+    pub material: String,
+    /// The visgroup of the solid, if any.
+    pub visgroup_id: Option<i32>,
+    /// The lightmap scale of the side, in world units per luxel.
+    pub lightmap_scale: u16,
+    /// The area of the face, in square world units.
+    pub area: f64,
+    /// The estimated number of luxels, the area divided by the lightmap scale squared.
+    pub luxels: f64,
+    /// The size of the lightmap along the U and V texture axes, in luxels.
+    pub extents: [f64; 2],
+    /// Whether an extent exceeds what Source fits in one lightmap: 32 luxels for
+    /// brush faces, which VBSP then splits, and 125 for displacements.
+    pub exceeds_limit: bool,
+}
+
+/// Lightmap totals over a group of faces.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LightmapTotals {
+    /// The number of faces.
+    pub faces: usize,
+    /// The total area, in square world units.
+    pub area: f64,
+    /// The estimated total number of luxels.
+    pub luxels: f64,
+    /// The number of faces exceeding the lightmap size limit.
+    pub over_limit: usize,
+}
+
+impl LightmapTotals {
+    fn add(&mut self, face: &FaceLightmap) {
+        self.faces += 1;
+        self.area += face.area;
+        self.luxels += face.luxels;
+        if face.exceeds_limit {
+            self.over_limit += 1;
+        }
+    }
+}
+
+/// The lightmap budget of a map, as computed by `VmfFile::lightmap_report`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LightmapReport {
+    /// Every lit face, in document order.
+    pub faces: Vec<FaceLightmap>,
+    /// The totals over all faces.
+    pub total: LightmapTotals,
+    /// The totals per material.
+    pub by_material: BTreeMap<String, LightmapTotals>,
+    /// The totals per visgroup ID. Faces of solids without a visgroup are left out.
+    pub by_visgroup: BTreeMap<i32, LightmapTotals>,
+    /// The totals per cordon name, over the faces whose center lies in the cordon.
+    pub by_region: BTreeMap<String, LightmapTotals>,
+}
+
+/// Sets `Side::lightmap_scale` on the faces matching all of its conditions.
+/// A rule without conditions matches every face.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightmapRule {
+    /// A case-insensitive material pattern, where `*` matches any run of characters
+    /// and `?` any single character. Defaults to `None`, matching any material.
+    pub material: Option<String>,
+    /// Only match faces facing this way, for example `Vector3::Z` for floors.
+    /// Defaults to `None`, matching any direction.
+    pub facing: Option<Vector3>,
+    /// The largest angle in degrees between a face's normal and `facing`. Defaults to 45.
+    pub max_angle: f64,
+    /// The lightmap scale to set, in world units per luxel. Defaults to 16.
+    pub scale: u16,
+}
+
+impl Default for LightmapRule {
+    fn default() -> Self {
+        Self {
+            material: None,
+            facing: None,
+            max_angle: 45.0,
+            scale: 16,
+        }
+    }
+}
+
+impl VmfFile {
+    /// Estimates the lightmap size of every lit face.
+    ///
+    /// Faces of visible world and entity brushes are counted, except those with
+    /// a `TOOLS/` material, which VBSP doesn't light. Displacements are estimated
+    /// from their base face.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the per-face estimates and their totals by material,
+    /// visgroup and cordon, or a `VmfError` if a plane, texture axis or cordon is
+    /// malformed or a face has a lightmap scale of 0.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// let report = vmf.lightmap_report()?;
+    /// for (material, totals) in &report.by_material {
+    ///     println!("{}: {:.0} luxels", material, totals.luxels);
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn lightmap_report(&self) -> VmfResult<LightmapReport> {
+        // Each face with the center used to place it in a region.
+        let mut faces = Vec::new();
+        for solid in &self.world.solids {
+            face_lightmaps(solid, None, &mut faces)?;
+        }
+        for ent in self.entities.iter().filter(|ent| !ent.is_hidden) {
+            for solid in ent.solids.iter().flatten() {
+                face_lightmaps(solid, Some(ent.id()), &mut faces)?;
+            }
+        }
+        let regions = self
+            .cordons
+            .iter()
+            .map(|cordon| Ok((cordon.name.as_str(), cordon.bounds()?)))
+            .collect::<VmfResult<Vec<_>>>()?;
+
+        let mut report = LightmapReport::default();
+        for (face, center) in faces {
+            report.total.add(&face);
+            report
+                .by_material
+                .entry(face.material.clone())
+                .or_default()
+                .add(&face);
+            if let Some(visgroup_id) = face.visgroup_id {
+                report
+                    .by_visgroup
+                    .entry(visgroup_id)
+                    .or_default()
+                    .add(&face);
+            }
+            for (name, bounds) in &regions {
+                if bounds.contains_point(center) {
+                    report
+                        .by_region
+                        .entry(name.to_string())
+                        .or_default()
+                        .add(&face);
+                }
+            }
+            report.faces.push(face);
+        }
+        Ok(report)
+    }
+
+    /// Sets the lightmap scale of every side by rule, including hidden ones.
+    ///
+    /// Each side gets the scale of the first rule it matches; sides matching no
+    /// rule are left alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - The rules, in order of priority.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the number of sides whose scale changed, or a
+    /// `VmfError` if a rule has a scale of 0 or no facing direction, or a plane is
+    /// malformed. The map is left unchanged on error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("your_map.vmf")?;
+    /// let rules = [
+    ///     LightmapRule { material: Some("nature/*".to_string()), scale: 32, ..Default::default() },
+    ///     LightmapRule { facing: Some(-Vector3::Z), scale: 32, ..Default::default() },
+    /// ];
+    /// println!("{} sides changed", vmf.set_lightmap_scale(&rules)?);
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn set_lightmap_scale(&mut self, rules: &[LightmapRule]) -> VmfResult<usize> {
+        for rule in rules {
+            if rule.scale == 0 {
+                return Err(VmfError::InvalidFormat(
+                    "A lightmap scale must be at least 1".to_string(),
+                ));
+            }
+            if rule.facing.is_some_and(|f| f.normalize() == Vector3::ZERO) {
+                return Err(VmfError::InvalidFormat(
+                    "A lightmap rule faces no direction".to_string(),
+                ));
+            }
+        }
+        let needs_normals = rules.iter().any(|rule| rule.facing.is_some());
+
+        let mut scales: Vec<Option<u16>> = Vec::new();
+        for solid in self.solids(true) {
+            for side in &solid.sides {
+                let normal = if needs_normals {
+                    side.to_plane()?.normal
+                } else {
+                    Vector3::ZERO
+                };
+                let rule = rules.iter().find(|rule| {
+                    rule.material
+                        .as_ref()
+                        .is_none_or(|pattern| matches_wildcard(pattern, &side.material))
+                        && rule.facing.is_none_or(|facing| {
+                            normal.dot(facing.normalize()) >= rule.max_angle.to_radians().cos()
+                        })
+                });
+                scales.push(rule.map(|rule| rule.scale));
+            }
+        }
+
+        let mut scales = scales.into_iter();
+        let mut changed = 0;
+        for solid in self.solids_mut(true) {
+            for side in &mut solid.sides {
+                if let Some(Some(scale)) = scales.next()
+                    && side.lightmap_scale != scale
+                {
+                    side.lightmap_scale = scale;
+                    changed += 1;
+                }
+            }
+        }
+        Ok(changed)
+    }
+}
+
+/// Estimates the lightmap of each lit face of a solid, along with the face's center.
+fn face_lightmaps(
+    solid: &Solid,
+    entity_id: Option<u64>,
+    out: &mut Vec<(FaceLightmap, Vector3)>,
+) -> VmfResult<()> {
+    for face in solid.faces()? {
+        let side = face.side;
+        if matches_wildcard(UNLIT_MATERIALS, &side.material) {
+            continue;
+        }
+        if side.lightmap_scale == 0 {
+            return Err(VmfError::InvalidFormat(format!(
+                "Side {} has a lightmap scale of 0",
+                side.id
+            )));
+        }
+
+        let scale = side.lightmap_scale as f64;
+        let axes = side.texture_axes()?;
+        let extents = axes.map(|axis| {
+            let axis = axis.axis.normalize();
+            let (min, max) = face
+                .polygon
+                .vertices
+                .iter()
+                .map(|v| v.dot(axis))
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), d| {
+                    (lo.min(d), hi.max(d))
+                });
+            (max - min) / scale
+        });
+        let limit = if side.dispinfo.is_some() {
+            MAX_DISP_LUXELS
+        } else {
+            MAX_BRUSH_LUXELS
+        };
+        let area = face.polygon.area();
+
+        let lightmap = FaceLightmap {
+            solid_id: solid.id,
+            side_id: side.id,
+            entity_id,
+            material: side.material.clone(),
+            visgroup_id: solid.editor.visgroup_id,
+            lightmap_scale: side.lightmap_scale,
+            area,
+            luxels: area / (scale * scale),
+            extents,
+            exceeds_limit: extents.iter().any(|&e| e > limit),
+        };
+        out.push((lightmap, face.polygon.center()));
+    }
+    Ok(())
+}
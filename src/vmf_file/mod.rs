@@ -1,14 +1,46 @@
 use crate::VmfSerializable;
 
-use super::vmf::entities::Entities;
+use super::vmf::entities::{Entities, Entity};
 use super::vmf::metadata::{VersionInfo, ViewSettings, VisGroups};
 use super::vmf::regions::{Cameras, Cordons};
-use super::vmf::world::World;
+use super::vmf::world::{Solid, World};
 
+mod bounds;
+mod carve;
+mod cordon;
+mod export;
+mod grid;
+mod heightmap;
+mod ids;
+mod instance_graph;
+mod instances;
 mod io;
+mod io_graph;
+mod leaks;
+mod lightmap;
 mod merge;
+mod overlays;
+mod png;
+mod sculpt;
+mod spatial;
+mod texture_ops;
+mod validation;
 mod visgroup_ops;
 
+pub use cordon::CordonOptions;
+pub use grid::GridSnap;
+pub use heightmap::{Heightmap, HeightmapOptions};
+pub use ids::IdAllocator;
+pub use instance_graph::{
+    InstanceEdge, InstanceGraph, InstanceParameter, MissingInstance, UndeclaredParameter,
+};
+pub use instances::{FixupStyle, InstanceResolver};
+pub use io_graph::{IoEdge, IoGraph, IoNode, IoTarget};
+pub use leaks::{Leak, LeakOptions, NON_SEALING_MATERIALS};
+pub use lightmap::{FaceLightmap, LightmapReport, LightmapRule, LightmapTotals};
+pub use overlays::{OverlayOptions, OverlayProblem, OverlayProblemKind};
+pub use spatial::{RayHit, SpatialEntry, SpatialIndex, SpatialKey};
+
 /// Represents a parsed VMF file.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serialization", derive(Serialize, Deserialize))]
@@ -85,4 +117,53 @@ impl VmfFile {
 
         output
     }
+
+    /// Returns an iterator over every solid in the file: world brushes first,
+    /// then the brushes of each entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_hidden` - Whether hidden world solids and hidden entities are included.
+    pub fn solids(&self, include_hidden: bool) -> impl Iterator<Item = &Solid> + '_ {
+        let hidden: &[Solid] = if include_hidden {
+            &self.world.hidden
+        } else {
+            &[]
+        };
+        let hidden_entities: &[Entity] = if include_hidden { &self.hiddens } else { &[] };
+
+        self.world.solids.iter().chain(hidden).chain(
+            self.entities
+                .iter()
+                .chain(hidden_entities)
+                .filter_map(|ent| ent.solids.as_ref())
+                .flatten(),
+        )
+    }
+
+    /// Returns a mutable iterator over every solid, in the same order as `solids`.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_hidden` - Whether hidden world solids and hidden entities are included.
+    pub fn solids_mut(&mut self, include_hidden: bool) -> impl Iterator<Item = &mut Solid> + '_ {
+        let hidden: &mut [Solid] = if include_hidden {
+            &mut self.world.hidden
+        } else {
+            &mut []
+        };
+        let hidden_entities: &mut [Entity] = if include_hidden {
+            &mut self.hiddens
+        } else {
+            &mut []
+        };
+
+        self.world.solids.iter_mut().chain(hidden).chain(
+            self.entities
+                .iter_mut()
+                .chain(hidden_entities)
+                .filter_map(|ent| ent.solids.as_mut())
+                .flatten(),
+        )
+    }
 }
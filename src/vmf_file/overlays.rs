@@ -0,0 +1,442 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::{IdAllocator, VmfFile};
+use crate::errors::{VmfError, VmfResult};
+use crate::geometry::{Face, Vector3};
+use crate::prelude::Entity;
+use crate::vmf::overlays::{OVERLAY_CLASSNAME, Overlay, parse_side_ids, remap_side_ids, side_keys};
+
+/// Faces whose normal is closer than this cosine to the overlay normal (about 45°)
+/// can carry it.
+const MIN_FACING: f64 = 0.7;
+/// The most faces VBSP accepts under one overlay, from `OVERLAY_BSP_FACE_COUNT`.
+const MAX_OVERLAY_FACES: usize = 64;
+/// Faces must overlap the footprint by more than this to carry the overlay, so
+/// faces merely touching its edge are left out.
+const OVERLAP_EPSILON: f64 = 0.01;
+
+/// How `VmfFile::make_overlay` places an overlay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlayOptions {
+    /// The material of the overlay.
+    pub material: String,
+    /// The center of the overlay, on the surface it's projected onto.
+    pub origin: Vector3,
+    /// The direction the overlay faces, out of the surface.
+    pub normal: Vector3,
+    /// The size of the overlay along its U axis, in world units.
+    pub width: f64,
+    /// The size of the overlay along its V axis, in world units.
+    pub height: f64,
+    /// The rotation of the overlay around its normal, in degrees. Defaults to 0,
+    /// which runs U horizontally on walls and along +X on floors and ceilings.
+    pub rotation: f64,
+    /// How far in front of or behind the overlay a face may lie and still carry it.
+    /// Defaults to 1.
+    pub max_distance: f64,
+}
+
+impl OverlayOptions {
+    /// Creates options for an unrotated overlay on faces within 1 unit of `origin`.
+    ///
+    /// # Arguments
+    ///
+    /// * `material` - The material of the overlay.
+    /// * `origin` - The center of the overlay.
+    /// * `normal` - The direction the overlay faces.
+    /// * `width` - The size of the overlay along its U axis.
+    /// * `height` - The size of the overlay along its V axis.
+    pub fn new(
+        material: impl Into<String>,
+        origin: Vector3,
+        normal: Vector3,
+        width: f64,
+        height: f64,
+    ) -> Self {
+        Self {
+            material: material.into(),
+            origin,
+            normal,
+            width,
+            height,
+            rotation: 0.0,
+            max_distance: 1.0,
+        }
+    }
+}
+
+/// The reason an overlay or decal is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayProblemKind {
+    /// The entity isn't projected onto any side.
+    NoSides,
+    /// A side key isn't a list of side IDs.
+    InvalidSides,
+    /// The entity refers to sides that don't exist.
+    MissingSides,
+}
+
+impl fmt::Display for OverlayProblemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            OverlayProblemKind::NoSides => "no sides",
+            OverlayProblemKind::InvalidSides => "invalid side list",
+            OverlayProblemKind::MissingSides => "missing sides",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// A problem found on an overlay or decal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlayProblem {
+    /// The ID of the entity.
+    pub entity_id: u64,
+    /// The classname of the entity.
+    pub classname: String,
+    /// What is wrong with the entity.
+    pub kind: OverlayProblemKind,
+    /// The IDs of the missing sides.
+    pub side_ids: Vec<u32>,
+}
+
+impl fmt::Display for OverlayProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.classname, self.entity_id, self.kind)?;
+        if !self.side_ids.is_empty() {
+            let ids: Vec<String> = self.side_ids.iter().map(u32::to_string).collect();
+            write!(f, " (sides {})", ids.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl VmfFile {
+    /// Returns the typed view of every `info_overlay`, including hidden ones.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the overlays in document order, or a `VmfError` if
+    /// an overlay is missing a key or has a malformed one.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// for overlay in vmf.overlays()? {
+    ///     println!("{} on sides {:?}", overlay.material, overlay.sides);
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn overlays(&self) -> VmfResult<Vec<Overlay>> {
+        self.entities
+            .iter()
+            .chain(self.hiddens.iter())
+            .filter(|ent| {
+                ent.classname()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(OVERLAY_CLASSNAME))
+            })
+            .map(Overlay::try_from)
+            .collect()
+    }
+
+    /// Checks that every overlay and decal refers to sides that exist.
+    ///
+    /// `info_overlay`, `info_overlay_transition` and `info_decal` entities are
+    /// checked, including hidden ones, against the sides of every solid.
+    ///
+    /// # Returns
+    ///
+    /// A vector of the problems found, in document order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// for problem in vmf.validate_overlays() {
+    ///     eprintln!("{}", problem);
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn validate_overlays(&self) -> Vec<OverlayProblem> {
+        let existing: HashSet<u32> = self
+            .solids(true)
+            .flat_map(|solid| &solid.sides)
+            .map(|side| side.id)
+            .collect();
+
+        let mut problems = Vec::new();
+        for ent in self.entities.iter().chain(self.hiddens.iter()) {
+            let keys = side_keys(ent);
+            if keys.is_empty() {
+                continue;
+            }
+            let problem = |kind, side_ids| OverlayProblem {
+                entity_id: ent.id(),
+                classname: ent.classname().unwrap_or_default().to_string(),
+                kind,
+                side_ids,
+            };
+
+            // Decals are usually placed without sides and attach to the nearest face.
+            let required = !ent
+                .classname()
+                .is_some_and(|name| name.eq_ignore_ascii_case("info_decal"));
+            let mut listed = Vec::new();
+            let mut invalid = false;
+            for key in keys {
+                match parse_side_ids(ent.get(key).map_or("", String::as_str)) {
+                    Ok(ids) => listed.extend(ids),
+                    Err(_) => invalid = true,
+                }
+            }
+            if invalid {
+                problems.push(problem(OverlayProblemKind::InvalidSides, Vec::new()));
+            } else if listed.is_empty() && required {
+                problems.push(problem(OverlayProblemKind::NoSides, Vec::new()));
+            }
+            let missing: Vec<u32> = listed
+                .into_iter()
+                .filter(|id| !existing.contains(id))
+                .collect();
+            if !missing.is_empty() {
+                problems.push(problem(OverlayProblemKind::MissingSides, missing));
+            }
+        }
+        problems
+    }
+
+    /// Updates the side IDs overlays and decals refer to after sides were renumbered,
+    /// removed or split.
+    ///
+    /// Operations of `VmfFile` that replace sides, such as `carve`, already do this.
+    /// Use it after editing solids directly, for example with the map returned by
+    /// `Solid::derived_sides`.
+    ///
+    /// # Arguments
+    ///
+    /// * `remap` - The sides that took the place of each old side ID: none if it was
+    ///   removed, one if it was renumbered and several if it was split. Sides not in
+    ///   the map are left alone.
+    ///
+    /// # Returns
+    ///
+    /// The number of entities whose side lists changed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("your_map.vmf")?;
+    /// let mut ids = vmf.id_allocator();
+    /// let plane = Plane::new(Vector3::Z, 64.0);
+    /// let solid = vmf.world.solids.remove(0);
+    /// let clipped = solid.clip(&plane, ClipMode::KeepBoth, "TOOLS/TOOLSNODRAW", &mut ids)?;
+    /// let pieces: Vec<Solid> = clipped.front.into_iter().chain(clipped.back).collect();
+    /// vmf.remap_overlay_sides(&solid.derived_sides(&pieces));
+    /// vmf.world.solids.extend(pieces);
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn remap_overlay_sides(&mut self, remap: &HashMap<u32, Vec<u32>>) -> usize {
+        self.entities
+            .iter_mut()
+            .chain(self.hiddens.iter_mut())
+            .map(|ent| remap_side_ids(ent, remap))
+            .filter(|&changed| changed)
+            .count()
+    }
+
+    /// Creates an `info_overlay` projected onto the brush faces under it, like
+    /// placing one with Hammer's overlay tool.
+    ///
+    /// The overlay's footprint is a `width` × `height` rectangle centered on the
+    /// origin. It is put on every visible world and entity brush face that faces the
+    /// same way, lies within `max_distance` of the origin along the normal and
+    /// overlaps the footprint. The material covers the footprint once.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Where the overlay goes and how big it is.
+    /// * `ids` - Where the entity ID comes from, usually `VmfFile::id_allocator`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the entity, ready to be added to `entities`, or a
+    /// `VmfError` if the options are invalid, a plane is malformed, or no face or
+    /// more than the 64 faces VBSP allows lie under the overlay. Nothing is taken
+    /// from `ids` on error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    /// use vmf_forge::vmf_file::OverlayOptions;
+    ///
+    /// let mut vmf = VmfFile::open("your_map.vmf")?;
+    /// let mut ids = vmf.id_allocator();
+    /// let options = OverlayOptions::new(
+    ///     "decals/decalgraffiti001a",
+    ///     Vector3::new(128.0, 256.0, 64.0),
+    ///     -Vector3::Y,
+    ///     64.0,
+    ///     64.0,
+    /// );
+    /// let overlay = vmf.make_overlay(&options, &mut ids)?;
+    /// vmf.entities.push(overlay);
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn make_overlay(
+        &self,
+        options: &OverlayOptions,
+        ids: &mut IdAllocator,
+    ) -> VmfResult<Entity> {
+        let normal = options.normal.normalize();
+        let valid = normal != Vector3::ZERO
+            && options.width > 0.0
+            && options.height > 0.0
+            && options.rotation.is_finite()
+            && options.max_distance >= 0.0;
+        if !valid {
+            return Err(VmfError::InvalidFormat(format!(
+                "Invalid overlay placement: normal {}, size {}x{}, rotation {}, distance {}",
+                options.normal,
+                options.width,
+                options.height,
+                options.rotation,
+                options.max_distance
+            )));
+        }
+        let (basis_u, basis_v) = overlay_basis(normal, options.rotation);
+        let half = [options.width / 2.0, options.height / 2.0];
+
+        let mut sides = Vec::new();
+        for solid in self.solids(false) {
+            for face in solid.faces()? {
+                if face.plane.normal.normalize().dot(normal) >= MIN_FACING
+                    && is_under(&face, options, normal, [basis_u, basis_v], half)
+                {
+                    sides.push(face.side.id);
+                }
+            }
+        }
+        if sides.is_empty() {
+            return Err(VmfError::InvalidFormat(format!(
+                "No brush face under the overlay at {}",
+                options.origin
+            )));
+        }
+        if sides.len() > MAX_OVERLAY_FACES {
+            return Err(VmfError::InvalidFormat(format!(
+                "The overlay at {} covers {} faces, more than the {} VBSP allows",
+                options.origin,
+                sides.len(),
+                MAX_OVERLAY_FACES
+            )));
+        }
+
+        let overlay = Overlay {
+            id: ids.next_object_id(),
+            material: options.material.clone(),
+            sides,
+            basis_origin: options.origin,
+            basis_u,
+            basis_v,
+            basis_normal: normal,
+            uv: [
+                Vector3::new(-half[0], -half[1], 0.0),
+                Vector3::new(-half[0], half[1], 0.0),
+                Vector3::new(half[0], half[1], 0.0),
+                Vector3::new(half[0], -half[1], 0.0),
+            ],
+            start_u: 0.0,
+            start_v: 0.0,
+            end_u: 1.0,
+            end_v: 1.0,
+        };
+        let mut entity = Entity::new(OVERLAY_CLASSNAME, overlay.id);
+        entity.set_angles(Vector3::ZERO);
+        entity.set_origin(options.origin);
+        entity.set("fademindist".to_string(), "-1".to_string());
+        overlay.apply(&mut entity);
+        Ok(entity)
+    }
+}
+
+/// Returns the U and V axes of an overlay facing `normal`, with `U × V = normal`.
+///
+/// Unrotated, V points down the surface on walls, as in Hammer, and U runs along +X
+/// on floors and ceilings.
+fn overlay_basis(normal: Vector3, rotation: f64) -> (Vector3, Vector3) {
+    let down = -Vector3::Z - normal * normal.dot(-Vector3::Z);
+    let (u, v) = if down.length() > 0.1 {
+        let v = down.normalize();
+        (v.cross(normal), v)
+    } else {
+        let u = (Vector3::X - normal * normal.dot(Vector3::X)).normalize();
+        (u, normal.cross(u))
+    };
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    (u * cos + v * sin, v * cos - u * sin)
+}
+
+/// Checks whether a face lies within reach of the overlay plane and overlaps its
+/// footprint, comparing the two as convex shapes in the overlay's UV space.
+fn is_under(
+    face: &Face,
+    options: &OverlayOptions,
+    normal: Vector3,
+    axes: [Vector3; 2],
+    half: [f64; 2],
+) -> bool {
+    let vertices = &face.polygon.vertices;
+    let (near, far) = vertices
+        .iter()
+        .map(|&p| (p - options.origin).dot(normal))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), d| {
+            (lo.min(d), hi.max(d))
+        });
+    if near > options.max_distance || far < -options.max_distance {
+        return false;
+    }
+
+    let face_uv: Vec<[f64; 2]> = vertices
+        .iter()
+        .map(|&p| axes.map(|axis| (p - options.origin).dot(axis)))
+        .collect();
+    let footprint = [
+        [-half[0], -half[1]],
+        [-half[0], half[1]],
+        [half[0], half[1]],
+        [half[0], -half[1]],
+    ];
+
+    // Separating axis test: the footprint's axes, then each face edge's normal.
+    let mut axes_2d = vec![[1.0, 0.0], [0.0, 1.0]];
+    for (i, a) in face_uv.iter().enumerate() {
+        let b = face_uv[(i + 1) % face_uv.len()];
+        axes_2d.push([b[1] - a[1], a[0] - b[0]]);
+    }
+    axes_2d.into_iter().all(|axis| {
+        let length = axis[0].hypot(axis[1]);
+        if length < 1e-9 {
+            return true;
+        }
+        let project = |points: &[[f64; 2]]| {
+            points
+                .iter()
+                .map(|p| (p[0] * axis[0] + p[1] * axis[1]) / length)
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), d| {
+                    (lo.min(d), hi.max(d))
+                })
+        };
+        let (face_min, face_max) = project(&face_uv);
+        let (foot_min, foot_max) = project(&footprint);
+        face_max.min(foot_max) - face_min.max(foot_min) > OVERLAP_EPSILON
+    })
+}
@@ -0,0 +1,437 @@
+//! A minimal PNG decoder for heightmaps: non-interlaced grayscale, RGB and their
+//! alpha variants at 8 or 16 bits per channel. Palettes, lower bit depths and
+//! interlacing are rejected, and checksums are not verified.
+
+use crate::errors::{VmfError, VmfResult};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+/// Decodes a PNG into its width, height and one value from 0 to 1 per pixel,
+/// row by row from the top. Color pixels are averaged and alpha is ignored.
+pub(crate) fn decode_gray(data: &[u8]) -> VmfResult<(usize, usize, Vec<f64>)> {
+    if !data.starts_with(&SIGNATURE) {
+        return Err(invalid("not a PNG file"));
+    }
+
+    let mut header = None;
+    let mut compressed = Vec::new();
+    let mut pos = SIGNATURE.len();
+    while pos + 8 <= data.len() {
+        let length = u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+        let kind = &data[pos + 4..pos + 8];
+        let body = data
+            .get(pos + 8..pos + 8 + length)
+            .ok_or_else(|| invalid("truncated chunk"))?;
+        match kind {
+            b"IHDR" => header = Some(Header::parse(body)?),
+            b"IDAT" => compressed.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        // Skip the body and its CRC.
+        pos += 12 + length;
+    }
+    let header = header.ok_or_else(|| invalid("missing IHDR chunk"))?;
+
+    let raw = zlib_decompress(&compressed, header.filtered_size()?)?;
+    let pixels = header.unfilter(&raw)?;
+    Ok((header.width, header.height, header.gray(&pixels)))
+}
+
+struct Header {
+    width: usize,
+    height: usize,
+    /// Bytes per channel, 1 or 2.
+    depth: usize,
+    channels: usize,
+}
+
+impl Header {
+    fn parse(body: &[u8]) -> VmfResult<Self> {
+        if body.len() != 13 {
+            return Err(invalid("malformed IHDR chunk"));
+        }
+        let width = u32::from_be_bytes(body[0..4].try_into().unwrap()) as usize;
+        let height = u32::from_be_bytes(body[4..8].try_into().unwrap()) as usize;
+        let depth = match body[8] {
+            8 => 1,
+            16 => 2,
+            bits => return Err(invalid(&format!("unsupported bit depth {}", bits))),
+        };
+        let channels = match body[9] {
+            0 => 1,
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            kind => return Err(invalid(&format!("unsupported color type {}", kind))),
+        };
+        if body[10] != 0 || body[11] != 0 {
+            return Err(invalid("unknown compression or filter method"));
+        }
+        if body[12] != 0 {
+            return Err(invalid("interlaced images are not supported"));
+        }
+        if width == 0 || height == 0 {
+            return Err(invalid("empty image"));
+        }
+        Ok(Self {
+            width,
+            height,
+            depth,
+            channels,
+        })
+    }
+
+    fn pixel_bytes(&self) -> usize {
+        self.depth * self.channels
+    }
+
+    /// The number of bytes in one unfiltered row.
+    fn stride(&self) -> VmfResult<usize> {
+        // The dimensions come straight from the file, so guard every product.
+        self.width
+            .checked_mul(self.pixel_bytes())
+            .ok_or_else(|| invalid("image is too large"))
+    }
+
+    /// The size of the decompressed image data: every row plus its filter byte.
+    fn filtered_size(&self) -> VmfResult<usize> {
+        self.stride()?
+            .checked_add(1)
+            .and_then(|row| row.checked_mul(self.height))
+            .ok_or_else(|| invalid("image is too large"))
+    }
+
+    /// Reverses the per-row filters, returning the bare pixel bytes.
+    fn unfilter(&self, raw: &[u8]) -> VmfResult<Vec<u8>> {
+        let bpp = self.pixel_bytes();
+        let stride = self.stride()?;
+        let filtered = self.filtered_size()?;
+        let size = stride
+            .checked_mul(self.height)
+            .ok_or_else(|| invalid("image is too large"))?;
+        if raw.len() < filtered {
+            return Err(invalid("image data is too short"));
+        }
+
+        let mut pixels = vec![0u8; size];
+        for row in 0..self.height {
+            let filter = raw[row * (stride + 1)];
+            let line = &raw[row * (stride + 1) + 1..(row + 1) * (stride + 1)];
+            let (done, rest) = pixels.split_at_mut(row * stride);
+            let previous = if row > 0 {
+                &done[(row - 1) * stride..]
+            } else {
+                &[][..]
+            };
+            let current = &mut rest[..stride];
+            for i in 0..stride {
+                let a = if i >= bpp { current[i - bpp] } else { 0 };
+                let b = previous.get(i).copied().unwrap_or(0);
+                let c = if i >= bpp {
+                    previous.get(i - bpp).copied().unwrap_or(0)
+                } else {
+                    0
+                };
+                let predicted = match filter {
+                    0 => 0,
+                    1 => a,
+                    2 => b,
+                    3 => ((a as u16 + b as u16) / 2) as u8,
+                    4 => paeth(a, b, c),
+                    _ => return Err(invalid(&format!("unknown filter type {}", filter))),
+                };
+                current[i] = line[i].wrapping_add(predicted);
+            }
+        }
+        Ok(pixels)
+    }
+
+    /// Converts pixel bytes to values from 0 to 1, averaging the color channels.
+    fn gray(&self, pixels: &[u8]) -> Vec<f64> {
+        let max = if self.depth == 2 { 65535.0 } else { 255.0 };
+        let color_channels = if self.channels >= 3 { 3 } else { 1 };
+        pixels
+            .chunks_exact(self.pixel_bytes())
+            .map(|pixel| {
+                let sum: f64 = (0..color_channels)
+                    .map(|c| {
+                        let at = c * self.depth;
+                        if self.depth == 2 {
+                            f64::from(u16::from_be_bytes([pixel[at], pixel[at + 1]]))
+                        } else {
+                            f64::from(pixel[at])
+                        }
+                    })
+                    .sum();
+                sum / color_channels as f64 / max
+            })
+            .collect()
+    }
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn invalid(reason: &str) -> VmfError {
+    VmfError::InvalidFormat(format!("Unsupported PNG: {}", reason))
+}
+
+/// Strips the zlib header and inflates the deflate stream after it, failing as
+/// soon as the output grows past `limit` bytes.
+fn zlib_decompress(data: &[u8], limit: usize) -> VmfResult<Vec<u8>> {
+    if data.len() < 2
+        || data[0] & 0x0f != 8
+        || (u16::from(data[0]) << 8 | u16::from(data[1])) % 31 != 0
+    {
+        return Err(invalid("malformed zlib stream"));
+    }
+    if data[1] & 0x20 != 0 {
+        return Err(invalid("preset zlib dictionaries are not supported"));
+    }
+    Inflater::new(&data[2..], limit).inflate()
+}
+
+/// Order of the code length code lengths in a dynamic block header.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// A canonical Huffman code, decoded one bit at a time.
+struct Huffman {
+    /// The number of codes of each length.
+    counts: [u16; 16],
+    /// The symbols, ordered by code.
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+        let mut offsets = [0u16; 16];
+        for i in 1..16 {
+            offsets[i] = offsets[i - 1] + counts[i - 1];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Self { counts, symbols }
+    }
+}
+
+struct Inflater<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit: u32,
+    out: Vec<u8>,
+    /// The most bytes the stream may inflate to.
+    limit: usize,
+}
+
+impl<'a> Inflater<'a> {
+    fn new(data: &'a [u8], limit: usize) -> Self {
+        Self {
+            data,
+            pos: 0,
+            bit: 0,
+            out: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Fails if writing `count` more bytes would pass the output limit.
+    fn reserve(&self, count: usize) -> VmfResult<()> {
+        if count > self.limit - self.out.len() {
+            return Err(invalid("image data is too long"));
+        }
+        Ok(())
+    }
+
+    fn bits(&mut self, count: u32) -> VmfResult<u32> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = *self
+                .data
+                .get(self.pos)
+                .ok_or_else(|| invalid("truncated deflate stream"))?;
+            value |= u32::from((byte >> self.bit) & 1) << i;
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.pos += 1;
+            }
+        }
+        Ok(value)
+    }
+
+    fn decode(&mut self, code: &Huffman) -> VmfResult<u16> {
+        let (mut value, mut first, mut index) = (0i32, 0i32, 0i32);
+        for length in 1..16 {
+            value |= self.bits(1)? as i32;
+            let count = i32::from(code.counts[length]);
+            if value - count < first {
+                return Ok(code.symbols[(index + value - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            value <<= 1;
+        }
+        Err(invalid("invalid Huffman code"))
+    }
+
+    fn inflate(mut self) -> VmfResult<Vec<u8>> {
+        loop {
+            let last = self.bits(1)? == 1;
+            match self.bits(2)? {
+                0 => self.stored()?,
+                1 => {
+                    let mut lengths = [0u8; 288];
+                    lengths[..144].fill(8);
+                    lengths[144..256].fill(9);
+                    lengths[256..280].fill(7);
+                    lengths[280..].fill(8);
+                    let lengths_code = Huffman::new(&lengths);
+                    let distance_code = Huffman::new(&[5; 30]);
+                    self.codes(&lengths_code, &distance_code)?;
+                }
+                2 => {
+                    let (lengths_code, distance_code) = self.dynamic_codes()?;
+                    self.codes(&lengths_code, &distance_code)?;
+                }
+                _ => return Err(invalid("invalid deflate block type")),
+            }
+            if last {
+                return Ok(self.out);
+            }
+        }
+    }
+
+    fn stored(&mut self) -> VmfResult<()> {
+        if self.bit != 0 {
+            self.bit = 0;
+            self.pos += 1;
+        }
+        let header = self
+            .data
+            .get(self.pos..self.pos + 4)
+            .ok_or_else(|| invalid("truncated stored block"))?;
+        let length = u16::from_le_bytes([header[0], header[1]]) as usize;
+        self.pos += 4;
+        let body = self
+            .data
+            .get(self.pos..self.pos + length)
+            .ok_or_else(|| invalid("truncated stored block"))?;
+        self.reserve(body.len())?;
+        self.out.extend_from_slice(body);
+        self.pos += length;
+        Ok(())
+    }
+
+    fn dynamic_codes(&mut self) -> VmfResult<(Huffman, Huffman)> {
+        let literal_count = self.bits(5)? as usize + 257;
+        let distance_count = self.bits(5)? as usize + 1;
+        let code_count = self.bits(4)? as usize + 4;
+
+        let mut code_lengths = [0u8; 19];
+        for &index in &CODE_LENGTH_ORDER[..code_count] {
+            code_lengths[index] = self.bits(3)? as u8;
+        }
+        let code_lengths = Huffman::new(&code_lengths);
+
+        let mut lengths = Vec::with_capacity(literal_count + distance_count);
+        while lengths.len() < literal_count + distance_count {
+            let symbol = self.decode(&code_lengths)?;
+            let (value, repeat) = match symbol {
+                0..=15 => (symbol as u8, 1),
+                16 => {
+                    let previous = *lengths
+                        .last()
+                        .ok_or_else(|| invalid("repeat with no previous length"))?;
+                    (previous, 3 + self.bits(2)?)
+                }
+                17 => (0, 3 + self.bits(3)?),
+                _ => (0, 11 + self.bits(7)?),
+            };
+            lengths.extend(std::iter::repeat_n(value, repeat as usize));
+        }
+        if lengths.len() != literal_count + distance_count {
+            return Err(invalid("too many code lengths"));
+        }
+        Ok((
+            Huffman::new(&lengths[..literal_count]),
+            Huffman::new(&lengths[literal_count..]),
+        ))
+    }
+
+    fn codes(&mut self, lengths_code: &Huffman, distance_code: &Huffman) -> VmfResult<()> {
+        loop {
+            let symbol = self.decode(lengths_code)? as usize;
+            match symbol {
+                0..=255 => {
+                    self.reserve(1)?;
+                    self.out.push(symbol as u8);
+                }
+                256 => return Ok(()),
+                _ => {
+                    let index = symbol - 257;
+                    if index >= LENGTH_BASE.len() {
+                        return Err(invalid("invalid length symbol"));
+                    }
+                    let length = LENGTH_BASE[index] as usize
+                        + self.bits(u32::from(LENGTH_EXTRA[index]))? as usize;
+                    let index = self.decode(distance_code)? as usize;
+                    if index >= DIST_BASE.len() {
+                        return Err(invalid("invalid distance symbol"));
+                    }
+                    let distance = DIST_BASE[index] as usize
+                        + self.bits(u32::from(DIST_EXTRA[index]))? as usize;
+                    if distance > self.out.len() {
+                        return Err(invalid("distance too far back"));
+                    }
+                    self.reserve(length)?;
+                    let start = self.out.len() - distance;
+                    for i in 0..length {
+                        let byte = self.out[start + i];
+                        self.out.push(byte);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use super::VmfFile;
+use crate::errors::VmfResult;
+use crate::geometry::{DispSew, SculptBrush, SculptOp, sculpt_solids, sew_solids};
+
+impl VmfFile {
+    /// Sculpts every visible displacement in the file, like Hammer's sculpt tool.
+    ///
+    /// Vertices within the brush are moved along the normal of their face, which
+    /// keeps them on the line `normals` and `distances` describe. Vertices shared by
+    /// neighboring displacements, even on different brushes, are welded and moved
+    /// together, so seams stay closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `brush` - The area to sculpt.
+    /// * `op` - What to do to the vertices in it.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the number of displacements changed, or a `VmfError`
+    /// if the brush or the operation is invalid, a plane is malformed or the
+    /// displacement data is malformed. The map is left unchanged on error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("displacements.vmf")?;
+    /// let brush = SculptBrush::new(Vector3::new(0.0, 0.0, 0.0), 256.0);
+    /// vmf.sculpt_displacements(&brush, SculptOp::Noise { amplitude: 8.0, seed: 1 })?;
+    /// vmf.sculpt_displacements(&brush, SculptOp::Smooth(0.5))?;
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn sculpt_displacements(&mut self, brush: &SculptBrush, op: SculptOp) -> VmfResult<usize> {
+        let solids = self
+            .solids_mut(false)
+            .filter(|solid| solid.sides.iter().any(|side| side.dispinfo.is_some()))
+            .collect();
+        sculpt_solids(solids, brush, op)
+    }
+
+    /// Sews neighboring displacements together and recomputes the fields Hammer
+    /// derives from their shape, including those of hidden objects.
+    ///
+    /// Edge vertices shared by several displacements move to their average, and edge
+    /// vertices lying between the vertices of a coarser or offset neighbor move onto
+    /// its edge. Those vertices are then removed from `allowed_verts`, along with the
+    /// vertices that depend on them, so the engine never uses them to open a crack.
+    /// `triangle_tags` are recomputed from the slope of each triangle, keeping the
+    /// walkable and buildable values forced in Hammer.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing what was changed, or a `VmfError` if a plane or the
+    /// displacement data is malformed. The map is left unchanged on error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let mut vmf = VmfFile::open("displacements.vmf")?;
+    /// let brush = SculptBrush::new(Vector3::new(0.0, 0.0, 0.0), 256.0);
+    /// vmf.sculpt_displacements(&brush, SculptOp::Raise(32.0))?;
+    /// let sew = vmf.sew_displacements()?;
+    /// println!("{} vertices sewn", sew.sewn_vertices);
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn sew_displacements(&mut self) -> VmfResult<DispSew> {
+        let solids = self
+            .solids_mut(true)
+            .filter(|solid| solid.sides.iter().any(|side| side.dispinfo.is_some()))
+            .collect();
+        sew_solids(solids)
+    }
+}
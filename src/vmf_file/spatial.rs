@@ -0,0 +1,751 @@
+//! An octree over the brushes and entities of a map for fast spatial queries.
+
+use std::collections::HashMap;
+
+use super::VmfFile;
+use crate::errors::{VmfError, VmfResult};
+use crate::geometry::{Aabb, BoundsOptions, ON_EPSILON, Plane, Vector3};
+use crate::prelude::{Entity, Side, Solid};
+
+/// The number of entries a node holds before it is split.
+const NODE_CAPACITY: usize = 8;
+
+/// The maximum depth of the octree.
+const MAX_DEPTH: usize = 12;
+
+/// Identifies an object in a `SpatialIndex`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SpatialKey {
+    /// A brush, by solid ID.
+    Solid(u64),
+    /// An entity, by entity ID.
+    Entity(u64),
+}
+
+/// An object stored in a `SpatialIndex`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpatialEntry {
+    /// The object.
+    pub key: SpatialKey,
+    /// The bounding box of the object when it was inserted.
+    pub bounds: Aabb,
+    /// The class name of an entity.
+    pub classname: Option<String>,
+    /// The ID of the entity owning a brush, `None` for world brushes.
+    pub owner: Option<u64>,
+}
+
+/// The first brush face hit by a ray.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// The ID of the brush that was hit.
+    pub solid_id: u64,
+    /// The ID of the side that was hit.
+    pub side_id: u32,
+    /// The distance from the ray origin to the hit.
+    pub distance: f64,
+    /// The point that was hit.
+    pub point: Vector3,
+    /// The normal of the face that was hit.
+    pub normal: Vector3,
+}
+
+/// An entry with the geometry needed for exact tests.
+#[derive(Debug, Clone)]
+struct Item {
+    entry: SpatialEntry,
+    node: usize,
+    /// The side planes of a brush, empty for entities.
+    planes: Vec<Plane>,
+    side_ids: Vec<u32>,
+    /// The vertices of a brush, or the origin of a point entity.
+    points: Vec<Vector3>,
+    /// The brushes of a brush entity.
+    owned: Vec<SpatialKey>,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    /// The region the node's entries lie in.
+    bounds: Aabb,
+    /// The octant the node was made for, before it was loosened.
+    cell: Aabb,
+    depth: usize,
+    children: Option<[usize; 8]>,
+    keys: Vec<SpatialKey>,
+}
+
+/// A loose octree over the brushes and entities of a map.
+///
+/// The index answers box, sphere, brush, point and ray queries without scanning the
+/// whole map. It holds IDs and copies of the geometry it needs rather than borrowing
+/// the file, so it stays usable while the map is edited; call `insert_solid`,
+/// `insert_entity` or `remove` for objects that change. Results can be turned back
+/// into references with `VmfFile::find_solid` and `VmfFile::find_entity`.
+///
+/// Brushes are tested against their side planes. Entities are tested by their
+/// origin, or by their brushes if they have any.
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    items: HashMap<SpatialKey, Item>,
+    nodes: Vec<Node>,
+}
+
+impl SpatialIndex {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds an index of the brushes and entities of a map.
+    ///
+    /// # Arguments
+    ///
+    /// * `vmf` - The map to index.
+    /// * `options` - Which objects to include, as for `VmfFile::bounds`.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the index, or a `VmfError` if a plane is malformed or
+    /// two brushes or two entities share an ID.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// let index = SpatialIndex::build(&vmf, &BoundsOptions::default())?;
+    /// let trigger = vmf.entities.find_by_classname("trigger_once").next().unwrap();
+    /// for solid in trigger.solids.iter().flatten() {
+    ///     for entry in index.query_solid(solid)? {
+    ///         println!("{:?} touches the trigger", entry.key);
+    ///     }
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn build(vmf: &VmfFile, options: &BoundsOptions) -> VmfResult<Self> {
+        let mut index = Self::new();
+        for solid in &vmf.world.solids {
+            index.ensure_new(SpatialKey::Solid(solid.id))?;
+            index.insert_solid(solid, None)?;
+        }
+        if options.include_hidden {
+            for solid in &vmf.world.hidden {
+                index.ensure_new(SpatialKey::Solid(solid.id))?;
+                index.insert_solid(solid, None)?;
+            }
+        }
+
+        let hidden = if options.include_hidden {
+            vmf.hiddens.as_slice()
+        } else {
+            &[]
+        };
+        for ent in vmf.entities.iter().chain(hidden) {
+            if ent.is_hidden && !options.include_hidden {
+                continue;
+            }
+            let solids = ent.solids.as_deref().unwrap_or_default();
+            let include = if solids.is_empty() {
+                options.include_point_entities
+            } else {
+                options.include_entity_brushes
+            };
+            if include {
+                index.ensure_new(SpatialKey::Entity(ent.id()))?;
+                for solid in solids {
+                    index.ensure_new(SpatialKey::Solid(solid.id))?;
+                }
+                index.insert_entity(ent)?;
+            }
+        }
+        Ok(index)
+    }
+
+    /// Fails if `key` is already in the index, so `build` doesn't silently replace
+    /// one object with another that has the same ID.
+    fn ensure_new(&self, key: SpatialKey) -> VmfResult<()> {
+        if !self.items.contains_key(&key) {
+            return Ok(());
+        }
+        let (kind, id) = match key {
+            SpatialKey::Solid(id) => ("solid", id),
+            SpatialKey::Entity(id) => ("entity", id),
+        };
+        Err(VmfError::InvalidFormat(format!(
+            "More than one {} has ID {}",
+            kind, id
+        )))
+    }
+
+    /// Returns the number of objects in the index.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the entry of an object, if it is in the index.
+    pub fn get(&self, key: SpatialKey) -> Option<&SpatialEntry> {
+        self.items.get(&key).map(|item| &item.entry)
+    }
+
+    /// Adds a brush to the index, replacing an earlier version of it.
+    ///
+    /// Brushes without any faces are left out. After editing an entity's brushes,
+    /// prefer `insert_entity` so the entity's bounds are updated as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `solid` - The brush.
+    /// * `owner` - The ID of the entity owning the brush, or `None` for world brushes.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if a plane is malformed.
+    pub fn insert_solid(&mut self, solid: &Solid, owner: Option<u64>) -> VmfResult<()> {
+        let key = SpatialKey::Solid(solid.id);
+        self.remove_item(key);
+
+        let planes = solid.planes()?;
+        let points = solid.vertices()?;
+        let Some(bounds) = Aabb::from_points(points.iter().copied()) else {
+            return Ok(());
+        };
+        self.insert_item(Item {
+            entry: SpatialEntry {
+                key,
+                bounds,
+                classname: None,
+                owner,
+            },
+            node: 0,
+            planes,
+            side_ids: solid.sides.iter().map(|s| s.id).collect(),
+            points,
+            owned: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Adds an entity and its brushes to the index, replacing an earlier version of it.
+    ///
+    /// Entities without brushes or an `origin` are left out.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` indicating success, or a `VmfError` if a plane is malformed.
+    pub fn insert_entity(&mut self, entity: &Entity) -> VmfResult<()> {
+        let key = SpatialKey::Entity(entity.id());
+        self.remove(key);
+
+        let solids = entity.solids.as_deref().unwrap_or_default();
+        for solid in solids {
+            self.insert_solid(solid, Some(entity.id()))?;
+        }
+        let points = if solids.is_empty() {
+            entity.origin().into_iter().collect()
+        } else {
+            Vec::new()
+        };
+        let owned: Vec<SpatialKey> = solids
+            .iter()
+            .map(|s| SpatialKey::Solid(s.id))
+            .filter(|key| self.items.contains_key(key))
+            .collect();
+        let bounds = owned
+            .iter()
+            .map(|key| self.items[key].entry.bounds)
+            .chain(points.iter().map(|&p| Aabb::from_point(p)))
+            .reduce(|a, b| a.union(&b));
+        let Some(bounds) = bounds else {
+            return Ok(());
+        };
+
+        self.insert_item(Item {
+            entry: SpatialEntry {
+                key,
+                bounds,
+                classname: entity.classname().map(str::to_string),
+                owner: None,
+            },
+            node: 0,
+            planes: Vec::new(),
+            side_ids: Vec::new(),
+            points,
+            owned,
+        });
+        Ok(())
+    }
+
+    /// Removes an object from the index. Removing an entity also removes its brushes.
+    ///
+    /// # Returns
+    ///
+    /// The entry of the object, or `None` if it wasn't in the index.
+    pub fn remove(&mut self, key: SpatialKey) -> Option<SpatialEntry> {
+        let item = self.remove_item(key)?;
+        for solid in &item.owned {
+            self.remove_item(*solid);
+        }
+        Some(item.entry)
+    }
+
+    /// Finds the objects whose bounding box overlaps `bounds`.
+    ///
+    /// # Returns
+    ///
+    /// The entries, sorted by key.
+    pub fn query_aabb(&self, bounds: &Aabb) -> Vec<&SpatialEntry> {
+        self.collect(bounds, |_| true)
+    }
+
+    /// Finds the objects within `radius` of `center`.
+    ///
+    /// Brushes are reported if no side plane or bounding box face is further away than
+    /// `radius`, which can include brushes just beyond a corner.
+    ///
+    /// # Returns
+    ///
+    /// The entries, sorted by key.
+    pub fn query_sphere(&self, center: Vector3, radius: f64) -> Vec<&SpatialEntry> {
+        let bounds = Aabb::from_point(center).expanded(radius);
+        self.collect(&bounds, |item| {
+            if aabb_distance(&item.entry.bounds, center) > radius {
+                return false;
+            }
+            match item.entry.key {
+                SpatialKey::Solid(_) => item.planes.iter().all(|p| p.distance_to(center) <= radius),
+                SpatialKey::Entity(_) if item.points.is_empty() => self
+                    .owned_items(item)
+                    .any(|solid| solid.planes.iter().all(|p| p.distance_to(center) <= radius)),
+                SpatialKey::Entity(_) => item.points.iter().any(|&p| p.distance(center) <= radius),
+            }
+        })
+    }
+
+    /// Finds the brushes containing `point`, and the entities owning them.
+    ///
+    /// Points on a face count as inside.
+    ///
+    /// # Returns
+    ///
+    /// The entries, sorted by key.
+    pub fn query_point(&self, point: Vector3) -> Vec<&SpatialEntry> {
+        self.collect(&Aabb::from_point(point), |item| match item.entry.key {
+            SpatialKey::Solid(_) => contains(&item.planes, point),
+            SpatialKey::Entity(_) => self
+                .owned_items(item)
+                .any(|solid| contains(&solid.planes, point)),
+        })
+    }
+
+    /// Finds the objects overlapping a brush, such as everything touching a trigger.
+    ///
+    /// Point entities are reported if their origin lies in the brush. Brushes are
+    /// reported unless a side plane of either brush separates them. The brush itself
+    /// is reported too if it is in the index.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the entries sorted by key, or a `VmfError` if a plane
+    /// of `solid` is malformed.
+    pub fn query_solid(&self, solid: &Solid) -> VmfResult<Vec<&SpatialEntry>> {
+        let planes = solid.planes()?;
+        let vertices = solid.vertices()?;
+        let Some(bounds) = Aabb::from_points(vertices.iter().copied()) else {
+            return Ok(Vec::new());
+        };
+
+        let overlaps =
+            |item: &Item| !separates(&planes, &item.points) && !separates(&item.planes, &vertices);
+        Ok(self.collect(&bounds, |item| match item.entry.key {
+            SpatialKey::Solid(_) => overlaps(item),
+            SpatialKey::Entity(_) if item.points.is_empty() => self.owned_items(item).any(overlaps),
+            SpatialKey::Entity(_) => item.points.iter().all(|&p| contains(&planes, p)),
+        }))
+    }
+
+    /// Finds the object nearest to `point` that passes `filter`.
+    ///
+    /// The distance is measured to the bounding box of each object, so it is exact
+    /// for point entities and a lower bound for brushes.
+    ///
+    /// # Returns
+    ///
+    /// The nearest entry and its distance, or `None` if nothing passes the filter.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// let index = SpatialIndex::build(&vmf, &BoundsOptions::default())?;
+    /// let nearest = index.nearest(Vector3::ZERO, |e| e.classname.as_deref() == Some("info_node"));
+    /// if let Some((entry, distance)) = nearest {
+    ///     println!("{:?} is {} units away", entry.key, distance);
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn nearest(
+        &self,
+        point: Vector3,
+        mut filter: impl FnMut(&SpatialEntry) -> bool,
+    ) -> Option<(&SpatialEntry, f64)> {
+        let mut best: Option<(&SpatialEntry, f64)> = None;
+        let mut stack = if self.nodes.is_empty() {
+            Vec::new()
+        } else {
+            vec![0]
+        };
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let node_distance = aabb_distance(&node.bounds, point);
+            if best.is_some_and(|(_, d)| node_distance >= d) {
+                continue;
+            }
+            for key in &node.keys {
+                let entry = &self.items[key].entry;
+                let distance = aabb_distance(&entry.bounds, point);
+                let closer = match best {
+                    Some((best_entry, d)) => {
+                        distance < d || (distance == d && entry.key < best_entry.key)
+                    }
+                    None => true,
+                };
+                if closer && filter(entry) {
+                    best = Some((entry, distance));
+                }
+            }
+            if let Some(children) = node.children {
+                stack.extend(children);
+            }
+        }
+        best
+    }
+
+    /// Casts a ray against the indexed brushes.
+    ///
+    /// Brushes containing the origin of the ray are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - Where the ray starts.
+    /// * `direction` - The direction of the ray. It doesn't have to be normalized.
+    /// * `max_distance` - How far the ray reaches.
+    ///
+    /// # Returns
+    ///
+    /// The first face hit, or `None` if the ray hits nothing.
+    pub fn ray_cast(
+        &self,
+        origin: Vector3,
+        direction: Vector3,
+        max_distance: f64,
+    ) -> Option<RayHit> {
+        if direction.length_squared() == 0.0 || self.nodes.is_empty() {
+            return None;
+        }
+        let direction = direction.normalize();
+        let mut best: Option<RayHit> = None;
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let reach = best.map_or(max_distance, |hit| hit.distance);
+            if ray_aabb(&node.bounds, origin, direction, reach).is_none() {
+                continue;
+            }
+            for key in &node.keys {
+                let item = &self.items[key];
+                let SpatialKey::Solid(solid_id) = item.entry.key else {
+                    continue;
+                };
+                let reach = best.map_or(max_distance, |hit| hit.distance);
+                if ray_aabb(&item.entry.bounds, origin, direction, reach).is_none() {
+                    continue;
+                }
+                if let Some((distance, side)) = ray_brush(&item.planes, origin, direction)
+                    && distance <= reach
+                {
+                    best = Some(RayHit {
+                        solid_id,
+                        side_id: item.side_ids[side],
+                        distance,
+                        point: origin + direction * distance,
+                        normal: item.planes[side].normal,
+                    });
+                }
+            }
+            if let Some(children) = node.children {
+                stack.extend(children);
+            }
+        }
+        best
+    }
+
+    /// Collects the entries in nodes overlapping `bounds` whose boxes overlap it and
+    /// that pass `test`, sorted by key.
+    fn collect(&self, bounds: &Aabb, mut test: impl FnMut(&Item) -> bool) -> Vec<&SpatialEntry> {
+        let mut result = Vec::new();
+        let mut stack = if self.nodes.is_empty() {
+            Vec::new()
+        } else {
+            vec![0]
+        };
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.bounds.intersects(bounds) {
+                continue;
+            }
+            for key in &node.keys {
+                let item = &self.items[key];
+                if item.entry.bounds.intersects(bounds) && test(item) {
+                    result.push(&item.entry);
+                }
+            }
+            if let Some(children) = node.children {
+                stack.extend(children);
+            }
+        }
+        result.sort_by_key(|entry| entry.key);
+        result
+    }
+
+    /// Returns the brushes owned by an entity item.
+    fn owned_items<'a>(&'a self, item: &'a Item) -> impl Iterator<Item = &'a Item> + 'a {
+        item.owned.iter().filter_map(|key| self.items.get(key))
+    }
+
+    fn insert_item(&mut self, mut item: Item) {
+        let fits = self
+            .nodes
+            .first()
+            .is_some_and(|root| root.bounds.contains(&item.entry.bounds));
+        if !fits {
+            self.rebuild(&item.entry.bounds);
+        }
+
+        let mut index = 0;
+        loop {
+            if self.nodes[index].children.is_none()
+                && self.nodes[index].keys.len() >= NODE_CAPACITY
+                && self.nodes[index].depth < MAX_DEPTH
+            {
+                self.split(index);
+            }
+            match self.child_for(index, &item.entry.bounds) {
+                Some(child) => index = child,
+                None => break,
+            }
+        }
+
+        self.nodes[index].keys.push(item.entry.key);
+        item.node = index;
+        self.items.insert(item.entry.key, item);
+    }
+
+    fn remove_item(&mut self, key: SpatialKey) -> Option<Item> {
+        let item = self.items.remove(&key)?;
+        let keys = &mut self.nodes[item.node].keys;
+        if let Some(position) = keys.iter().position(|&k| k == key) {
+            keys.remove(position);
+        }
+        Some(item)
+    }
+
+    /// Returns the child of a node that an entry belongs in: the one whose octant holds
+    /// the center of the entry, if the entry fits in that child's loose bounds.
+    fn child_for(&self, index: usize, bounds: &Aabb) -> Option<usize> {
+        let children = self.nodes[index].children?;
+        let center = self.nodes[index].cell.center();
+        let point = bounds.center();
+        let octant = (0..3)
+            .filter(|&axis| point[axis] >= center[axis])
+            .map(|axis| 1 << axis)
+            .sum::<usize>();
+        let child = children[octant];
+        self.nodes[child].bounds.contains(bounds).then_some(child)
+    }
+
+    /// Creates the children of a node and moves the entries that fit into them.
+    ///
+    /// Each child covers its octant grown by half its size on every side, so an entry
+    /// straddling the center planes can still move down if it is small enough.
+    fn split(&mut self, index: usize) {
+        let Node {
+            cell: bounds,
+            depth,
+            ..
+        } = self.nodes[index];
+        let center = bounds.center();
+        let first = self.nodes.len();
+        for octant in 0..8 {
+            let pick = |bit: usize, axis: usize| {
+                if octant & bit == 0 {
+                    (bounds.min[axis], center[axis])
+                } else {
+                    (center[axis], bounds.max[axis])
+                }
+            };
+            let (x, y, z) = (pick(1, 0), pick(2, 1), pick(4, 2));
+            let (min, max) = (Vector3::new(x.0, y.0, z.0), Vector3::new(x.1, y.1, z.1));
+            let margin = (max - min) * 0.5;
+            self.nodes.push(Node {
+                bounds: Aabb::new(min - margin, max + margin),
+                cell: Aabb::new(min, max),
+                depth: depth + 1,
+                children: None,
+                keys: Vec::new(),
+            });
+        }
+        let children: [usize; 8] = std::array::from_fn(|i| first + i);
+        self.nodes[index].children = Some(children);
+
+        let keys = std::mem::take(&mut self.nodes[index].keys);
+        for key in keys {
+            let bounds = self.items[&key].entry.bounds;
+            let target = self.child_for(index, &bounds).unwrap_or(index);
+            self.nodes[target].keys.push(key);
+            if let Some(item) = self.items.get_mut(&key) {
+                item.node = target;
+            }
+        }
+    }
+
+    /// Rebuilds the tree with a root large enough for every entry and `extra`.
+    fn rebuild(&mut self, extra: &Aabb) {
+        let bounds = self
+            .items
+            .values()
+            .fold(*extra, |acc, item| acc.union(&item.entry.bounds));
+        // A cube with some room to grow, so the tree isn't rebuilt on every insert.
+        let half = (bounds.size() * 0.5).max(Vector3::splat(1.0));
+        let half = half.x.max(half.y).max(half.z) * 2.0;
+        let center = bounds.center();
+
+        let bounds = Aabb::new(center - Vector3::splat(half), center + Vector3::splat(half));
+        self.nodes = vec![Node {
+            bounds,
+            cell: bounds,
+            depth: 0,
+            children: None,
+            keys: Vec::new(),
+        }];
+        let items: Vec<Item> = self.items.drain().map(|(_, item)| item).collect();
+        for item in items {
+            self.insert_item(item);
+        }
+    }
+}
+
+impl VmfFile {
+    /// Finds a world or entity brush by ID, including hidden ones.
+    pub fn find_solid(&self, id: u64) -> Option<&Solid> {
+        self.solids(true).find(|solid| solid.id == id)
+    }
+
+    /// Finds a world or entity brush by ID for editing, including hidden ones.
+    pub fn find_solid_mut(&mut self, id: u64) -> Option<&mut Solid> {
+        self.solids_mut(true).find(|solid| solid.id == id)
+    }
+
+    /// Finds an entity by ID, including hidden ones.
+    pub fn find_entity(&self, id: u64) -> Option<&Entity> {
+        self.entities
+            .iter()
+            .chain(self.hiddens.iter())
+            .find(|ent| ent.id() == id)
+    }
+
+    /// Finds an entity by ID for editing, including hidden ones.
+    pub fn find_entity_mut(&mut self, id: u64) -> Option<&mut Entity> {
+        self.entities
+            .iter_mut()
+            .chain(self.hiddens.iter_mut())
+            .find(|ent| ent.id() == id)
+    }
+
+    /// Finds a brush side by ID, including sides of hidden brushes.
+    pub fn find_side(&self, id: u32) -> Option<&Side> {
+        self.solids(true)
+            .flat_map(|solid| &solid.sides)
+            .find(|side| side.id == id)
+    }
+}
+
+/// Returns `true` if `point` is behind or on every plane.
+fn contains(planes: &[Plane], point: Vector3) -> bool {
+    !planes.is_empty() && planes.iter().all(|p| p.distance_to(point) <= ON_EPSILON)
+}
+
+/// Returns `true` if one of `planes` has every point in front of it.
+fn separates(planes: &[Plane], points: &[Vector3]) -> bool {
+    !points.is_empty()
+        && planes
+            .iter()
+            .any(|p| points.iter().all(|&v| p.distance_to(v) >= -ON_EPSILON))
+}
+
+/// Returns the distance from `point` to the nearest point of `bounds`.
+fn aabb_distance(bounds: &Aabb, point: Vector3) -> f64 {
+    let nearest = point.max(bounds.min).min(bounds.max);
+    nearest.distance(point)
+}
+
+/// Intersects a ray with a box, returning the distances where it enters and leaves it.
+fn ray_aabb(bounds: &Aabb, origin: Vector3, direction: Vector3, reach: f64) -> Option<(f64, f64)> {
+    let mut near = 0.0f64;
+    let mut far = reach;
+    for axis in 0..3 {
+        if direction[axis] == 0.0 {
+            if origin[axis] < bounds.min[axis] || origin[axis] > bounds.max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let a = (bounds.min[axis] - origin[axis]) / direction[axis];
+        let b = (bounds.max[axis] - origin[axis]) / direction[axis];
+        near = near.max(a.min(b));
+        far = far.min(a.max(b));
+        if near > far {
+            return None;
+        }
+    }
+    Some((near, far))
+}
+
+/// Intersects a ray with a convex brush, returning the distance and the index of the
+/// plane where it enters. Returns `None` if it misses or starts inside.
+fn ray_brush(planes: &[Plane], origin: Vector3, direction: Vector3) -> Option<(f64, usize)> {
+    let mut near = f64::NEG_INFINITY;
+    let mut far = f64::INFINITY;
+    let mut entry = None;
+    for (i, plane) in planes.iter().enumerate() {
+        let distance = plane.distance_to(origin);
+        let speed = plane.normal.dot(direction);
+        if speed == 0.0 {
+            if distance > 0.0 {
+                return None;
+            }
+            continue;
+        }
+        let t = -distance / speed;
+        if speed < 0.0 {
+            if t > near {
+                near = t;
+                entry = Some(i);
+            }
+        } else {
+            far = far.min(t);
+        }
+        if near > far {
+            return None;
+        }
+    }
+    entry.filter(|_| near >= 0.0).map(|i| (near, i))
+}
@@ -0,0 +1,126 @@
+use std::collections::VecDeque;
+
+use super::VmfFile;
+use crate::errors::{VmfError, VmfResult};
+use crate::geometry::{Plane, Polygon, Vector3};
+
+/// Two faces closer than this are considered to touch.
+const TOUCH_EPSILON: f64 = 0.01;
+
+impl VmfFile {
+    /// Copies the texture alignment of a side to every coplanar face connected to it.
+    ///
+    /// Starting from the side with ID `side_id`, faces of world and entity brushes that
+    /// lie on the same plane, face the same way and touch or overlap an already aligned
+    /// face get the same texture projection, so the texture wraps seamlessly across them.
+    ///
+    /// # Arguments
+    ///
+    /// * `side_id` - The ID of the side whose alignment is copied.
+    ///
+    /// # Returns
+    ///
+    /// A `VmfResult` containing the number of sides that were updated, or a `VmfError`
+    /// if the side doesn't exist or a brush has a malformed plane.
+    pub fn copy_alignment_to_coplanar(&mut self, side_id: u32) -> VmfResult<usize> {
+        // (solid index, side index, plane, polygon) for every face in the file.
+        let mut faces: Vec<(usize, usize, Plane, Polygon)> = Vec::new();
+        for (solid_index, solid) in self.solids(false).enumerate() {
+            for face in solid.faces()? {
+                faces.push((solid_index, face.side_index, face.plane, face.polygon));
+            }
+        }
+
+        let source_index = self
+            .solids(false)
+            .enumerate()
+            .find_map(|(solid_index, solid)| {
+                let side_index = solid.sides.iter().position(|s| s.id == side_id)?;
+                faces
+                    .iter()
+                    .position(|f| f.0 == solid_index && f.1 == side_index)
+            })
+            .ok_or_else(|| {
+                VmfError::InvalidFormat(format!("No face found for side {}", side_id))
+            })?;
+        let source_plane = faces[source_index].2;
+
+        let candidates: Vec<usize> = (0..faces.len())
+            .filter(|&i| faces[i].2.approx_eq(&source_plane, 1e-5, TOUCH_EPSILON))
+            .collect();
+
+        let mut reached = vec![source_index];
+        let mut queue = VecDeque::from([source_index]);
+        while let Some(current) = queue.pop_front() {
+            for &candidate in &candidates {
+                if !reached.contains(&candidate) && touches(&faces[current].3, &faces[candidate].3)
+                {
+                    reached.push(candidate);
+                    queue.push_back(candidate);
+                }
+            }
+        }
+
+        let (source_solid, source_side) = (faces[source_index].0, faces[source_index].1);
+        let source = self
+            .solids(false)
+            .nth(source_solid)
+            .map(|solid| solid.sides[source_side].clone())
+            .expect("source face belongs to a solid");
+
+        let targets: Vec<(usize, usize)> = reached[1..]
+            .iter()
+            .map(|&i| (faces[i].0, faces[i].1))
+            .collect();
+        for (solid_index, solid) in self.solids_mut(false).enumerate() {
+            for &(_, side_index) in targets.iter().filter(|t| t.0 == solid_index) {
+                solid.sides[side_index].copy_alignment_from(&source);
+            }
+        }
+        Ok(targets.len())
+    }
+}
+
+/// Returns `true` if two coplanar convex polygons share at least one point: on their
+/// boundaries, with one lying inside the other, or where their edges cross.
+fn touches(a: &Polygon, b: &Polygon) -> bool {
+    let on_boundary = |p: Vector3, polygon: &Polygon| {
+        polygon
+            .edges()
+            .any(|(start, end)| distance_to_segment(p, start, end) <= TOUCH_EPSILON)
+    };
+    let inside = |p: Vector3, polygon: &Polygon| {
+        let normal = polygon.normal();
+        polygon
+            .edges()
+            .all(|(start, end)| (end - start).cross(p - start).dot(normal) >= 0.0)
+    };
+    let reaches = |p: Vector3, polygon: &Polygon| on_boundary(p, polygon) || inside(p, polygon);
+    a.vertices.iter().any(|&p| reaches(p, b))
+        || b.vertices.iter().any(|&p| reaches(p, a))
+        || edges_cross(a, b)
+}
+
+/// Returns `true` if an edge of `a` properly crosses an edge of `b`, as when two
+/// coplanar faces overlap in a cross shape with no vertex inside the other.
+fn edges_cross(a: &Polygon, b: &Polygon) -> bool {
+    let normal = a.normal();
+    let side =
+        |start: Vector3, end: Vector3, p: Vector3| (end - start).cross(p - start).dot(normal);
+    a.edges().any(|(p1, p2)| {
+        b.edges().any(|(q1, q2)| {
+            side(p1, p2, q1) * side(p1, p2, q2) < 0.0 && side(q1, q2, p1) * side(q1, q2, p2) < 0.0
+        })
+    })
+}
+
+/// Returns the distance from `p` to the segment between `a` and `b`.
+fn distance_to_segment(p: Vector3, a: Vector3, b: Vector3) -> f64 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared == 0.0 {
+        return p.distance(a);
+    }
+    let t = ((p - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    p.distance(a + ab * t)
+}
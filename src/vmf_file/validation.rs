@@ -0,0 +1,42 @@
+use super::VmfFile;
+use crate::geometry::SolidProblem;
+
+impl VmfFile {
+    /// Validates every solid in the file, including hidden ones and those owned by entities.
+    ///
+    /// # Returns
+    ///
+    /// A vector of the problems found, in document order. Problems on entity
+    /// brushes carry the ID of the owning entity.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vmf_forge::prelude::*;
+    ///
+    /// let vmf = VmfFile::open("your_map.vmf")?;
+    /// for problem in vmf.validate_solids() {
+    ///     eprintln!("{}", problem);
+    /// }
+    /// # Ok::<(), VmfError>(())
+    /// ```
+    pub fn validate_solids(&self) -> Vec<SolidProblem> {
+        let mut problems: Vec<SolidProblem> = self
+            .world
+            .solids
+            .iter()
+            .chain(&self.world.hidden)
+            .flat_map(|solid| solid.validate())
+            .collect();
+
+        for ent in self.entities.iter().chain(self.hiddens.iter()) {
+            for solid in ent.solids.iter().flatten() {
+                problems.extend(solid.validate().into_iter().map(|problem| SolidProblem {
+                    entity_id: Some(ent.id()),
+                    ..problem
+                }));
+            }
+        }
+        problems
+    }
+}
@@ -186,7 +186,7 @@ impl VmfFile {
 /// Recursively collects the IDs of a VisGroup and all its children into a HashSet.
 /// The passed `group` must be the one found by ID/Name previously.
 /// Uses the `collected_ids` set to avoid infinite loops in case of (unlikely) cycles.
-fn collect_child_visgroup_ids(group: &VisGroup, collected_ids: &mut HashSet<i32>) {
+pub(super) fn collect_child_visgroup_ids(group: &VisGroup, collected_ids: &mut HashSet<i32>) {
     // Insert the current group's ID. If it was already present, stop to prevent cycles.
     if !collected_ids.insert(group.id) {
         return;
@@ -0,0 +1,180 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::prelude::*;
+
+    /// Builds an axis-aligned box with one material; side IDs start at `first_side_id`.
+    fn box_solid(id: u64, first_side_id: u32, min: Vector3, max: Vector3, material: &str) -> Solid {
+        let planes = [
+            Plane::new(Vector3::Z, max.z),
+            Plane::new(-Vector3::Z, -min.z),
+            Plane::new(-Vector3::X, -min.x),
+            Plane::new(Vector3::X, max.x),
+            Plane::new(Vector3::Y, max.y),
+            Plane::new(-Vector3::Y, -min.y),
+        ];
+        let sides = planes
+            .iter()
+            .enumerate()
+            .map(|(i, plane)| {
+                let mut side = Side {
+                    id: first_side_id + i as u32,
+                    material: material.to_string(),
+                    u_axis: "[1 0 0 0] 0.25".to_string(),
+                    v_axis: "[0 -1 0 0] 0.25".to_string(),
+                    ..Default::default()
+                };
+                side.set_plane_points(plane.to_points(64.0));
+                side.align_to_world().unwrap();
+                side
+            })
+            .collect();
+        Solid {
+            id,
+            sides,
+            ..Default::default()
+        }
+    }
+
+    fn wall() -> Solid {
+        box_solid(
+            2,
+            1,
+            Vector3::ZERO,
+            Vector3::new(256.0, 16.0, 128.0),
+            "brick/brickwall001a",
+        )
+    }
+
+    fn doorway() -> Solid {
+        box_solid(
+            100,
+            100,
+            Vector3::new(96.0, -8.0, 0.0),
+            Vector3::new(160.0, 24.0, 96.0),
+            "tools/toolsnodraw",
+        )
+    }
+
+    fn bounds(solid: &Solid) -> (Vector3, Vector3) {
+        let bounds = solid.bounds().unwrap().unwrap();
+        (bounds.min, bounds.max)
+    }
+
+    #[test]
+    fn carve_doorway() {
+        let mut vmf = VmfFile::default();
+        vmf.world.solids.push(wall());
+
+        assert_eq!(vmf.carve(&doorway(), None).unwrap(), 1);
+        let pieces = &vmf.world.solids;
+        assert_eq!(pieces.len(), 3);
+
+        assert_eq!(
+            pieces.iter().map(bounds).collect::<Vec<_>>(),
+            vec![
+                (
+                    Vector3::new(0.0, 0.0, 96.0),
+                    Vector3::new(256.0, 16.0, 128.0)
+                ),
+                (Vector3::new(0.0, 0.0, 0.0), Vector3::new(96.0, 16.0, 96.0)),
+                (
+                    Vector3::new(160.0, 0.0, 0.0),
+                    Vector3::new(256.0, 16.0, 96.0)
+                ),
+            ]
+        );
+        assert!(pieces.iter().all(Solid::is_valid));
+
+        // The lintel keeps the wall's IDs; the other pieces get fresh ones.
+        assert_eq!(pieces[0].id, 2);
+        assert_eq!(pieces[0].sides[0].id, 1);
+        assert_eq!((pieces[1].id, pieces[2].id), (3, 4));
+        let mut side_ids: Vec<u32> = pieces
+            .iter()
+            .flat_map(|s| s.sides.iter().map(|side| side.id))
+            .collect();
+        let count = side_ids.len();
+        side_ids.sort();
+        side_ids.dedup();
+        assert_eq!(side_ids.len(), count);
+
+        // Faces on the doorway take the cutter's material; the rest keep the wall's.
+        let lintel_bottom = pieces[0].sides.last().unwrap();
+        assert_eq!(lintel_bottom.material, "tools/toolsnodraw");
+        assert_eq!(
+            lintel_bottom.to_plane().unwrap().normal,
+            Vector3::new(0.0, 0.0, -1.0)
+        );
+        let wall_faces = pieces
+            .iter()
+            .flat_map(|s| &s.sides)
+            .filter(|side| side.material == "brick/brickwall001a")
+            .count();
+        assert_eq!(wall_faces, 13);
+    }
+
+    #[test]
+    fn carve_skips_untouched_and_unlisted_brushes() {
+        let mut vmf = VmfFile::default();
+        vmf.world.solids.push(wall());
+        let far = box_solid(
+            3,
+            7,
+            Vector3::new(1024.0, 0.0, 0.0),
+            Vector3::new(1088.0, 64.0, 64.0),
+            "brick/brickwall001a",
+        );
+        vmf.world.solids.push(far.clone());
+
+        // Touching the top of the wall isn't an overlap.
+        let above = box_solid(
+            50,
+            50,
+            Vector3::new(0.0, 0.0, 128.0),
+            Vector3::new(64.0, 16.0, 192.0),
+            "tools/toolsnodraw",
+        );
+        assert_eq!(vmf.carve(&above, None).unwrap(), 0);
+        assert_eq!(vmf.world.solids, vec![wall(), far.clone()]);
+
+        assert_eq!(vmf.carve(&doorway(), Some(&[3])).unwrap(), 0);
+        assert_eq!(vmf.world.solids.len(), 2);
+    }
+
+    #[test]
+    fn carve_removes_enclosed_brushes_and_cuts_entities() {
+        let mut vmf = VmfFile::default();
+        let small = box_solid(
+            3,
+            7,
+            Vector3::new(100.0, 0.0, 16.0),
+            Vector3::new(120.0, 8.0, 32.0),
+            "brick/brickwall001a",
+        );
+        vmf.world.solids.push(small);
+
+        let mut detail = Entity::new("func_detail", 4);
+        detail.solids = Some(vec![wall()]);
+        vmf.entities.push(detail);
+
+        assert_eq!(vmf.carve(&doorway(), None).unwrap(), 2);
+        assert!(vmf.world.solids.is_empty());
+        assert_eq!(vmf.entities[0].solids.as_ref().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn subtract_keeps_ids_untouched_without_overlap() {
+        let vmf = VmfFile::default();
+        let mut ids = vmf.id_allocator();
+        let far = box_solid(
+            3,
+            7,
+            Vector3::new(1024.0, 0.0, 0.0),
+            Vector3::new(1088.0, 64.0, 64.0),
+            "brick/brickwall001a",
+        );
+        assert_eq!(far.subtract(&doorway(), &mut ids).unwrap(), None);
+        assert_eq!(ids.next_object_id(), 2);
+    }
+}
@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::geometry::ClipResult;
+    use vmf_forge::prelude::*;
+    use vmf_forge::vmf::world::DispInfo;
+
+    fn side(id: u32, plane: &Plane) -> Side {
+        let mut side = Side {
+            id,
+            material: "dev/dev_measuregeneric01".to_string(),
+            u_axis: "[1 0 0 0] 0.25".to_string(),
+            v_axis: "[0 -1 0 0] 0.25".to_string(),
+            ..Default::default()
+        };
+        side.set_plane_points(plane.to_points(64.0));
+        side
+    }
+
+    /// A 64 unit cube at the origin in a document, as solid 2 with sides 1 to 6.
+    fn cube_vmf() -> VmfFile {
+        let planes = [
+            Plane::new(Vector3::Z, 64.0),
+            Plane::new(-Vector3::Z, 0.0),
+            Plane::new(-Vector3::X, 0.0),
+            Plane::new(Vector3::X, 64.0),
+            Plane::new(Vector3::Y, 64.0),
+            Plane::new(-Vector3::Y, 0.0),
+        ];
+        let mut vmf = VmfFile::default();
+        vmf.world.solids.push(Solid {
+            id: 2,
+            sides: planes
+                .iter()
+                .enumerate()
+                .map(|(i, p)| side(i as u32 + 1, p))
+                .collect(),
+            ..Default::default()
+        });
+        vmf
+    }
+
+    fn side_ids(solid: &Solid) -> Vec<u32> {
+        solid.sides.iter().map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn clip_keep_both() {
+        let vmf = cube_vmf();
+        let mut ids = vmf.id_allocator();
+        let cube = &vmf.world.solids[0];
+
+        let plane = Plane::new(Vector3::X, 16.0);
+        let pieces = cube
+            .clip(&plane, ClipMode::KeepBoth, "TOOLS/TOOLSNODRAW", &mut ids)
+            .unwrap();
+        let front = pieces.front.unwrap();
+        let back = pieces.back.unwrap();
+
+        // The front piece keeps the original IDs, minus the side it no longer touches.
+        assert_eq!(front.id, 2);
+        assert_eq!(side_ids(&front), vec![1, 2, 4, 5, 6, 7]);
+        assert_eq!(back.id, 3);
+        assert_eq!(side_ids(&back), vec![8, 9, 10, 11, 12, 13]);
+
+        let front_bounds = front.bounds().unwrap().unwrap();
+        assert_eq!(front_bounds.min, Vector3::new(16.0, 0.0, 0.0));
+        assert_eq!(front_bounds.max, Vector3::new(64.0, 64.0, 64.0));
+        let back_bounds = back.bounds().unwrap().unwrap();
+        assert_eq!(back_bounds.max, Vector3::new(16.0, 64.0, 64.0));
+        assert!(front.is_valid());
+        assert!(back.is_valid());
+
+        // The new faces sit on the plane, facing into the removed part, and are world aligned.
+        let cap = front.sides.last().unwrap();
+        assert_eq!(cap.material, "TOOLS/TOOLSNODRAW");
+        assert!(
+            cap.to_plane()
+                .unwrap()
+                .approx_eq(&plane.flipped(), 1e-9, 1e-6)
+        );
+        let [u, v] = cap.texture_axes().unwrap();
+        assert_eq!((u.axis, v.axis), (Vector3::Y, Vector3::new(0.0, 0.0, -1.0)));
+        assert_eq!(u.scale, 0.25);
+        assert!(
+            back.sides
+                .last()
+                .unwrap()
+                .to_plane()
+                .unwrap()
+                .approx_eq(&plane, 1e-9, 1e-6)
+        );
+    }
+
+    #[test]
+    fn clip_keep_one_side() {
+        let vmf = cube_vmf();
+        let mut ids = vmf.id_allocator();
+        let cube = &vmf.world.solids[0];
+
+        // A diagonal cut leaves two wedges.
+        let plane = Plane::from_point_normal(Vector3::splat(32.0), Vector3::new(1.0, 1.0, 0.0));
+        let pieces = cube
+            .clip(&plane, ClipMode::KeepBack, "dev/dev_blendmeasure", &mut ids)
+            .unwrap();
+        assert!(pieces.front.is_none());
+        let back = pieces.back.unwrap();
+        assert_eq!(back.id, 2);
+        assert_eq!(back.sides.len(), 5);
+        assert_eq!(back.vertices().unwrap().len(), 6);
+        assert!(back.is_valid());
+        assert!(
+            back.vertices()
+                .unwrap()
+                .iter()
+                .all(|&v| plane.distance_to(v) < 1e-6)
+        );
+
+        let pieces = cube
+            .clip(
+                &plane,
+                ClipMode::KeepFront,
+                "dev/dev_blendmeasure",
+                &mut ids,
+            )
+            .unwrap();
+        assert!(pieces.back.is_none());
+        assert!(pieces.front.unwrap().is_valid());
+    }
+
+    #[test]
+    fn clip_plane_missing_the_brush() {
+        let vmf = cube_vmf();
+        let mut ids = vmf.id_allocator();
+        let cube = &vmf.world.solids[0];
+
+        // Touching the top face doesn't cut anything.
+        let plane = Plane::new(Vector3::Z, 64.0);
+        let pieces = cube
+            .clip(&plane, ClipMode::KeepBoth, "TOOLS/TOOLSNODRAW", &mut ids)
+            .unwrap();
+        assert!(pieces.front.is_none());
+        assert_eq!(pieces.back.as_ref(), Some(cube));
+
+        let pieces = cube
+            .clip(&plane, ClipMode::KeepFront, "TOOLS/TOOLSNODRAW", &mut ids)
+            .unwrap();
+        assert_eq!(pieces, ClipResult::default());
+        // No IDs were used up.
+        assert_eq!(ids.next_object_id(), 3);
+    }
+
+    #[test]
+    fn clip_rejects_displacements() {
+        let mut vmf = cube_vmf();
+        let mut ids = vmf.id_allocator();
+        vmf.world.solids[0].sides[0].dispinfo = Some(DispInfo::default());
+
+        let result = vmf.world.solids[0].clip(
+            &Plane::new(Vector3::X, 16.0),
+            ClipMode::KeepBoth,
+            "TOOLS/TOOLSNODRAW",
+            &mut ids,
+        );
+        assert!(matches!(result, Err(VmfError::InvalidFormat(_))));
+    }
+}
@@ -0,0 +1,155 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::prelude::*;
+    use vmf_forge::vmf::regions::Cordon;
+
+    const MATERIAL: &str = "dev/dev_measuregeneric01";
+
+    fn aabb(min: [f64; 3], max: [f64; 3]) -> Aabb {
+        Aabb::new(Vector3::from(min), Vector3::from(max))
+    }
+
+    /// A map with a cordon around 0..256 and brushes and entities inside, outside and across it.
+    fn map() -> VmfFile {
+        let mut vmf = VmfFile::default();
+        let mut ids = vmf.id_allocator();
+        for (min, max) in [
+            ([0.0, 0.0, 0.0], [256.0, 256.0, 16.0]),
+            ([512.0, 0.0, 0.0], [768.0, 256.0, 16.0]),
+            ([128.0, 0.0, 16.0], [384.0, 64.0, 128.0]),
+        ] {
+            let solid = Solid::block(&aabb(min, max), MATERIAL, &mut ids).unwrap();
+            vmf.world.solids.push(solid);
+        }
+
+        for origin in [
+            Vector3::new(64.0, 64.0, 32.0),
+            Vector3::new(600.0, 64.0, 32.0),
+        ] {
+            let mut light = Entity::new("light", ids.next_object_id());
+            light.set_origin(origin);
+            vmf.entities.push(light);
+        }
+        let mut door = Entity::new("func_door", ids.next_object_id());
+        let panel = aabb([640.0, 0.0, 16.0], [656.0, 64.0, 128.0]);
+        door.solids = Some(vec![Solid::block(&panel, MATERIAL, &mut ids).unwrap()]);
+        vmf.entities.push(door);
+
+        vmf.cordons.active = 1;
+        vmf.cordons.push(Cordon {
+            name: "cordon".to_string(),
+            active: true,
+            min: "(0 0 0)".to_string(),
+            max: "(256 256 256)".to_string(),
+        });
+        vmf
+    }
+
+    fn bounds(solid: &Solid) -> Aabb {
+        solid.bounds().unwrap().unwrap()
+    }
+
+    #[test]
+    fn apply_cordons_keeps_crossing_brushes_whole() {
+        let mut vmf = map();
+        let before = vmf.clone();
+        assert_eq!(vmf.apply_cordons(&CordonOptions::default()).unwrap(), 1);
+
+        // The brush inside and the one crossing the cordon stay, followed by the seal.
+        let solids = &vmf.world.solids;
+        assert_eq!(solids.len(), 8);
+        assert_eq!(solids[0], before.world.solids[0]);
+        assert_eq!(solids[1], before.world.solids[2]);
+
+        let seal = &solids[2..];
+        assert!(seal.iter().all(Solid::is_valid));
+        assert!(
+            seal.iter()
+                .flat_map(|s| &s.sides)
+                .all(|side| side.material == "TOOLS/TOOLSSKYBOX")
+        );
+        let outside = seal.iter().map(bounds).reduce(|a, b| a.union(&b)).unwrap();
+        assert_eq!(outside, aabb([-16.0, -16.0, -16.0], [272.0, 272.0, 272.0]));
+
+        // Only the light inside is left; the door outside has no brushes left.
+        assert_eq!(vmf.entities.len(), 1);
+        assert_eq!(vmf.entities[0], before.entities[0]);
+
+        assert_eq!(vmf.cordons.active, 0);
+        assert!(!vmf.cordons[0].active);
+        // Applying again does nothing.
+        assert_eq!(vmf.apply_cordons(&CordonOptions::default()).unwrap(), 0);
+        assert_eq!(vmf.world.solids.len(), 8);
+    }
+
+    #[test]
+    fn apply_cordons_with_clipping() {
+        let mut vmf = map();
+        let options = CordonOptions {
+            material: "TOOLS/TOOLSNODRAW".to_string(),
+            thickness: 32.0,
+            clip_brushes: true,
+        };
+        vmf.apply_cordons(&options).unwrap();
+
+        let crossing = &vmf.world.solids[1];
+        assert_eq!(crossing.id, map().world.solids[2].id);
+        assert!(crossing.is_valid());
+        assert_eq!(
+            bounds(crossing),
+            aabb([128.0, 0.0, 16.0], [256.0, 64.0, 128.0])
+        );
+        assert_eq!(crossing.sides.last().unwrap().material, "TOOLS/TOOLSNODRAW");
+
+        let seal = vmf.world.solids[2..]
+            .iter()
+            .map(bounds)
+            .reduce(|a, b| a.union(&b))
+            .unwrap();
+        assert_eq!(seal, aabb([-32.0, -32.0, -32.0], [288.0, 288.0, 288.0]));
+    }
+
+    #[test]
+    fn apply_cordons_remaps_overlay_sides() {
+        // An overlay across the top of the brush crossing the cordon and the one outside it.
+        let mut vmf = map();
+        let crossing_top = vmf.world.solids[2].sides[0].clone();
+        let outside_top = vmf.world.solids[1].sides[0].id;
+        let mut overlay = Entity::new("info_overlay", 100);
+        overlay.set_origin(Vector3::new(200.0, 32.0, 128.0));
+        overlay.set(
+            "sides".to_string(),
+            format!("{} {}", crossing_top.id, outside_top),
+        );
+        vmf.entities.push(overlay);
+
+        let options = CordonOptions {
+            clip_brushes: true,
+            ..CordonOptions::default()
+        };
+        vmf.apply_cordons(&options).unwrap();
+        let tops: Vec<String> = vmf.world.solids[1]
+            .sides
+            .iter()
+            .filter(|side| side.plane == crossing_top.plane)
+            .map(|side| side.id.to_string())
+            .collect();
+        let overlay = vmf
+            .entities
+            .find_by_classname("info_overlay")
+            .next()
+            .unwrap();
+        assert_eq!(overlay.get("sides").unwrap(), &tops.join(" "));
+        assert_eq!(vmf.validate_overlays(), Vec::new());
+    }
+
+    #[test]
+    fn apply_cordons_without_active_cordons() {
+        let mut vmf = map();
+        vmf.cordons[0].active = false;
+        let before = vmf.clone();
+        assert_eq!(vmf.apply_cordons(&CordonOptions::default()).unwrap(), 0);
+        assert_eq!(vmf, before);
+    }
+}
@@ -0,0 +1,220 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::geometry::DispMesh;
+    use vmf_forge::prelude::*;
+    use vmf_forge::vmf::world::{DispInfo, DispRows};
+
+    /// The first brush of `displacements.vmf` and the index of its displaced side (ID 8).
+    fn displaced_brush() -> (Solid, usize) {
+        let vmf = VmfFile::open("vmf_examples/displacements.vmf").unwrap();
+        let solid = vmf.world.solids[0].clone();
+        let index = solid.sides.iter().position(|s| s.id == 8).unwrap();
+        (solid, index)
+    }
+
+    fn build(solid: &Solid, index: usize) -> DispMesh {
+        let side = &solid.sides[index];
+        side.dispinfo
+            .as_ref()
+            .unwrap()
+            .build_mesh(side, solid)
+            .unwrap()
+    }
+
+    /// A flat power 2 displacement with every vertex pushed `distance` along `normal`.
+    fn flat_disp(start: Vector3, normal: Vector3, distance: f64) -> DispInfo {
+        DispInfo {
+            power: 2,
+            start_position: format!("[{}]", start),
+            normals: DispRows::from_vectors(&vec![vec![normal; 5]; 5]),
+            distances: DispRows::from_floats(&vec![vec![distance; 5]; 5]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn example_displacement_surface() {
+        let (solid, index) = displaced_brush();
+        let mesh = build(&solid, index);
+
+        assert_eq!(mesh.size(), 9);
+        assert_eq!(mesh.vertices.len(), 81);
+        assert_eq!(mesh.alphas.len(), 81);
+        assert_eq!(mesh.triangles.len(), 128);
+
+        // The side lies on y = 0 and faces -Y; the grid starts at the start position,
+        // rows run up along Z and columns along X.
+        assert_eq!(mesh.flat_vertices[0], Vector3::new(-512.0, 0.0, 0.0));
+        assert_eq!(mesh.flat_vertices[8], Vector3::new(0.0, 0.0, 0.0));
+        assert_eq!(mesh.flat_vertices[72], Vector3::new(-512.0, 0.0, 512.0));
+        assert!(
+            mesh.vertex(0, 0)
+                .approx_eq(Vector3::new(-512.0, 11.5132, 0.0), 1e-9)
+        );
+        assert!(
+            mesh.vertex(0, 8)
+                .approx_eq(Vector3::new(0.0, 174.004, 0.0), 1e-9)
+        );
+        assert!(
+            mesh.vertex(8, 0)
+                .approx_eq(Vector3::new(-512.0, 11.8335, 512.0), 1e-9)
+        );
+        assert!(
+            mesh.vertex(4, 4)
+                .approx_eq(Vector3::new(-256.0, 180.036, 256.0), 1e-9)
+        );
+    }
+
+    #[test]
+    fn triangles_alternate_and_face_out() {
+        let (solid, index) = displaced_brush();
+        let mesh = build(&solid, index);
+
+        assert_eq!(
+            mesh.triangles[0..4],
+            [[0, 1, 10], [0, 10, 9], [1, 2, 10], [2, 11, 10]]
+        );
+        // The first quad of the second row starts on an odd index and flips again.
+        assert_eq!(mesh.triangles[16..18], [[9, 10, 18], [10, 19, 18]]);
+
+        for triangle in &mesh.triangles {
+            let [a, b, c] = triangle.map(|i| mesh.flat_vertices[i as usize]);
+            let normal = (b - a).cross(c - a);
+            assert!(normal.dot(-Vector3::Y) > 0.0);
+        }
+    }
+
+    #[test]
+    fn elevation_and_start_corner() {
+        let mut solid = VmfFile::open("vmf_examples/instances/frame.vmf")
+            .unwrap()
+            .world
+            .solids[0]
+            .clone();
+        // Top of the frame box, starting at the (+X, -Y) corner.
+        let mut disp = flat_disp(Vector3::new(40.0, -8.0, 8.0), Vector3::Z, 4.0);
+        disp.elevation = 2.0;
+        solid.sides[0].dispinfo = Some(disp);
+
+        let side = &solid.sides[0];
+        let mesh = side
+            .dispinfo
+            .as_ref()
+            .unwrap()
+            .build_mesh(side, &solid)
+            .unwrap();
+        assert_eq!(mesh.vertices.len(), 25);
+        assert_eq!(mesh.vertex(0, 0), Vector3::new(40.0, -8.0, 14.0));
+        assert!(mesh.vertices.iter().all(|v| v.z == 14.0));
+        // Clockwise from above: the first edge runs towards -X.
+        assert_eq!(mesh.vertex(4, 0), Vector3::new(-40.0, -8.0, 14.0));
+        assert_eq!(mesh.vertex(0, 4), Vector3::new(40.0, 8.0, 14.0));
+        assert!(mesh.normals().iter().all(|n| n.approx_eq(Vector3::Z, 1e-9)));
+
+        // Only the displaced surface of a displacement brush is exported.
+        let exported = solid.to_mesh(&ExportOptions::default()).unwrap();
+        assert_eq!(exported.triangle_count(), 32);
+        assert_eq!(exported.vertices.len(), 25);
+    }
+
+    #[test]
+    fn malformed_displacements() {
+        let (mut solid, index) = displaced_brush();
+        solid.sides[index].dispinfo.as_mut().unwrap().power = 2;
+        let side = &solid.sides[index];
+        assert!(matches!(
+            side.dispinfo.as_ref().unwrap().build_mesh(side, &solid),
+            Err(VmfError::InvalidFormat(_))
+        ));
+
+        // A side that doesn't belong to the solid.
+        let (solid, index) = displaced_brush();
+        let mut stray = solid.sides[index].clone();
+        stray.id = 999;
+        assert!(
+            stray
+                .dispinfo
+                .as_ref()
+                .unwrap()
+                .build_mesh(&stray, &solid)
+                .is_err()
+        );
+    }
+
+    /// A 256 by 128 unit floor brush.
+    fn floor() -> Solid {
+        let mut ids = VmfFile::default().id_allocator();
+        let bounds = Aabb::new(
+            Vector3::new(0.0, 0.0, -16.0),
+            Vector3::new(256.0, 128.0, 0.0),
+        );
+        Solid::block(&bounds, "nature/blendgrassdirt", &mut ids).unwrap()
+    }
+
+    #[test]
+    fn make_displacement_like_hammer() {
+        let mut solid = floor();
+        let disp = solid.sides[0].make_displacement(&solid, 3).unwrap();
+        assert_eq!(disp.power, 3);
+        assert_eq!(disp.start_position, "[0 0 0]");
+        assert_eq!(disp.flags, Some(0));
+        assert_eq!(
+            disp.normals.vectors().unwrap(),
+            vec![vec![Vector3::Z; 9]; 9]
+        );
+        assert_eq!(
+            disp.offset_normals.vectors().unwrap(),
+            vec![vec![Vector3::Z; 9]; 9]
+        );
+        assert_eq!(disp.distances.floats().unwrap(), vec![vec![0.0; 9]; 9]);
+        assert_eq!(
+            disp.offsets.vectors().unwrap(),
+            vec![vec![Vector3::ZERO; 9]; 9]
+        );
+        assert_eq!(disp.alphas.floats().unwrap(), vec![vec![0.0; 9]; 9]);
+        // A floor is walkable and buildable.
+        assert_eq!(disp.triangle_tags.floats().unwrap(), vec![vec![9.0; 16]; 8]);
+        assert_eq!(disp.allowed_verts["10"], vec![-1; 10]);
+
+        // A wall is neither.
+        let wall = solid.sides[2].make_displacement(&solid, 2).unwrap();
+        assert_eq!(wall.triangle_tags.floats().unwrap(), vec![vec![0.0; 8]; 4]);
+        assert_eq!(wall.normals.vectors().unwrap()[0][0], -Vector3::X);
+
+        solid.sides[0].dispinfo = Some(disp);
+        let mesh = build(&solid, 0);
+        assert_eq!(mesh.vertices, mesh.flat_vertices);
+        assert_eq!(mesh.vertex(0, 0), Vector3::new(0.0, 0.0, 0.0));
+        assert_eq!(mesh.vertex(8, 8), Vector3::new(256.0, 128.0, 0.0));
+    }
+
+    #[test]
+    fn make_displacement_rejects_bad_faces() {
+        let solid = floor();
+        assert!(matches!(
+            solid.sides[0].make_displacement(&solid, 5),
+            Err(VmfError::InvalidFormat(_))
+        ));
+
+        // Cutting the brush diagonally leaves triangular top and bottom faces.
+        let mut ids = VmfFile::default().id_allocator();
+        let plane = Plane::new(Vector3::new(1.0, 1.0, 0.0).normalize(), 128.0 / 2f64.sqrt());
+        let wedge = solid
+            .clip(&plane, ClipMode::KeepBack, "TOOLS/TOOLSNODRAW", &mut ids)
+            .unwrap()
+            .back
+            .unwrap();
+        let top = wedge
+            .faces()
+            .unwrap()
+            .into_iter()
+            .find(|face| face.plane.normal == Vector3::Z)
+            .unwrap()
+            .side_index;
+        assert!(matches!(
+            wedge.sides[top].make_displacement(&wedge, 2),
+            Err(VmfError::InvalidFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,234 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::export::HAMMER_UNITS_TO_METERS;
+    use vmf_forge::prelude::*;
+
+    /// The box from `frame.vmf`: 80x16x8 units, centered on X and Y, floor at 0.
+    fn frame_vmf() -> VmfFile {
+        VmfFile::open("vmf_examples/instances/frame.vmf").unwrap()
+    }
+
+    fn z_up() -> ExportOptions {
+        ExportOptions {
+            y_up: false,
+            ..Default::default()
+        }
+    }
+
+    fn position_bounds(mesh: &Mesh) -> Aabb {
+        Aabb::from_points(mesh.vertices.iter().map(|v| v.position)).unwrap()
+    }
+
+    #[test]
+    fn solid_mesh_with_uvs() {
+        let vmf = frame_vmf();
+        let mut options = z_up();
+        options
+            .material_sizes
+            .insert("METAL/BLACK_WALL_METAL_002C".to_string(), [64, 32]);
+
+        let mesh = vmf.world.solids[0].to_mesh(&options).unwrap();
+        assert_eq!(mesh.triangle_count(), 12);
+        assert_eq!(mesh.vertices.len(), 24);
+        assert_eq!(mesh.groups.len(), 1);
+        assert_eq!(mesh.groups[0].material, "metal/black_wall_metal_002c");
+
+        // The top face: normal up, U along X and V along -Y at 0.25 units per texel.
+        let corner = mesh
+            .vertices
+            .iter()
+            .find(|v| v.normal == Vector3::Z && v.position == Vector3::new(-40.0, 8.0, 8.0))
+            .unwrap();
+        assert_eq!(corner.uv, [-160.0 / 64.0, -32.0 / 32.0]);
+
+        // Triangles face the same way as their face.
+        let indices = &mesh.groups[0].indices;
+        for triangle in indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize]);
+            let normal = (b.position - a.position).cross(c.position - a.position);
+            assert!(normal.dot(a.normal) > 0.0);
+        }
+    }
+
+    #[test]
+    fn skips_tool_materials() {
+        let mut vmf = frame_vmf();
+        vmf.world.solids[0].sides[0].material = "tools/toolsnodraw".to_string();
+        vmf.world.solids[0].sides[1].material = "TOOLS/TOOLSSKYBOX".to_string();
+
+        let mesh = vmf.to_mesh(&z_up()).unwrap();
+        assert_eq!(mesh.triangle_count(), 8);
+
+        let options = ExportOptions {
+            skip_materials: Vec::new(),
+            ..z_up()
+        };
+        let mesh = vmf.to_mesh(&options).unwrap();
+        assert_eq!(mesh.triangle_count(), 12);
+        assert_eq!(mesh.groups.len(), 3);
+    }
+
+    #[test]
+    fn scale_and_up_axis() {
+        let vmf = frame_vmf();
+        let bounds = position_bounds(&vmf.to_mesh(&z_up()).unwrap());
+        assert_eq!(bounds.min, Vector3::new(-40.0, -8.0, 0.0));
+        assert_eq!(bounds.max, Vector3::new(40.0, 8.0, 8.0));
+
+        let options = ExportOptions {
+            unit_scale: HAMMER_UNITS_TO_METERS,
+            ..Default::default()
+        };
+        let mesh = vmf.to_mesh(&options).unwrap();
+        let bounds = position_bounds(&mesh);
+        assert!(
+            bounds
+                .min
+                .approx_eq(Vector3::new(-1.016, 0.0, -0.2032), 1e-9)
+        );
+        assert!(
+            bounds
+                .max
+                .approx_eq(Vector3::new(1.016, 0.2032, 0.2032), 1e-9)
+        );
+
+        // The top face now points along +Y.
+        assert!(mesh.vertices.iter().any(|v| v.normal == Vector3::Y));
+    }
+
+    #[test]
+    fn hidden_and_visgroup_filtering() {
+        let mut vmf = frame_vmf();
+        let solid = vmf.world.solids[0].clone();
+
+        let mut in_group = solid.clone();
+        in_group.editor.visgroup_id = Some(2);
+        let mut hidden_by_visgroup = solid.clone();
+        hidden_by_visgroup.editor.visgroup_shown = false;
+        vmf.world.solids.push(in_group);
+        vmf.world.solids.push(hidden_by_visgroup);
+        vmf.world.hidden.push(solid.clone());
+
+        let mut brush = Entity::new("func_detail", 10);
+        brush.solids = Some(vec![solid.clone()]);
+        vmf.entities.push(brush);
+
+        vmf.visgroups.groups.push(VisGroup {
+            name: "parent".to_string(),
+            id: 1,
+            color: "0 0 255".to_string(),
+            children: Some(vec![VisGroup {
+                name: "child".to_string(),
+                id: 2,
+                color: "0 255 0".to_string(),
+                children: None,
+            }]),
+        });
+
+        let count = |options: &ExportOptions| vmf.to_mesh(options).unwrap().triangle_count();
+
+        // The original box, the one in the visgroup and the func_detail.
+        assert_eq!(count(&z_up()), 36);
+        assert_eq!(
+            count(&ExportOptions {
+                exclude_visgroups: vec![1],
+                ..z_up()
+            }),
+            24
+        );
+        assert_eq!(
+            count(&ExportOptions {
+                include_entity_brushes: false,
+                ..z_up()
+            }),
+            24
+        );
+        assert_eq!(
+            count(&ExportOptions {
+                include_hidden: true,
+                ..z_up()
+            }),
+            60
+        );
+    }
+
+    #[test]
+    fn obj_output() {
+        let mesh = frame_vmf().to_mesh(&Default::default()).unwrap();
+        let obj = mesh.to_obj(Some("frame.mtl"));
+        let count = |prefix: &str| obj.lines().filter(|l| l.starts_with(prefix)).count();
+
+        assert!(obj.contains("mtllib frame.mtl\n"));
+        assert!(obj.contains("usemtl metal/black_wall_metal_002c\n"));
+        assert_eq!(count("v "), 24);
+        assert_eq!(count("vt "), 24);
+        assert_eq!(count("vn "), 24);
+        assert_eq!(count("f "), 12);
+        assert!(obj.contains("f 1/1/1 2/2/2 3/3/3\n"));
+
+        let mtl = mesh.to_mtl();
+        assert!(mtl.contains("newmtl metal/black_wall_metal_002c\n"));
+        assert!(mtl.contains("map_Kd metal/black_wall_metal_002c.png\n"));
+    }
+
+    #[test]
+    fn gltf_output() {
+        let mesh = frame_vmf().to_mesh(&Default::default()).unwrap();
+
+        let json = String::from_utf8(mesh.to_gltf(GltfFormat::Json)).unwrap();
+        assert!(json.starts_with(r#"{"asset":{"version":"2.0""#));
+        assert!(json.contains(r#""uri":"data:application/octet-stream;base64,"#));
+        assert!(json.contains(r#""count":24,"type":"VEC3","min":[-40,0,-8],"max":[40,8,8]"#));
+        assert!(json.contains(r#""count":36,"type":"SCALAR""#));
+        assert!(json.contains(r#""name":"metal/black_wall_metal_002c""#));
+
+        // 24 vertices * (12 + 12 + 8) bytes + 36 indices * 4 bytes.
+        let buffer_len = 24 * 32 + 36 * 4;
+        assert!(json.contains(&format!(r#""byteLength":{}"#, buffer_len)));
+
+        let glb = mesh.to_gltf(GltfFormat::Binary);
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(u32::from_le_bytes(glb[4..8].try_into().unwrap()), 2);
+        assert_eq!(
+            u32::from_le_bytes(glb[8..12].try_into().unwrap()) as usize,
+            glb.len()
+        );
+        let json_len = u32::from_le_bytes(glb[12..16].try_into().unwrap()) as usize;
+        assert_eq!(json_len % 4, 0);
+        assert_eq!(&glb[16..20], b"JSON");
+        let bin = 20 + json_len;
+        assert_eq!(
+            u32::from_le_bytes(glb[bin..bin + 4].try_into().unwrap()) as usize,
+            buffer_len
+        );
+        assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+
+        let empty = Mesh::new().to_gltf(GltfFormat::Json);
+        assert!(String::from_utf8(empty).unwrap().contains(r#""nodes":[]"#));
+    }
+
+    #[test]
+    fn export_files() {
+        let vmf = frame_vmf();
+        let dir = std::env::temp_dir().join("vmf_forge_export_test");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        vmf.export_obj(dir.join("frame.obj"), &Default::default())
+            .unwrap();
+        let obj = std::fs::read_to_string(dir.join("frame.obj")).unwrap();
+        assert!(obj.contains("mtllib frame.mtl"));
+        assert!(dir.join("frame.mtl").exists());
+
+        vmf.export_gltf(
+            dir.join("frame.glb"),
+            &Default::default(),
+            GltfFormat::Binary,
+        )
+        .unwrap();
+        assert_eq!(
+            &std::fs::read(dir.join("frame.glb")).unwrap()[0..4],
+            b"glTF"
+        );
+    }
+}
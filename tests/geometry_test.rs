@@ -0,0 +1,395 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::geometry::ON_EPSILON;
+    use vmf_forge::prelude::*;
+
+    fn side(id: u32, plane: &Plane) -> Side {
+        let mut side = Side {
+            id,
+            material: "dev/dev_measuregeneric01".to_string(),
+            ..Default::default()
+        };
+        side.set_plane_points(plane.to_points(64.0));
+        side
+    }
+
+    fn box_solid(min: Vector3, max: Vector3) -> Solid {
+        let planes = [
+            Plane::new(Vector3::Z, max.z),
+            Plane::new(-Vector3::Z, -min.z),
+            Plane::new(-Vector3::X, -min.x),
+            Plane::new(Vector3::X, max.x),
+            Plane::new(Vector3::Y, max.y),
+            Plane::new(-Vector3::Y, -min.y),
+        ];
+        Solid {
+            id: 1,
+            sides: planes
+                .iter()
+                .enumerate()
+                .map(|(i, p)| side(i as u32 + 1, p))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plane_from_hammer_points() {
+        // Top face of a box, wound clockwise from above.
+        let plane = Plane::from_points(
+            Vector3::new(-40.0, 8.0, 8.0),
+            Vector3::new(40.0, 8.0, 8.0),
+            Vector3::new(40.0, -8.0, 8.0),
+        )
+        .unwrap();
+        assert!(plane.normal.approx_eq(Vector3::Z, 1e-9));
+        assert_eq!(plane.dist, 8.0);
+
+        let [a, b, c] = plane.to_points(32.0);
+        let round_trip = Plane::from_points(a, b, c).unwrap();
+        assert!(round_trip.approx_eq(&plane, 1e-9, 1e-9));
+
+        assert!(Plane::from_points(Vector3::ZERO, Vector3::X, Vector3::X * 2.0).is_none());
+    }
+
+    #[test]
+    fn side_to_plane() {
+        let vmf = VmfFile::open("vmf_examples/instances/frame.vmf").unwrap();
+        let sides = &vmf.world.solids[0].sides;
+        assert!(
+            sides[0]
+                .to_plane()
+                .unwrap()
+                .normal
+                .approx_eq(Vector3::Z, 1e-9)
+        );
+        assert!(
+            sides[3]
+                .to_plane()
+                .unwrap()
+                .normal
+                .approx_eq(Vector3::X, 1e-9)
+        );
+
+        let degenerate = Side {
+            plane: "(0 0 0) (1 0 0) (2 0 0)".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            degenerate.to_plane(),
+            Err(VmfError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn polygon_split() {
+        let square = Polygon::new(vec![
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(64.0, 0.0, 0.0),
+            Vector3::new(64.0, 64.0, 0.0),
+            Vector3::new(0.0, 64.0, 0.0),
+        ]);
+        assert_eq!(square.area(), 4096.0);
+        assert!(square.normal().approx_eq(Vector3::Z, 1e-9));
+
+        let (front, back) = square.split(&Plane::new(Vector3::X, 16.0), ON_EPSILON);
+        assert_eq!(front.unwrap().area(), 48.0 * 64.0);
+        assert_eq!(back.unwrap().area(), 16.0 * 64.0);
+
+        let (front, back) = square.split(&Plane::new(Vector3::X, 128.0), ON_EPSILON);
+        assert!(front.is_none());
+        assert_eq!(back.unwrap(), square);
+    }
+
+    #[test]
+    fn box_faces_from_file() {
+        let vmf = VmfFile::open("vmf_examples/instances/frame.vmf").unwrap();
+        let solid = &vmf.world.solids[0];
+        let faces = solid.faces().unwrap();
+
+        assert_eq!(faces.len(), 6);
+        for (i, face) in faces.iter().enumerate() {
+            assert_eq!(face.side_index, i);
+            assert_eq!(face.side.id, solid.sides[i].id);
+            assert_eq!(face.polygon.len(), 4);
+            // Polygons are wound counter-clockwise seen from outside.
+            assert!(face.polygon.normal().approx_eq(face.plane.normal, 1e-9));
+        }
+        assert_eq!(faces[0].polygon.area(), 80.0 * 16.0);
+        assert_eq!(faces[2].polygon.area(), 16.0 * 8.0);
+
+        let vertices = solid.vertices().unwrap();
+        assert_eq!(vertices.len(), 8);
+        for v in &vertices {
+            assert_eq!(v.x.abs(), 40.0);
+            assert_eq!(v.y.abs(), 8.0);
+            assert!(v.z == 0.0 || v.z == 8.0);
+        }
+        assert_eq!(solid.edges().unwrap().len(), 12);
+    }
+
+    #[test]
+    fn wedge_faces() {
+        // A box cut in half along a diagonal plane.
+        let mut solid = box_solid(Vector3::ZERO, Vector3::splat(64.0));
+        solid.sides.remove(0);
+        solid.sides.push(side(
+            7,
+            &Plane::from_point_normal(Vector3::new(0.0, 0.0, 64.0), Vector3::new(1.0, 0.0, 1.0)),
+        ));
+
+        let faces = solid.faces().unwrap();
+        assert_eq!(faces.len(), 5);
+        // The +X side shrinks to a single edge, leaving a triangle on each Y side.
+        let counts: Vec<usize> = faces.iter().map(|f| f.polygon.len()).collect();
+        assert_eq!(counts, vec![4, 4, 3, 3, 4]);
+        assert_eq!(faces[2].side.id, 5);
+        assert_eq!(solid.vertices().unwrap().len(), 6);
+        assert_eq!(solid.edges().unwrap().len(), 9);
+    }
+
+    #[test]
+    fn redundant_sides_have_no_polygon() {
+        let mut solid = box_solid(Vector3::ZERO, Vector3::splat(64.0));
+        // Lies entirely outside the box, so it never touches it.
+        solid
+            .sides
+            .push(side(7, &Plane::new(Vector3::new(1.0, 1.0, 1.0), 512.0)));
+
+        let polygons = solid.side_polygons().unwrap();
+        assert_eq!(polygons.len(), 7);
+        assert!(polygons[6].is_none());
+        assert_eq!(solid.faces().unwrap().len(), 6);
+        assert_eq!(solid.vertices().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn off_axis_vertices_are_exact_enough() {
+        let mut solid = box_solid(Vector3::ZERO, Vector3::splat(64.0));
+        solid.sides[0] = side(
+            1,
+            &Plane::from_point_normal(Vector3::new(0.0, 0.0, 32.0), Vector3::new(0.0, -1.0, 2.0)),
+        );
+
+        for v in solid.vertices().unwrap() {
+            for axis in 0..3 {
+                let nearest = v[axis].round();
+                assert!((v[axis] - nearest).abs() < 1e-6, "{v:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn aabb_operations() {
+        let a = Aabb::new(Vector3::splat(64.0), Vector3::ZERO);
+        assert_eq!(a.min, Vector3::ZERO);
+        assert_eq!(a.size(), Vector3::splat(64.0));
+        assert_eq!(a.center(), Vector3::splat(32.0));
+        assert_eq!(a.volume(), 64.0 * 64.0 * 64.0);
+
+        let b = Aabb::new(Vector3::splat(32.0), Vector3::splat(128.0));
+        assert_eq!(a.union(&b), Aabb::new(Vector3::ZERO, Vector3::splat(128.0)));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Aabb::new(Vector3::splat(32.0), Vector3::splat(64.0)))
+        );
+        assert!(a.intersects(&b));
+        assert!(!a.contains(&b));
+        assert!(a.expanded(64.0).contains(&b));
+        assert!(a.contains_point(Vector3::new(0.0, 64.0, 10.0)));
+
+        let far = Aabb::from_point(Vector3::splat(256.0));
+        assert!(!a.intersects(&far));
+        assert_eq!(Aabb::from_points(Vec::new()), None);
+    }
+
+    #[test]
+    fn solid_and_entity_bounds() {
+        let solid = box_solid(
+            Vector3::new(-16.0, -16.0, 0.0),
+            Vector3::new(16.0, 16.0, 72.0),
+        );
+        let expected = Aabb::new(
+            Vector3::new(-16.0, -16.0, 0.0),
+            Vector3::new(16.0, 16.0, 72.0),
+        );
+        assert_eq!(solid.bounds().unwrap(), Some(expected));
+
+        let mut brush = Entity::new("func_detail", 10);
+        let mut moved = box_solid(Vector3::splat(64.0), Vector3::splat(128.0));
+        moved.id = 2;
+        brush.solids = Some(vec![solid, moved]);
+        let options = BoundsOptions::default();
+        assert_eq!(
+            brush.bounds(&options).unwrap(),
+            Some(Aabb::new(
+                Vector3::new(-16.0, -16.0, 0.0),
+                Vector3::splat(128.0)
+            ))
+        );
+
+        let mut point = Entity::new("info_player_start", 11);
+        point.set_origin(Vector3::new(8.0, 16.0, 32.0));
+        assert_eq!(
+            point.bounds(&options).unwrap(),
+            Some(Aabb::from_point(Vector3::new(8.0, 16.0, 32.0)))
+        );
+
+        let options = BoundsOptions {
+            include_entity_brushes: false,
+            include_point_entities: false,
+            ..Default::default()
+        };
+        assert_eq!(brush.bounds(&options).unwrap(), None);
+        assert_eq!(point.bounds(&options).unwrap(), None);
+    }
+
+    #[test]
+    fn map_bounds_and_cordons() {
+        let mut vmf = VmfFile::open("vmf_examples/valid.vmf").unwrap();
+        let world = Aabb::new(
+            Vector3::new(-274.0, -351.766, 0.0),
+            Vector3::new(256.0, 320.0, 384.0),
+        );
+        let options = BoundsOptions::default();
+        assert_eq!(vmf.world.bounds(&options).unwrap(), Some(world));
+        assert_eq!(vmf.bounds(&options).unwrap(), Some(world));
+
+        let cordon = &vmf.cordons[0];
+        assert!(cordon.contains(&world).unwrap());
+        assert!(
+            cordon
+                .bounds()
+                .unwrap()
+                .contains_point(Vector3::new(1070.0, 1336.0, 831.0))
+        );
+
+        // A hidden brush far away only counts when hidden objects are included.
+        let hidden = box_solid(Vector3::splat(2048.0), Vector3::splat(2112.0));
+        vmf.world.hidden.push(hidden);
+        assert_eq!(vmf.bounds(&options).unwrap(), Some(world));
+
+        let with_hidden = vmf
+            .bounds(&BoundsOptions {
+                include_hidden: true,
+                ..Default::default()
+            })
+            .unwrap()
+            .unwrap();
+        assert_eq!(with_hidden.max, Vector3::splat(2112.0));
+        assert!(!vmf.cordons[0].contains(&with_hidden).unwrap());
+        assert!(vmf.cordons[0].intersects(&with_hidden).unwrap());
+    }
+
+    fn kinds(solid: &Solid) -> Vec<SolidProblemKind> {
+        solid.validate().into_iter().map(|p| p.kind).collect()
+    }
+
+    #[test]
+    fn validate_box_and_example_maps() {
+        let solid = box_solid(Vector3::ZERO, Vector3::splat(64.0));
+        assert!(solid.is_valid());
+
+        for path in ["vmf_examples/complex.vmf", "vmf_examples/valid.vmf"] {
+            let vmf = VmfFile::open(path).unwrap();
+            assert_eq!(vmf.validate_solids(), Vec::new(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn validate_reports_structural_problems() {
+        let mut solid = box_solid(Vector3::ZERO, Vector3::splat(64.0));
+        solid.sides.truncate(3);
+        let problems = solid.validate();
+        assert_eq!(problems[0].kind, SolidProblemKind::TooFewSides);
+        assert_eq!(problems[0].side_ids, vec![1, 2, 3]);
+
+        let mut solid = box_solid(Vector3::ZERO, Vector3::splat(64.0));
+        solid.sides[2].plane = "(0 0 0) (0 0 0) (0 0 0)".to_string();
+        solid.sides[4].plane = "(0 0 0) (1 0 0)".to_string();
+        let problems = solid.validate();
+        assert_eq!(problems.len(), 2);
+        assert!(
+            problems
+                .iter()
+                .all(|p| p.kind == SolidProblemKind::InvalidPlane)
+        );
+        assert_eq!(problems[1].side_ids, vec![5]);
+    }
+
+    #[test]
+    fn validate_reports_duplicate_and_coplanar_planes() {
+        let mut solid = box_solid(Vector3::ZERO, Vector3::splat(64.0));
+        solid.sides.push(side(7, &Plane::new(Vector3::Z, 64.0)));
+        let problems = solid.validate();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].kind, SolidProblemKind::DuplicatePlane);
+        assert_eq!(problems[0].side_ids, vec![1, 7]);
+
+        // A box squashed to zero height.
+        let flat = box_solid(Vector3::ZERO, Vector3::new(64.0, 64.0, 0.0));
+        let kinds = kinds(&flat);
+        assert!(kinds.contains(&SolidProblemKind::CoplanarPlanes));
+        assert!(kinds.contains(&SolidProblemKind::ZeroAreaFace));
+        let problem = flat
+            .validate()
+            .into_iter()
+            .find(|p| p.kind == SolidProblemKind::CoplanarPlanes)
+            .unwrap();
+        assert_eq!(problem.side_ids, vec![1, 2]);
+        assert_eq!(
+            problem.to_string(),
+            "solid 1: coplanar opposing planes (sides 1, 2)"
+        );
+    }
+
+    #[test]
+    fn validate_reports_open_and_redundant_sides() {
+        // Without its top, the box is open upwards.
+        let mut solid = box_solid(Vector3::ZERO, Vector3::splat(64.0));
+        solid.sides.remove(0);
+        let problems = solid.validate();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].kind, SolidProblemKind::Open);
+        assert_eq!(problems[0].side_ids, vec![3, 4, 5, 6]);
+
+        // A flipped top leaves nothing behind every plane but an unbounded slab.
+        let mut solid = box_solid(Vector3::ZERO, Vector3::splat(64.0));
+        solid.sides[0] = side(1, &Plane::new(-Vector3::Z, -64.0));
+        assert!(kinds(&solid).contains(&SolidProblemKind::Open));
+
+        let mut solid = box_solid(Vector3::ZERO, Vector3::splat(64.0));
+        solid
+            .sides
+            .push(side(7, &Plane::new(Vector3::new(1.0, 1.0, 1.0), 512.0)));
+        let problems = solid.validate();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].kind, SolidProblemKind::ZeroAreaFace);
+        assert_eq!(problems[0].side_ids, vec![7]);
+    }
+
+    #[test]
+    fn validate_solids_reports_entity_brushes() {
+        let mut vmf = VmfFile::default();
+        vmf.world
+            .solids
+            .push(box_solid(Vector3::ZERO, Vector3::splat(64.0)));
+
+        let mut bad = box_solid(Vector3::ZERO, Vector3::splat(64.0));
+        bad.id = 5;
+        bad.sides.truncate(2);
+        let mut ent = Entity::new("func_detail", 4);
+        ent.solids = Some(vec![bad]);
+        vmf.entities.push(ent);
+
+        let problems = vmf.validate_solids();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].solid_id, 5);
+        assert_eq!(problems[0].entity_id, Some(4));
+        assert_eq!(
+            problems[0].to_string(),
+            "solid 5 (entity 4): fewer than four sides (sides 1, 2)"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::prelude::*;
+
+    const MATERIAL: &str = "dev/dev_measuregeneric01";
+
+    fn block(min: [f64; 3], max: [f64; 3], ids: &mut IdAllocator) -> Solid {
+        let bounds = Aabb::new(Vector3::from(min), Vector3::from(max));
+        Solid::block(&bounds, MATERIAL, ids).unwrap()
+    }
+
+    /// A map with an on-grid floor, a box nudged off the grid and a thin sliver.
+    fn map() -> VmfFile {
+        let mut vmf = VmfFile::default();
+        let mut ids = vmf.id_allocator();
+        vmf.world
+            .solids
+            .push(block([0.0, 0.0, -16.0], [256.0, 256.0, 0.0], &mut ids));
+        vmf.world
+            .solids
+            .push(block([64.3, 64.0, 0.0], [128.3, 128.0, 64.0], &mut ids));
+
+        let mut detail = Entity::new("func_detail", ids.next_object_id());
+        detail.solids = Some(vec![block([0.0, 0.0, 0.0], [64.0, 1.0, 64.0], &mut ids)]);
+        vmf.entities.push(detail);
+        vmf
+    }
+
+    #[test]
+    fn find_off_grid() {
+        let vmf = map();
+        let found = vmf.find_off_grid(None).unwrap();
+        assert_eq!(found.len(), 2);
+
+        // Every plane of the nudged box has a corner on it off the grid, as do all eight vertices.
+        let nudged = &vmf.world.solids[1];
+        assert_eq!(found[0].solid_id, nudged.id);
+        assert_eq!(found[0].entity_id, None);
+        let all: Vec<u32> = nudged.sides.iter().map(|s| s.id).collect();
+        assert_eq!(found[0].side_ids, all);
+        assert_eq!(found[0].vertices.len(), 8);
+
+        let sliver = &vmf.entities[0].solids.as_ref().unwrap()[0];
+        assert_eq!(found[1].solid_id, sliver.id);
+        assert_eq!(found[1].entity_id, Some(vmf.entities[0].id()));
+        // Only its -Y side lies entirely on the grid.
+        let off: Vec<u32> = sliver.sides[..5].iter().map(|s| s.id).collect();
+        assert_eq!(found[1].side_ids, off);
+        assert_eq!(found[1].vertices.len(), 4);
+
+        // A finer grid only catches the nudged box.
+        let found = vmf.find_off_grid(Some(1.0)).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].solid_id, nudged.id);
+        assert_eq!(
+            found[0].to_string(),
+            "solid 3: 6 off-grid sides, 8 off-grid vertices"
+        );
+    }
+
+    #[test]
+    fn grid_spacing_is_the_default() {
+        let mut vmf = map();
+        vmf.viewsettings.grid_spacing = 1;
+        assert_eq!(
+            vmf.find_off_grid(None).unwrap(),
+            vmf.find_off_grid(Some(1.0)).unwrap()
+        );
+    }
+
+    #[test]
+    fn snap_to_grid() {
+        let mut vmf = map();
+        let before = vmf.clone();
+        let result = vmf.snap_to_grid(None).unwrap();
+
+        // The box snaps back onto the grid; the floor was already on it.
+        assert_eq!(result.snapped, vec![vmf.world.solids[1].id]);
+        assert_eq!(vmf.world.solids[0], before.world.solids[0]);
+        let bounds = vmf.world.solids[1].bounds().unwrap().unwrap();
+        assert_eq!(
+            bounds,
+            Aabb::new(
+                Vector3::new(64.0, 64.0, 0.0),
+                Vector3::new(128.0, 128.0, 64.0)
+            )
+        );
+
+        // The sliver would collapse to nothing, so it is left alone and reported.
+        let sliver = &vmf.entities[0].solids.as_ref().unwrap()[0];
+        assert_eq!(vmf.entities[0], before.entities[0]);
+        assert!(!result.failed.is_empty());
+        assert!(
+            result
+                .failed
+                .iter()
+                .all(|p| p.solid_id == sliver.id && p.entity_id == Some(vmf.entities[0].id()))
+        );
+
+        let found = vmf.find_off_grid(None).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].solid_id, sliver.id);
+    }
+
+    #[test]
+    fn rejects_bad_grid_sizes() {
+        let mut vmf = map();
+        for grid in [0.0, -8.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                vmf.find_off_grid(Some(grid)),
+                Err(VmfError::InvalidFormat(_))
+            ));
+            assert!(matches!(
+                vmf.snap_to_grid(Some(grid)),
+                Err(VmfError::InvalidFormat(_))
+            ));
+        }
+        vmf.viewsettings.grid_spacing = 0;
+        assert!(vmf.find_off_grid(None).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::geometry::DispMesh;
+    use vmf_forge::prelude::*;
+    use vmf_forge::vmf_file::{Heightmap, HeightmapOptions};
+
+    fn open(name: &str) -> Heightmap {
+        let data = std::fs::read(format!("vmf_examples/{}", name)).unwrap();
+        Heightmap::from_png(&data).unwrap()
+    }
+
+    /// A heightmap rising linearly from 0 at the left edge to 1 at the right edge.
+    fn ramp(width: usize, height: usize) -> Heightmap {
+        let samples = (0..width * height)
+            .map(|i| (i % width) as f64 / (width - 1) as f64)
+            .collect();
+        Heightmap::new(width, height, samples).unwrap()
+    }
+
+    fn bounds() -> Aabb {
+        Aabb::new(
+            Vector3::new(0.0, 0.0, -16.0),
+            Vector3::new(512.0, 256.0, 0.0),
+        )
+    }
+
+    fn options() -> HeightmapOptions {
+        HeightmapOptions {
+            power: 2,
+            ..HeightmapOptions::new(bounds(), [2, 1], 256.0)
+        }
+    }
+
+    fn top_mesh(solid: &Solid) -> DispMesh {
+        let side = &solid.sides[0];
+        side.dispinfo
+            .as_ref()
+            .unwrap()
+            .build_mesh(side, solid)
+            .unwrap()
+    }
+
+    #[test]
+    fn decode_png() {
+        // Dynamic Huffman blocks and every row filter.
+        let image = open("heightmap_gray8.png");
+        assert_eq!((image.width, image.height), (65, 65));
+        for (i, &sample) in image.samples.iter().enumerate() {
+            let (x, y) = (i % 65, i / 65);
+            let expected = (x * x * 7 + y * y * 3 + x * y) % 256;
+            assert_eq!(sample, expected as f64 / 255.0);
+        }
+
+        // Fixed Huffman blocks.
+        let image = open("heightmap_small.png");
+        assert_eq!(
+            image.samples[17 * 16 + 16],
+            ((16 * 13 + 16 * 7) % 256) as f64 / 255.0
+        );
+
+        // Stored blocks with 16-bit RGB, averaged to gray.
+        let image = open("heightmap_rgb16.png");
+        assert_eq!((image.width, image.height), (9, 9));
+        assert_eq!(
+            image.samples[9 * 4 + 5],
+            (5 * 4000 + 4 * 100) as f64 / 65535.0
+        );
+
+        assert!(matches!(
+            Heightmap::from_png(b"not a png"),
+            Err(VmfError::InvalidFormat(_))
+        ));
+        let mut truncated = std::fs::read("vmf_examples/heightmap_gray8.png").unwrap();
+        truncated.truncate(200);
+        assert!(Heightmap::from_png(&truncated).is_err());
+
+        // A header claiming a 16-bit RGBA image of 2^32 - 1 pixels squared.
+        let mut huge = std::fs::read("vmf_examples/heightmap_rgb16.png").unwrap();
+        huge[16..24].fill(0xFF);
+        huge[25] = 6;
+        assert!(matches!(
+            Heightmap::from_png(&huge),
+            Err(VmfError::InvalidFormat(_))
+        ));
+        // Image data that isn't a valid zlib stream.
+        let mut corrupt = std::fs::read("vmf_examples/heightmap_gray8.png").unwrap();
+        let data = corrupt.windows(4).position(|w| w == b"IDAT").unwrap() + 4;
+        corrupt[data..data + 16].fill(0xFF);
+        assert!(Heightmap::from_png(&corrupt).is_err());
+        // A header claiming two rows over image data that inflates to 65.
+        let mut bomb = std::fs::read("vmf_examples/heightmap_gray8.png").unwrap();
+        bomb[20..24].copy_from_slice(&2u32.to_be_bytes());
+        assert!(matches!(
+            Heightmap::from_png(&bomb),
+            Err(VmfError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn raw16_and_sampling() {
+        let data: Vec<u8> = [0u16, 65535, 32768, 0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let heightmap = Heightmap::from_raw16(&data, 2, 2).unwrap();
+        assert_eq!(heightmap.samples[1], 1.0);
+        assert_eq!(heightmap.sample(1.0, 0.0), 1.0);
+        assert_eq!(heightmap.sample(0.5, 0.0), 0.5);
+        assert_eq!(heightmap.sample(2.0, -1.0), 1.0);
+
+        assert!(Heightmap::from_raw16(&data, 3, 2).is_err());
+        assert!(Heightmap::from_raw16(&data[..7], 2, 2).is_err());
+        assert!(Heightmap::new(1, 1, vec![0.0]).is_err());
+    }
+
+    #[test]
+    fn import_creates_tiles_with_matching_seams() {
+        let mut vmf = VmfFile::default();
+        let ids = vmf.import_heightmap(&ramp(9, 5), &options()).unwrap();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(vmf.world.solids.len(), 2);
+
+        for solid in &vmf.world.solids {
+            let mesh = top_mesh(solid);
+            assert_eq!(mesh.power, 2);
+            // The ramp climbs 256 units over 512, so each vertex sits at half its X.
+            for vertex in &mesh.vertices {
+                assert_eq!(vertex.z, vertex.x / 2.0);
+            }
+            let disp = solid.sides[0].dispinfo.as_ref().unwrap();
+            assert!(
+                disp.normals
+                    .vectors()
+                    .unwrap()
+                    .concat()
+                    .iter()
+                    .all(|n| *n == Vector3::Z)
+            );
+            assert_eq!(disp.triangle_tags.rows.len(), 4);
+            // A slope of one in two is still walkable and buildable.
+            let tags = disp.triangle_tags.floats().unwrap().concat();
+            assert!(tags.iter().all(|&tag| tag == 9.0));
+        }
+
+        // Four times as high, the ramp is too steep for either.
+        let mut steep = VmfFile::default();
+        let options = HeightmapOptions {
+            height: 1024.0,
+            ..options()
+        };
+        steep.import_heightmap(&ramp(9, 5), &options).unwrap();
+        let disp = steep.world.solids[0].sides[0].dispinfo.as_ref().unwrap();
+        let tags = disp.triangle_tags.floats().unwrap().concat();
+        assert!(tags.iter().all(|&tag| tag == 0.0));
+
+        let west = top_mesh(&vmf.world.solids[0]);
+        let east = top_mesh(&vmf.world.solids[1]);
+        let seam = |mesh: &DispMesh| {
+            let mut edge: Vec<Vector3> = mesh
+                .vertices
+                .iter()
+                .copied()
+                .filter(|v| v.x == 256.0)
+                .collect();
+            edge.sort_by(|a, b| a.y.total_cmp(&b.y));
+            edge
+        };
+        assert_eq!(seam(&west).len(), 5);
+        assert_eq!(seam(&west), seam(&east));
+    }
+
+    #[test]
+    fn import_updates_existing_tiles() {
+        let mut vmf = VmfFile::default();
+        let mut ids = vmf.id_allocator();
+        let tile = Aabb::new(
+            Vector3::new(0.0, 0.0, -16.0),
+            Vector3::new(256.0, 256.0, 0.0),
+        );
+        vmf.world
+            .solids
+            .push(Solid::block(&tile, "nature/dirt", &mut ids).unwrap());
+
+        let mut options = options();
+        options.height = -64.0;
+        options.alphas = Some(ramp(2, 2));
+        let ids = vmf.import_heightmap(&ramp(9, 5), &options).unwrap();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(vmf.world.solids[0].sides[0].material, "nature/dirt");
+        assert_eq!(
+            vmf.world.solids[1].sides[0].material,
+            "DEV/DEV_BLENDMEASURE"
+        );
+
+        // Negative heights point the normals down instead of storing negative distances.
+        let mesh = top_mesh(&vmf.world.solids[1]);
+        assert!(mesh.vertices.iter().all(|v| v.z == -v.x / 8.0));
+        let disp = vmf.world.solids[1].sides[0].dispinfo.as_ref().unwrap();
+        assert!(
+            disp.distances
+                .floats()
+                .unwrap()
+                .concat()
+                .iter()
+                .all(|&d| d >= 0.0)
+        );
+        assert!(mesh.alphas.iter().all(|&a| a > 127.0));
+        assert_eq!(mesh.alphas.iter().cloned().fold(0.0, f64::max), 255.0);
+
+        // Importing again replaces the displacements without adding brushes.
+        assert_eq!(
+            vmf.import_heightmap(&ramp(9, 5), &options).unwrap(),
+            vec![2, 3]
+        );
+        assert_eq!(vmf.world.solids.len(), 2);
+    }
+
+    #[test]
+    fn import_rejects_bad_options() {
+        let mut vmf = VmfFile::default();
+        let heightmap = ramp(9, 5);
+        for options in [
+            HeightmapOptions {
+                tiles: [0, 1],
+                ..options()
+            },
+            HeightmapOptions {
+                power: 5,
+                ..options()
+            },
+            HeightmapOptions {
+                height: f64::NAN,
+                ..options()
+            },
+        ] {
+            assert!(matches!(
+                vmf.import_heightmap(&heightmap, &options),
+                Err(VmfError::InvalidFormat(_))
+            ));
+        }
+        assert!(vmf.world.solids.is_empty());
+    }
+}
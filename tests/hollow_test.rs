@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::prelude::*;
+
+    const MATERIALS: [&str; 6] = [
+        "dev/ceiling",
+        "dev/floor",
+        "dev/west",
+        "dev/east",
+        "dev/north",
+        "dev/south",
+    ];
+
+    /// A 256 unit room brush at the origin with a different material on each side.
+    fn room() -> Solid {
+        let planes = [
+            Plane::new(Vector3::Z, 256.0),
+            Plane::new(-Vector3::Z, 0.0),
+            Plane::new(-Vector3::X, 0.0),
+            Plane::new(Vector3::X, 256.0),
+            Plane::new(Vector3::Y, 256.0),
+            Plane::new(-Vector3::Y, 0.0),
+        ];
+        let sides = planes
+            .iter()
+            .zip(MATERIALS)
+            .enumerate()
+            .map(|(i, (plane, material))| {
+                let mut side = Side {
+                    id: i as u32 + 1,
+                    material: material.to_string(),
+                    u_axis: "[1 0 0 0] 0.25".to_string(),
+                    v_axis: "[0 -1 0 0] 0.25".to_string(),
+                    ..Default::default()
+                };
+                side.set_plane_points(plane.to_points(64.0));
+                side.align_to_world().unwrap();
+                side
+            })
+            .collect();
+        Solid {
+            id: 7,
+            sides,
+            ..Default::default()
+        }
+    }
+
+    fn volume_of(bounds: &Aabb) -> f64 {
+        let size = bounds.max - bounds.min;
+        size.x * size.y * size.z
+    }
+
+    fn volume(solid: &Solid) -> f64 {
+        volume_of(&solid.bounds().unwrap().unwrap())
+    }
+
+    #[test]
+    fn hollow_inwards() {
+        let room = room();
+        let mut ids = VmfFile::default().id_allocator();
+        let walls = room.hollow(16.0, &mut ids).unwrap();
+
+        assert_eq!(walls.len(), 6);
+        assert!(walls.iter().all(Solid::is_valid));
+        assert_eq!(walls[0].id, 7);
+
+        // The walls fill the room minus its 224 unit interior.
+        let total: f64 = walls.iter().map(volume).sum();
+        assert_eq!(total, 256.0f64.powi(3) - 224.0f64.powi(3));
+        let bounds = room.bounds().unwrap().unwrap();
+        for wall in &walls {
+            let wall_bounds = wall.bounds().unwrap().unwrap();
+            assert!(bounds.contains(&wall_bounds));
+        }
+
+        // Each wall's inner face has the material and alignment of the face it covers.
+        for (wall, original) in walls.iter().zip(&room.sides) {
+            let inner = wall.sides.last().unwrap();
+            assert_eq!(inner.material, original.material);
+            assert_eq!(inner.u_axis, original.u_axis);
+            assert_eq!(
+                inner.to_plane().unwrap().normal,
+                -original.to_plane().unwrap().normal
+            );
+        }
+    }
+
+    #[test]
+    fn hollow_outwards() {
+        let room = room();
+        let mut ids = VmfFile::default().id_allocator();
+        let walls = room.hollow(-16.0, &mut ids).unwrap();
+
+        assert_eq!(walls.len(), 6);
+        assert!(walls.iter().all(Solid::is_valid));
+        let total: f64 = walls.iter().map(volume).sum();
+        assert_eq!(total, 288.0f64.powi(3) - 256.0f64.powi(3));
+
+        // The room's volume is left empty.
+        let bounds = room.bounds().unwrap().unwrap();
+        for wall in &walls {
+            let wall_bounds = wall.bounds().unwrap().unwrap();
+            let overlap = bounds.intersection(&wall_bounds).unwrap();
+            assert_eq!(volume_of(&overlap), 0.0);
+        }
+        assert_eq!(walls[0].sides.last().unwrap().material, "dev/ceiling");
+    }
+
+    #[test]
+    fn hollow_rejects_bad_thickness() {
+        let room = room();
+        let mut ids = VmfFile::default().id_allocator();
+        for thickness in [0.0, 128.0, 512.0, f64::NAN] {
+            assert!(matches!(
+                room.hollow(thickness, &mut ids),
+                Err(VmfError::InvalidFormat(_))
+            ));
+        }
+        // The error quotes the thickness as given.
+        let error = room.hollow(512.0, &mut ids).unwrap_err().to_string();
+        assert!(
+            error.contains("Walls of 512 units are too thick"),
+            "{}",
+            error
+        );
+    }
+}
@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+    use vmf_forge::prelude::*;
+    use vmf_forge::vmf_file::InstanceParameter;
+
+    fn file_name(path: &Path) -> &str {
+        path.file_name().unwrap().to_str().unwrap()
+    }
+
+    #[test]
+    fn instance_parameter_parse() {
+        assert_eq!(
+            InstanceParameter::parse("$speed integer 100"),
+            Some(InstanceParameter {
+                name: "$speed".to_string(),
+                kind: "integer".to_string(),
+                default: Some("100".to_string()),
+            })
+        );
+        assert_eq!(
+            InstanceParameter::parse("$color color255 255 128 0"),
+            Some(InstanceParameter {
+                name: "$color".to_string(),
+                kind: "color255".to_string(),
+                default: Some("255 128 0".to_string()),
+            })
+        );
+        assert_eq!(
+            InstanceParameter::parse("$name target_destination")
+                .unwrap()
+                .default,
+            None
+        );
+        assert_eq!(InstanceParameter::parse("speed integer"), None);
+    }
+
+    #[test]
+    fn vmf_instance_parameters() {
+        let vmf = VmfFile::open("vmf_examples/instances/door.vmf").unwrap();
+        let params = vmf.instance_parameters();
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].name, "$speed");
+        assert_eq!(params[0].kind, "integer");
+
+        let frame = VmfFile::open("vmf_examples/instances/frame.vmf").unwrap();
+        assert!(frame.instance_parameters().is_empty());
+    }
+
+    #[test]
+    fn graph_collects_files_and_edges() {
+        let graph =
+            InstanceGraph::build("vmf_examples/instance_graph.vmf", &InstanceResolver::new())
+                .unwrap();
+
+        let names: Vec<&str> = graph.files.keys().map(|p| file_name(p)).collect();
+        assert_eq!(names, vec!["instance_graph.vmf", "door.vmf", "frame.vmf"]);
+        // Two door instances, one frame in the parent and one frame inside the door.
+        assert_eq!(graph.edges.len(), 4);
+        assert!(graph.missing.is_empty());
+        assert!(graph.cycles.is_empty());
+    }
+
+    #[test]
+    fn graph_dependents_and_dependencies() {
+        let graph =
+            InstanceGraph::build("vmf_examples/instance_graph.vmf", &InstanceResolver::new())
+                .unwrap();
+
+        let mut users: Vec<&str> = graph
+            .dependents_of("instances/frame.vmf")
+            .into_iter()
+            .map(file_name)
+            .collect();
+        users.sort();
+        assert_eq!(users, vec!["door.vmf", "instance_graph.vmf"]);
+
+        let direct: Vec<&str> = graph
+            .direct_dependents_of("instances/door.vmf")
+            .into_iter()
+            .map(file_name)
+            .collect();
+        assert_eq!(direct, vec!["instance_graph.vmf"]);
+
+        let deps: Vec<&str> = graph
+            .dependencies_of("instances/door.vmf")
+            .into_iter()
+            .map(file_name)
+            .collect();
+        assert_eq!(deps, vec!["frame.vmf"]);
+
+        assert!(graph.dependents_of("instances/unknown.vmf").is_empty());
+    }
+
+    #[test]
+    fn graph_reports_undeclared_parameters() {
+        let graph =
+            InstanceGraph::build("vmf_examples/instance_graph.vmf", &InstanceResolver::new())
+                .unwrap();
+
+        assert_eq!(
+            graph.parameters("instances/door.vmf").unwrap()[0].name,
+            "$speed"
+        );
+        assert_eq!(graph.undeclared_parameters.len(), 1);
+        let issue = &graph.undeclared_parameters[0];
+        assert_eq!(issue.parameter, "$color");
+        assert_eq!(issue.key, "replace03");
+        assert_eq!(issue.entity_id, 20);
+        assert_eq!(file_name(&issue.instance), "door.vmf");
+        assert!(!graph.is_valid());
+    }
+
+    #[test]
+    fn graph_reports_cycles() {
+        let graph =
+            InstanceGraph::build("vmf_examples/instance_cycle.vmf", &InstanceResolver::new())
+                .unwrap();
+
+        assert_eq!(graph.cycles.len(), 1);
+        let cycle: Vec<&str> = graph.cycles[0].iter().map(|p| file_name(p)).collect();
+        assert_eq!(cycle, vec!["cycle_a.vmf", "cycle_b.vmf", "cycle_a.vmf"]);
+        assert!(!graph.is_valid());
+    }
+
+    #[test]
+    fn graph_reports_missing_files() {
+        let graph = InstanceGraph::build(
+            "vmf_examples/instance_missing.vmf",
+            &InstanceResolver::new(),
+        )
+        .unwrap();
+
+        assert_eq!(graph.missing.len(), 1);
+        assert_eq!(graph.missing[0].file, "instances/does_not_exist.vmf");
+        assert_eq!(graph.missing[0].entity_id, 2);
+        assert_eq!(graph.files.len(), 1);
+    }
+}
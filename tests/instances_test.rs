@@ -0,0 +1,254 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+    use vmf_forge::prelude::*;
+    use vmf_forge::vmf_file::FixupStyle;
+
+    fn collapsed_parent() -> VmfFile {
+        let mut vmf = VmfFile::open("vmf_examples/instance_parent.vmf").unwrap();
+        let collapsed = vmf.collapse_instances(&InstanceResolver::new()).unwrap();
+        assert_eq!(collapsed, 3);
+        vmf
+    }
+
+    fn find<'a>(vmf: &'a VmfFile, name: &'a str) -> &'a Entity {
+        vmf.entities
+            .find_by_name(name)
+            .next()
+            .unwrap_or_else(|| panic!("entity '{}' not found", name))
+    }
+
+    #[test]
+    fn fixup_style_apply() {
+        assert_eq!(FixupStyle::Prefix.apply("door", "inst"), "inst-door");
+        assert_eq!(FixupStyle::Postfix.apply("door", "inst"), "door-inst");
+        assert_eq!(FixupStyle::None.apply("door", "inst"), "door");
+        assert_eq!(FixupStyle::Prefix.apply("@global", "inst"), "@global");
+        assert_eq!(FixupStyle::Prefix.apply("!player", "inst"), "!player");
+        assert_eq!(FixupStyle::from_key("1"), FixupStyle::Postfix);
+        assert_eq!(FixupStyle::from_key("2"), FixupStyle::None);
+        assert_eq!(FixupStyle::from_key(""), FixupStyle::Prefix);
+    }
+
+    #[test]
+    fn collapse_removes_instances_and_merges_contents() {
+        let vmf = collapsed_parent();
+
+        assert_eq!(vmf.entities.find_by_classname("func_instance").count(), 0);
+        assert_eq!(
+            vmf.entities
+                .find_by_classname("func_instance_parms")
+                .count(),
+            0
+        );
+        assert_eq!(
+            vmf.entities
+                .find_by_classname("func_instance_io_proxy")
+                .count(),
+            0
+        );
+        assert_eq!(vmf.world.solids.len(), 6);
+        assert_eq!(vmf.entities.len(), 13);
+    }
+
+    #[test]
+    fn collapse_transforms_entities() {
+        let vmf = collapsed_parent();
+
+        let door = find(&vmf, "door_a-door");
+        assert_eq!(door.get("origin").unwrap(), "128 0 64");
+
+        // Rotated 90 degrees around Z, then moved to the instance origin.
+        let relay = find(&vmf, "door_a-relay");
+        assert_eq!(relay.get("origin").unwrap(), "128 16 0");
+
+        // Nested instance: frame origin is 128 units up inside the door instance.
+        let light = find(&vmf, "door_a-frame-frame_light");
+        assert_eq!(light.get("origin").unwrap(), "128 0 144");
+        assert_eq!(light.get("angles").unwrap(), "0 90 0");
+    }
+
+    #[test]
+    fn collapse_transforms_solids() {
+        let vmf = collapsed_parent();
+
+        let door_solid = vmf
+            .world
+            .solids
+            .iter()
+            .find(|s| {
+                s.sides
+                    .iter()
+                    .all(|side| side.material == "tools/toolsnodraw")
+            })
+            .unwrap();
+        let top = &door_solid.sides[0];
+        let points = top.plane_points().unwrap();
+        for point in points {
+            assert!((point.z - 128.0).abs() < 1e-6);
+            assert!(point.x >= 120.0 - 1e-6 && point.x <= 136.0 + 1e-6);
+            assert!(point.y >= -32.0 - 1e-6 && point.y <= 32.0 + 1e-6);
+        }
+
+        // The U axis followed the rotation.
+        let [u, _] = top.texture_axes().unwrap();
+        assert!(u.axis.approx_eq(Vector3::Y, 1e-6));
+    }
+
+    #[test]
+    fn collapse_applies_replacements() {
+        let vmf = collapsed_parent();
+
+        assert_eq!(find(&vmf, "door_a-door").get("speed").unwrap(), "250");
+        // The second instance has no replacements, so the raw parameter stays.
+        assert_eq!(
+            find(&vmf, "door-InstanceAuto1").get("speed").unwrap(),
+            "$speed"
+        );
+
+        let materials: HashSet<&str> = vmf
+            .world
+            .solids
+            .iter()
+            .flat_map(|s| s.sides.iter().map(|side| side.material.as_str()))
+            .collect();
+        assert!(materials.contains("tools/toolsnodraw"));
+        assert!(materials.contains("dev/dev_blendmeasure"));
+    }
+
+    #[test]
+    fn collapse_fixes_up_names_and_connections() {
+        let vmf = collapsed_parent();
+
+        let relay = find(&vmf, "door_a-relay");
+        let connections = relay.connections.as_ref().unwrap();
+        assert_eq!(connections[0].1, "door_a-door\x1BOpen\x1B\x1B0\x1B-1");
+        assert_eq!(connections[1].1, "@global_relay\x1BTrigger\x1B\x1B0\x1B-1");
+
+        let postfix_relay = find(&vmf, "relay-InstanceAuto1");
+        assert_eq!(
+            postfix_relay.connections.as_ref().unwrap()[0].1,
+            "door-InstanceAuto1\x1BOpen\x1B\x1B0\x1B-1"
+        );
+
+        // fixup_style 2 leaves names untouched.
+        assert!(vmf.entities.find_by_name("frame_light").next().is_some());
+    }
+
+    #[test]
+    fn collapse_rewires_instance_io() {
+        let vmf = collapsed_parent();
+
+        // `instance:relay;Trigger` on the func_instance now reaches the relay directly.
+        let auto = vmf.entities.find_by_classname("logic_auto").next().unwrap();
+        assert_eq!(
+            auto.connections.as_ref().unwrap()[0].1,
+            "door_a-relay\x1BTrigger\x1B\x1B1\x1B-1"
+        );
+
+        // The proxy relay is replaced by the parent's connection, with delays added.
+        let button = find(&vmf, "door_a-button");
+        assert_eq!(
+            button.connections.as_ref().unwrap(),
+            &vec![(
+                "OnPressed".to_string(),
+                "lamp\x1BTurnOn\x1B\x1B0.75\x1B1".to_string()
+            )]
+        );
+
+        // The unnamed instance has no proxy outputs, so the relay is dropped.
+        assert!(find(&vmf, "button-InstanceAuto1").connections.is_none());
+    }
+
+    #[test]
+    fn collapse_matches_instance_names_case_insensitively() {
+        let mut vmf = VmfFile::open("vmf_examples/instance_parent.vmf").unwrap();
+        let auto = vmf
+            .entities
+            .find_by_classname_mut("logic_auto")
+            .next()
+            .unwrap();
+        auto.connections.as_mut().unwrap()[0].1 =
+            "DOOR_A\x1Binstance:relay;Trigger\x1B\x1B1\x1B-1".to_string();
+        vmf.collapse_instances(&InstanceResolver::new()).unwrap();
+
+        let auto = vmf.entities.find_by_classname("logic_auto").next().unwrap();
+        assert_eq!(
+            auto.connections.as_ref().unwrap()[0].1,
+            "door_a-relay\x1BTrigger\x1B\x1B1\x1B-1"
+        );
+    }
+
+    #[test]
+    fn collapse_assigns_unique_ids_and_remaps_overlays() {
+        let vmf = collapsed_parent();
+
+        let mut solid_ids = HashSet::new();
+        let mut side_ids = HashSet::new();
+        for solid in &vmf.world.solids {
+            assert!(solid_ids.insert(solid.id));
+            for side in &solid.sides {
+                assert!(side_ids.insert(side.id));
+            }
+        }
+
+        let mut entity_ids = HashSet::new();
+        for ent in vmf.entities.iter() {
+            assert!(entity_ids.insert(ent.id()));
+            assert!(!solid_ids.contains(&ent.id()));
+        }
+
+        let overlay = vmf
+            .entities
+            .find_by_classname("info_overlay")
+            .find(|o| {
+                o.get("sides")
+                    .unwrap()
+                    .split_whitespace()
+                    .all(|id| side_ids.contains(&id.parse::<u32>().unwrap()))
+            })
+            .unwrap();
+        let sides: Vec<u32> = overlay
+            .get("sides")
+            .unwrap()
+            .split_whitespace()
+            .map(|id| id.parse().unwrap())
+            .collect();
+        assert_eq!(sides.len(), 2);
+        assert!(!sides.contains(&1));
+    }
+
+    #[test]
+    fn collapse_detects_cycles() {
+        let mut vmf = VmfFile::open("vmf_examples/instance_cycle.vmf").unwrap();
+        let result = vmf.collapse_instances(&InstanceResolver::new());
+        assert!(matches!(result, Err(VmfError::InstanceCycle(_))));
+    }
+
+    #[test]
+    fn collapse_reports_missing_files() {
+        let mut vmf = VmfFile::open("vmf_examples/instance_missing.vmf").unwrap();
+        let result = vmf.collapse_instances(&InstanceResolver::new());
+        assert!(
+            matches!(result, Err(VmfError::InstanceNotFound(file)) if file == "instances/does_not_exist.vmf")
+        );
+    }
+
+    #[test]
+    fn collapse_uses_search_paths() {
+        let mut vmf = VmfFile::parse(
+            "world\n{\n\t\"id\" \"1\"\n\t\"classname\" \"worldspawn\"\n}\n\
+             entity\n{\n\t\"id\" \"2\"\n\t\"classname\" \"func_instance\"\n\
+             \t\"file\" \"instances/frame.vmf\"\n\t\"origin\" \"0 0 0\"\n}\n",
+        )
+        .unwrap();
+
+        let result = vmf.collapse_instances(&InstanceResolver::new());
+        assert!(matches!(result, Err(VmfError::InstanceNotFound(_))));
+
+        let resolver = InstanceResolver::new().with_search_path("vmf_examples");
+        assert_eq!(vmf.collapse_instances(&resolver).unwrap(), 1);
+        assert_eq!(vmf.world.solids.len(), 1);
+    }
+}
@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::prelude::*;
+    use vmf_forge::vmf_file::IoTarget;
+
+    fn named(classname: &str, id: u64, targetname: &str) -> Entity {
+        let mut ent = Entity::new(classname, id);
+        ent.set("targetname".to_string(), targetname.to_string());
+        ent
+    }
+
+    /// A relay firing at two doors by wildcard, itself, its activator, a prop by
+    /// classname and a name that doesn't exist.
+    fn logic() -> VmfFile {
+        let mut vmf = VmfFile::default();
+        let mut relay = named("logic_relay", 2, "relay");
+        relay.add_connection("OnTrigger", "door*", "Open", "", 0.5, 1);
+        relay.add_connection("OnTrigger", "!self", "Disable", "", 0.0, -1);
+        relay.add_connection("OnTrigger", "!activator", "Kill", "", 0.0, -1);
+        relay.add_connection("OnTrigger", "ghost", "Toggle", "", 0.0, -1);
+        relay.add_connection("OnTrigger", "prop_physics", "Wake", "", 0.0, -1);
+        vmf.entities.push(relay);
+        vmf.entities.push(named("func_door", 3, "door_a"));
+        vmf.entities.push(named("func_door", 4, "Door_B"));
+        vmf.entities.push(Entity::new("prop_physics", 5));
+        vmf.entities.push(named("info_target", 6, "lonely"));
+        vmf
+    }
+
+    #[test]
+    fn example_io_graph() {
+        let vmf = VmfFile::open("vmf_examples/complex.vmf").unwrap();
+        let graph = vmf.io_graph().unwrap();
+        assert_eq!(graph.nodes.len(), vmf.entities.len() + vmf.hiddens.len());
+        assert!(!graph.edges.is_empty());
+        let relay = graph
+            .edges
+            .iter()
+            .find(|edge| edge.target == "bridge_texturetoggle")
+            .unwrap();
+        assert_eq!(relay.input, "SetTextureIndex");
+        assert_eq!((relay.param.as_str(), relay.fire_limit), ("0", -1));
+        assert!(matches!(&relay.to, IoTarget::Entities(ids) if ids.len() == 1));
+    }
+
+    #[test]
+    fn targets_resolve_like_the_engine() {
+        let graph = logic().io_graph().unwrap();
+        let targets: Vec<&IoTarget> = graph.edges.iter().map(|edge| &edge.to).collect();
+        assert_eq!(
+            targets,
+            vec![
+                &IoTarget::Entities(vec![3, 4]),
+                &IoTarget::Entities(vec![2]),
+                &IoTarget::Runtime("!activator".to_string()),
+                &IoTarget::Missing,
+                &IoTarget::Entities(vec![5]),
+            ]
+        );
+        assert_eq!(graph.edges[0].delay, 0.5);
+        assert_eq!(graph.edges[0].fire_limit, 1);
+        assert_eq!(graph.outputs_of(2).count(), 5);
+        assert_eq!(graph.inputs_of(4).count(), 1);
+
+        let dangling: Vec<&str> = graph.dangling().map(|e| e.target.as_str()).collect();
+        assert_eq!(dangling, vec!["ghost"]);
+        // Firing at itself doesn't count as being targeted.
+        let untargeted: Vec<u64> = graph.untargeted().iter().map(|n| n.entity_id).collect();
+        assert_eq!(untargeted, vec![2, 6]);
+
+        // Only a trailing `*` is a wildcard.
+        let mut vmf = logic();
+        vmf.entities[0].add_connection("OnTrigger", "door?", "Open", "", 0.0, -1);
+        vmf.entities[0].add_connection("OnTrigger", "d*_a", "Open", "", 0.0, -1);
+        vmf.entities[0].add_connection("OnTrigger", "DOOR_A", "Open", "", 0.0, -1);
+        let graph = vmf.io_graph().unwrap();
+        let targets: Vec<&IoTarget> = graph.edges[5..].iter().map(|edge| &edge.to).collect();
+        assert_eq!(
+            targets,
+            vec![
+                &IoTarget::Missing,
+                &IoTarget::Missing,
+                &IoTarget::Entities(vec![3]),
+            ]
+        );
+    }
+
+    #[test]
+    fn dot_and_json_export() {
+        let graph = logic().io_graph().unwrap();
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph io {"));
+        assert!(dot.contains("\"e2\" [label=\"relay\\nlogic_relay\"];"));
+        assert!(dot.contains("\"e2\" -> \"e3\" [label=\"OnTrigger -> Open +0.5s x1\"];"));
+        assert!(dot.contains("\"e2\" -> \"e4\""));
+        assert!(dot.contains("\"missing:ghost\" [label=\"ghost\", style=dashed, color=red];"));
+        assert!(dot.contains("\"e2\" -> \"runtime:!activator\""));
+        // Entities without connections are left out.
+        assert!(!dot.contains("\"e6\""));
+
+        let json = graph.to_json();
+        assert!(json.starts_with("{\"nodes\":[{\"id\":2,\"classname\":\"logic_relay\","));
+        assert!(json.contains("{\"id\":5,\"classname\":\"prop_physics\",\"targetname\":null}"));
+        assert!(json.contains(
+            "{\"from\":2,\"output\":\"OnTrigger\",\"target\":\"door*\",\"input\":\"Open\",\
+             \"param\":\"\",\"delay\":0.5,\"fire_limit\":1,\"status\":\"resolved\",\"to\":[3,4]}"
+        ));
+        assert!(json.contains("\"status\":\"runtime\",\"to\":[]"));
+        assert!(json.ends_with("\"dangling\":[3],\"untargeted\":[2,6]}"));
+    }
+
+    #[test]
+    fn malformed_connections() {
+        let mut vmf = logic();
+        vmf.entities[0]
+            .connections
+            .as_mut()
+            .unwrap()
+            .push(("OnTrigger".to_string(), "door_a\x1BOpen".to_string()));
+        assert!(matches!(vmf.io_graph(), Err(VmfError::InvalidFormat(_))));
+
+        // Delays that can't be written as JSON are rejected too.
+        for delay in ["nan", "inf", "-inf"] {
+            let mut vmf = logic();
+            let value = format!("door_a\x1BOpen\x1B\x1B{}\x1B-1", delay);
+            vmf.entities[0]
+                .connections
+                .as_mut()
+                .unwrap()
+                .push(("OnTrigger".to_string(), value));
+            assert!(matches!(vmf.io_graph(), Err(VmfError::InvalidFormat(_))));
+        }
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::prelude::*;
+
+    /// A sealed 256 by 256 by 128 room with 16 unit walls and a light in the middle.
+    fn room() -> VmfFile {
+        let mut vmf = VmfFile::default();
+        let mut ids = vmf.id_allocator();
+        let inside = Aabb::new(Vector3::ZERO, Vector3::new(256.0, 256.0, 128.0));
+        let block = Solid::block(&inside, "dev/dev_measurewall01a", &mut ids).unwrap();
+        vmf.world.solids = block.hollow(-16.0, &mut ids).unwrap();
+
+        let mut light = Entity::new("light", ids.next_object_id());
+        light.set_origin(Vector3::new(128.0, 128.0, 64.0));
+        vmf.entities.push(light);
+        vmf
+    }
+
+    /// The index of the +X wall of `room`.
+    const EAST_WALL: usize = 3;
+
+    #[test]
+    fn sealed_room() {
+        let vmf = room();
+        assert_eq!(vmf.find_leaks(&LeakOptions::default()).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn missing_wall_leaks() {
+        let mut vmf = room();
+        vmf.world.solids.remove(EAST_WALL);
+
+        let leaks = vmf.find_leaks(&LeakOptions::default()).unwrap();
+        assert_eq!(leaks.len(), 1);
+        let leak = &leaks[0];
+        assert_eq!(leak.entity_id, vmf.entities[0].id());
+        assert_eq!(leak.classname.as_deref(), Some("light"));
+        assert_eq!(leak.path[0], Vector3::new(128.0, 128.0, 64.0));
+        // The shortest way out is straight through the gap, along the voxel centers.
+        assert_eq!(leak.path.len(), 3);
+        assert_eq!(leak.path[1].y, leak.path[2].y);
+        assert_eq!(leak.path[1].z, leak.path[2].z);
+        assert!(leak.path[2].x > 256.0);
+
+        let pointfile = leak.to_pointfile();
+        assert!(pointfile.starts_with("128 128 64\n"));
+        assert_eq!(pointfile.lines().count(), 3);
+    }
+
+    #[test]
+    fn non_sealing_brushes_leak() {
+        let mut vmf = room();
+        let wall = vmf.world.solids.remove(EAST_WALL);
+        let mut detail = Entity::new("func_detail", 100);
+        detail.solids = Some(vec![wall.clone()]);
+        vmf.entities.push(detail);
+        assert_eq!(vmf.find_leaks(&LeakOptions::default()).unwrap().len(), 1);
+
+        let mut vmf = room();
+        for side in &mut vmf.world.solids[EAST_WALL].sides {
+            side.material = "tools/toolstrigger".to_string();
+        }
+        assert_eq!(vmf.find_leaks(&LeakOptions::default()).unwrap().len(), 1);
+
+        let options = LeakOptions {
+            non_sealing_materials: vec![],
+            ..Default::default()
+        };
+        assert_eq!(vmf.find_leaks(&options).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn entities_outside_or_in_walls() {
+        let mut vmf = room();
+        let mut outside = Entity::new("info_player_start", 50);
+        outside.set_origin(Vector3::new(-100.0, 128.0, 64.0));
+        vmf.entities.push(outside);
+        let mut far = Entity::new("info_target", 51);
+        far.set_origin(Vector3::new(10000.0, 0.0, 0.0));
+        vmf.entities.push(far);
+        let mut in_wall = Entity::new("info_target", 52);
+        in_wall.set_origin(Vector3::new(264.0, 128.0, 64.0));
+        vmf.entities.push(in_wall);
+        let mut hidden = Entity::new("info_target", 53);
+        hidden.set_origin(Vector3::new(-100.0, 0.0, 0.0));
+        hidden.is_hidden = true;
+        vmf.entities.push(hidden);
+
+        let leaks = vmf.find_leaks(&LeakOptions::default()).unwrap();
+        let ids: Vec<u64> = leaks.iter().map(|l| l.entity_id).collect();
+        assert_eq!(ids, vec![50, 51]);
+        assert_eq!(leaks[1].path, vec![Vector3::new(10000.0, 0.0, 0.0)]);
+    }
+
+    #[test]
+    fn rejects_bad_voxel_sizes() {
+        let vmf = room();
+        // Half unit voxels would need a grid of over 100 million cells.
+        for voxel_size in [0.0, -16.0, f64::NAN, 1e-6, 0.5] {
+            let options = LeakOptions {
+                voxel_size,
+                ..Default::default()
+            };
+            assert!(matches!(
+                vmf.find_leaks(&options),
+                Err(VmfError::InvalidFormat(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::prelude::*;
+
+    const FLOOR: &str = "dev/dev_measuregeneric01";
+    const WALL: &str = "concrete/concretewall001a";
+
+    fn block(min: [f64; 3], max: [f64; 3], material: &str, ids: &mut IdAllocator) -> Solid {
+        let bounds = Aabb::new(Vector3::from(min), Vector3::from(max));
+        Solid::block(&bounds, material, ids).unwrap()
+    }
+
+    /// A floor, a long detail wall in visgroup 3, a nodraw block and a cordon over the west half.
+    fn map() -> VmfFile {
+        let mut vmf = VmfFile::default();
+        let mut ids = vmf.id_allocator();
+        vmf.world.solids.push(block(
+            [0.0, 0.0, -16.0],
+            [512.0, 256.0, 0.0],
+            FLOOR,
+            &mut ids,
+        ));
+        vmf.world.solids.push(block(
+            [0.0, 256.0, 0.0],
+            [64.0, 320.0, 64.0],
+            "TOOLS/TOOLSNODRAW",
+            &mut ids,
+        ));
+
+        let mut detail = Entity::new("func_detail", ids.next_object_id());
+        let mut wall = block([0.0, 0.0, 0.0], [1024.0, 16.0, 128.0], WALL, &mut ids);
+        wall.editor.visgroup_id = Some(3);
+        detail.solids = Some(vec![wall]);
+        vmf.entities.push(detail);
+
+        vmf.cordons.push(Cordon {
+            name: "west".to_string(),
+            active: false,
+            min: "(-1 -1 -32)".to_string(),
+            max: "(300 300 300)".to_string(),
+        });
+        vmf
+    }
+
+    #[test]
+    fn lightmap_report() {
+        let vmf = map();
+        let report = vmf.lightmap_report().unwrap();
+        // The nodraw block has no lightmaps.
+        assert_eq!(report.faces.len(), 12);
+
+        let floor = &vmf.world.solids[0];
+        let top = &report.faces[0];
+        assert_eq!(top.solid_id, floor.id);
+        assert_eq!(top.side_id, floor.sides[0].id);
+        assert_eq!(top.entity_id, None);
+        assert_eq!(top.area, 512.0 * 256.0);
+        assert_eq!(top.luxels, 512.0);
+        assert_eq!(top.extents, [32.0, 16.0]);
+        assert!(!top.exceeds_limit);
+
+        let floor_totals = report.by_material[FLOOR];
+        assert_eq!(floor_totals.faces, 6);
+        assert_eq!(floor_totals.area, 286720.0);
+        assert_eq!(floor_totals.luxels, 1120.0);
+        assert_eq!(floor_totals.over_limit, 0);
+
+        // The 1024 unit wall is too long for one lightmap on all but its ends.
+        let wall_totals = report.by_material[WALL];
+        assert_eq!(wall_totals.over_limit, 4);
+        assert_eq!(report.by_visgroup.len(), 1);
+        assert_eq!(report.by_visgroup[&3], wall_totals);
+        let wall_face = &report.faces[6];
+        assert_eq!(wall_face.entity_id, Some(vmf.entities[0].id()));
+        assert_eq!(wall_face.visgroup_id, Some(3));
+
+        assert_eq!(report.total.faces, 12);
+        assert_eq!(report.total.over_limit, 4);
+        assert_eq!(
+            report.total.luxels,
+            floor_totals.luxels + wall_totals.luxels
+        );
+
+        // Five floor faces and the wall's west end have their center in the cordon.
+        assert_eq!(report.by_region["west"].faces, 6);
+    }
+
+    #[test]
+    fn set_lightmap_scale_by_rule() {
+        let mut vmf = map();
+        let rules = [
+            LightmapRule {
+                material: Some("CONCRETE/*".to_string()),
+                scale: 32,
+                ..Default::default()
+            },
+            LightmapRule {
+                facing: Some(Vector3::Z * 2.0),
+                scale: 8,
+                ..Default::default()
+            },
+        ];
+        // All six wall sides, and the tops of the floor and the nodraw block.
+        assert_eq!(vmf.set_lightmap_scale(&rules).unwrap(), 8);
+
+        let wall = &vmf.entities[0].solids.as_ref().unwrap()[0];
+        assert!(wall.sides.iter().all(|s| s.lightmap_scale == 32));
+        let floor = &vmf.world.solids[0];
+        let scales: Vec<u16> = floor.sides.iter().map(|s| s.lightmap_scale).collect();
+        assert_eq!(scales, vec![8, 16, 16, 16, 16, 16]);
+
+        // Already applied.
+        assert_eq!(vmf.set_lightmap_scale(&rules).unwrap(), 0);
+        let report = vmf.lightmap_report().unwrap();
+        assert_eq!(report.faces[0].luxels, 2048.0);
+        assert_eq!(report.total.over_limit, 1);
+    }
+
+    #[test]
+    fn set_lightmap_scale_rejects_bad_rules() {
+        let mut vmf = map();
+        let before = vmf.clone();
+        for rule in [
+            LightmapRule {
+                scale: 0,
+                ..Default::default()
+            },
+            LightmapRule {
+                facing: Some(Vector3::ZERO),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                vmf.set_lightmap_scale(&[rule]),
+                Err(VmfError::InvalidFormat(_))
+            ));
+        }
+        assert_eq!(vmf, before);
+    }
+}
@@ -0,0 +1,298 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::prelude::*;
+    use vmf_forge::vmf_file::{OverlayOptions, OverlayProblem, OverlayProblemKind};
+
+    /// A 256 unit square floor brush with an overlay on its top face.
+    fn floor_with_overlay() -> VmfFile {
+        let mut vmf = VmfFile::default();
+        let mut ids = vmf.id_allocator();
+        let bounds = Aabb::new(
+            Vector3::new(0.0, 0.0, -16.0),
+            Vector3::new(256.0, 256.0, 0.0),
+        );
+        let floor = Solid::block(&bounds, "concrete/concretefloor001a", &mut ids).unwrap();
+        let mut overlay = Entity::new("info_overlay", ids.next_object_id());
+        overlay.set("sides".to_string(), floor.sides[0].id.to_string());
+        vmf.world.solids.push(floor);
+        vmf.entities.push(overlay);
+        vmf
+    }
+
+    fn overlay_sides(vmf: &VmfFile) -> Vec<u32> {
+        let overlay = vmf
+            .entities
+            .find_by_classname("info_overlay")
+            .next()
+            .unwrap();
+        overlay
+            .get("sides")
+            .unwrap()
+            .split_whitespace()
+            .map(|id| id.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn example_overlays() {
+        let vmf = VmfFile::open("vmf_examples/complex.vmf").unwrap();
+        let overlays = vmf.overlays().unwrap();
+        assert_eq!(overlays.len(), 14);
+
+        let overlay = &overlays[0];
+        assert_eq!(overlay.id, 8783);
+        assert_eq!(
+            overlay.material,
+            "signage/indicator_lights/indicator_lights_floor"
+        );
+        assert_eq!(overlay.sides, vec![2952, 2590, 1749]);
+        assert_eq!(overlay.basis_origin, Vector3::new(160.0, -512.0, 168.0));
+        assert_eq!(overlay.basis_normal, Vector3::Y);
+        assert_eq!(overlay.uv[2], Vector3::new(104.0, 8.0, 0.0));
+        assert_eq!((overlay.start_u, overlay.end_v), (3.0, 1.0));
+        assert!(overlay.corners()[2].approx_eq(Vector3::new(168.0, -512.0, 272.0), 1e-3));
+
+        // Writing an overlay into an entity reads back the same, up to the six
+        // decimal places Hammer writes.
+        let mut entity = Entity::new("info_overlay", overlay.id);
+        overlay.apply(&mut entity);
+        let read = Overlay::try_from(&entity).unwrap();
+        assert_eq!(read.basis_u, Vector3::Z);
+        assert_eq!(
+            Overlay {
+                basis_u: overlay.basis_u,
+                basis_v: overlay.basis_v,
+                ..read
+            },
+            *overlay
+        );
+        assert!(Overlay::try_from(&Entity::new("info_decal", 1)).is_err());
+        // Classnames match regardless of case, as they do for side remapping.
+        entity.set("classname".to_string(), "INFO_OVERLAY".to_string());
+        assert!(Overlay::try_from(&entity).is_ok());
+    }
+
+    #[test]
+    fn missing_sides_are_reported() {
+        let mut vmf = VmfFile::open("vmf_examples/complex.vmf").unwrap();
+        assert_eq!(vmf.validate_overlays(), Vec::new());
+
+        for solid in vmf.world.solids.iter_mut() {
+            solid.sides.retain(|side| side.id != 1749);
+        }
+        let problems = vmf.validate_overlays();
+        assert!(!problems.is_empty());
+        assert_eq!(
+            problems[0],
+            OverlayProblem {
+                entity_id: 8783,
+                classname: "info_overlay".to_string(),
+                kind: OverlayProblemKind::MissingSides,
+                side_ids: vec![1749],
+            }
+        );
+        assert_eq!(
+            problems[0].to_string(),
+            "info_overlay 8783: missing sides (sides 1749)"
+        );
+        assert!(problems.iter().all(|p| p.side_ids == vec![1749]));
+    }
+
+    #[test]
+    fn overlays_follow_carved_faces() {
+        let mut vmf = floor_with_overlay();
+        let top = vmf.world.solids[0].sides[0].plane.clone();
+
+        // Cutting a trench across the floor splits its top face in two.
+        let mut ids = vmf.id_allocator();
+        let trench = Aabb::new(
+            Vector3::new(96.0, -32.0, -32.0),
+            Vector3::new(160.0, 288.0, 32.0),
+        );
+        let cutter = Solid::block(&trench, "tools/toolsnodraw", &mut ids).unwrap();
+        assert_eq!(vmf.carve(&cutter, None).unwrap(), 1);
+        assert_eq!(vmf.world.solids.len(), 2);
+
+        let mut tops: Vec<u32> = vmf
+            .world
+            .solids
+            .iter()
+            .flat_map(|solid| &solid.sides)
+            .filter(|side| side.plane == top)
+            .map(|side| side.id)
+            .collect();
+        tops.sort();
+        let mut sides = overlay_sides(&vmf);
+        sides.sort();
+        assert_eq!(sides, tops);
+        assert_eq!(vmf.validate_overlays(), Vec::new());
+
+        // Carving the rest away leaves the overlay on nothing.
+        let everything = Aabb::new(Vector3::splat(-512.0), Vector3::splat(512.0));
+        let cutter = Solid::block(&everything, "tools/toolsnodraw", &mut ids).unwrap();
+        assert_eq!(vmf.carve(&cutter, None).unwrap(), 2);
+        assert_eq!(overlay_sides(&vmf), Vec::<u32>::new());
+        let problems = vmf.validate_overlays();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].kind, OverlayProblemKind::NoSides);
+    }
+
+    #[test]
+    fn remap_renumbered_sides() {
+        let mut vmf = floor_with_overlay();
+        let old = vmf.world.solids[0].sides[0].id;
+        vmf.world.solids[0].sides[0].id = 100;
+        assert_eq!(vmf.validate_overlays()[0].side_ids, vec![old]);
+
+        let remap = [(old, vec![100])].into_iter().collect();
+        assert_eq!(vmf.remap_overlay_sides(&remap), 1);
+        assert_eq!(overlay_sides(&vmf), vec![100]);
+        assert_eq!(vmf.remap_overlay_sides(&remap), 0);
+    }
+
+    #[test]
+    fn decals_without_sides_are_fine() {
+        let mut vmf = floor_with_overlay();
+        vmf.entities.push(Entity::new("INFO_DECAL", 50));
+        vmf.entities.push(Entity::new("INFO_OVERLAY", 51));
+        let problems = vmf.validate_overlays();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].entity_id, 51);
+        assert_eq!(problems[0].kind, OverlayProblemKind::NoSides);
+    }
+
+    /// Two 128 unit floor brushes side by side along X, with their tops at Z = 0.
+    fn two_floors() -> VmfFile {
+        let mut vmf = VmfFile::default();
+        let mut ids = vmf.id_allocator();
+        for x in [0.0, 128.0] {
+            let bounds = Aabb::new(
+                Vector3::new(x, 0.0, -16.0),
+                Vector3::new(x + 128.0, 128.0, 0.0),
+            );
+            let solid = Solid::block(&bounds, "concrete/concretefloor001a", &mut ids).unwrap();
+            vmf.world.solids.push(solid);
+        }
+        vmf
+    }
+
+    #[test]
+    fn make_overlay_across_faces() {
+        let mut vmf = two_floors();
+        let tops = [
+            vmf.world.solids[0].sides[0].id,
+            vmf.world.solids[1].sides[0].id,
+        ];
+        let mut ids = vmf.id_allocator();
+        let options = OverlayOptions::new(
+            "decals/decalcrack001a",
+            Vector3::new(128.0, 64.0, 0.0),
+            Vector3::Z,
+            64.0,
+            32.0,
+        );
+        let entity = vmf.make_overlay(&options, &mut ids).unwrap();
+        assert_eq!(entity.classname(), Some("info_overlay"));
+        assert_eq!(entity.origin(), Some(Vector3::new(128.0, 64.0, 0.0)));
+        assert_eq!(entity.get("uv2").unwrap(), "32 16 0");
+
+        let overlay = Overlay::try_from(&entity).unwrap();
+        assert_eq!(overlay.id, entity.id());
+        assert_eq!(overlay.sides, tops.to_vec());
+        assert_eq!(
+            (overlay.basis_u, overlay.basis_v, overlay.basis_normal),
+            (Vector3::X, Vector3::Y, Vector3::Z)
+        );
+        assert_eq!(overlay.corners()[0], Vector3::new(96.0, 48.0, 0.0));
+        assert_eq!((overlay.end_u, overlay.end_v), (1.0, 1.0));
+
+        vmf.entities.push(entity);
+        assert_eq!(vmf.validate_overlays(), Vec::new());
+    }
+
+    #[test]
+    fn make_overlay_picks_faces_under_footprint() {
+        let vmf = two_floors();
+        let mut ids = vmf.id_allocator();
+
+        // Touching the second brush along an edge isn't enough.
+        let options = OverlayOptions::new(
+            "decals/decalcrack001a",
+            Vector3::new(112.0, 64.0, 0.5),
+            Vector3::Z,
+            32.0,
+            32.0,
+        );
+        let overlay = Overlay::try_from(&vmf.make_overlay(&options, &mut ids).unwrap()).unwrap();
+        assert_eq!(overlay.sides, vec![vmf.world.solids[0].sides[0].id]);
+
+        // A wall faces sideways, with V running down it as in Hammer.
+        let options = OverlayOptions {
+            rotation: 90.0,
+            ..OverlayOptions::new(
+                "decals/decalgraffiti001a",
+                Vector3::new(0.0, 64.0, -8.0),
+                -Vector3::X,
+                16.0,
+                8.0,
+            )
+        };
+        let overlay = Overlay::try_from(&vmf.make_overlay(&options, &mut ids).unwrap()).unwrap();
+        let wall = &vmf.world.solids[0].sides[2];
+        assert_eq!(overlay.sides, vec![wall.id]);
+        assert!(overlay.basis_u.approx_eq(-Vector3::Z, 1e-9));
+        assert!(overlay.basis_v.approx_eq(-Vector3::Y, 1e-9));
+        assert!(
+            overlay
+                .basis_u
+                .cross(overlay.basis_v)
+                .approx_eq(overlay.basis_normal, 1e-9)
+        );
+
+        // Nothing under the overlay, or nowhere to face.
+        let mut options = OverlayOptions::new(
+            "decals/decalcrack001a",
+            Vector3::splat(1024.0),
+            Vector3::Z,
+            32.0,
+            32.0,
+        );
+        assert!(vmf.make_overlay(&options, &mut ids).is_err());
+        options.origin = Vector3::new(64.0, 64.0, 0.0);
+        options.normal = Vector3::ZERO;
+        assert!(vmf.make_overlay(&options, &mut ids).is_err());
+    }
+
+    #[test]
+    fn make_overlay_face_limit() {
+        // A floor of 9 × 8 tiles, 16 units each.
+        let mut vmf = VmfFile::default();
+        let mut ids = vmf.id_allocator();
+        for x in 0..9 {
+            for y in 0..8 {
+                let min = Vector3::new(x as f64 * 16.0, y as f64 * 16.0, -16.0);
+                let bounds = Aabb::new(min, min + Vector3::new(16.0, 16.0, 16.0));
+                let tile = Solid::block(&bounds, "concrete/concretefloor001a", &mut ids).unwrap();
+                vmf.world.solids.push(tile);
+            }
+        }
+        let mut options = OverlayOptions::new(
+            "decals/decalcrack001a",
+            Vector3::new(64.0, 64.0, 0.0),
+            Vector3::Z,
+            128.0,
+            128.0,
+        );
+        let overlay = Overlay::try_from(&vmf.make_overlay(&options, &mut ids).unwrap()).unwrap();
+        assert_eq!(overlay.sides.len(), 64);
+
+        // VBSP rejects overlays on more than 64 faces.
+        options.origin.x = 72.0;
+        options.width = 144.0;
+        assert!(matches!(
+            vmf.make_overlay(&options, &mut ids),
+            Err(VmfError::InvalidFormat(_))
+        ));
+    }
+}
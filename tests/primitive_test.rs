@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::prelude::*;
+
+    const MATERIAL: &str = "dev/dev_measuregeneric01";
+
+    fn bounds() -> Aabb {
+        Aabb::new(
+            Vector3::new(-64.0, -64.0, 0.0),
+            Vector3::new(64.0, 64.0, 128.0),
+        )
+    }
+
+    fn assert_fits(solid: &Solid, bounds: &Aabb) {
+        assert!(solid.is_valid(), "solid {} is invalid", solid.id);
+        assert!(solid.sides.iter().all(|s| s.material == MATERIAL));
+        let solid_bounds = solid.bounds().unwrap().unwrap();
+        assert!(bounds.expanded(1e-3).contains(&solid_bounds));
+    }
+
+    #[test]
+    fn block() {
+        let mut ids = VmfFile::default().id_allocator();
+        let block = Solid::block(&bounds(), MATERIAL, &mut ids).unwrap();
+
+        assert_eq!(block.id, 2);
+        assert_eq!(
+            block.sides.iter().map(|s| s.id).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+        assert_fits(&block, &bounds());
+        assert_eq!(block.bounds().unwrap().unwrap(), bounds());
+
+        // Hammer's winding and world-aligned axes.
+        let top = &block.sides[0];
+        assert_eq!(top.plane, "(-64 64 128) (64 64 128) (64 -64 128)");
+        assert_eq!(top.to_plane().unwrap().normal, Vector3::Z);
+        assert_eq!(top.u_axis, "[1 0 0 0] 0.25");
+        assert_eq!(top.v_axis, "[0 -1 0 0] 0.25");
+        assert_eq!(top.lightmap_scale, 16);
+    }
+
+    #[test]
+    fn wedge_and_spike() {
+        let mut ids = VmfFile::default().id_allocator();
+        let wedge = Solid::wedge(&bounds(), MATERIAL, &mut ids).unwrap();
+        assert_eq!(wedge.sides.len(), 5);
+        assert_eq!(wedge.vertices().unwrap().len(), 6);
+        assert_fits(&wedge, &bounds());
+        let wedge_bounds = wedge.bounds().unwrap().unwrap();
+        assert!(wedge_bounds.max.approx_eq(bounds().max, 1e-6));
+
+        let spike = Solid::spike(&bounds(), 4, MATERIAL, &mut ids).unwrap();
+        assert_eq!(spike.sides.len(), 5);
+        assert_eq!(spike.vertices().unwrap().len(), 5);
+        assert_fits(&spike, &bounds());
+        assert!(
+            spike
+                .vertices()
+                .unwrap()
+                .iter()
+                .any(|v| v.approx_eq(Vector3::new(0.0, 0.0, 128.0), 1e-6))
+        );
+    }
+
+    #[test]
+    fn cylinder_and_cone() {
+        let mut ids = VmfFile::default().id_allocator();
+        let cylinder = Solid::cylinder(&bounds(), 8, MATERIAL, &mut ids).unwrap();
+        assert_eq!(cylinder.sides.len(), 10);
+        assert_eq!(cylinder.vertices().unwrap().len(), 16);
+        assert_fits(&cylinder, &bounds());
+        let cylinder_bounds = cylinder.bounds().unwrap().unwrap();
+        assert!(cylinder_bounds.min.approx_eq(bounds().min, 1e-3));
+        assert!(cylinder_bounds.max.approx_eq(bounds().max, 1e-3));
+
+        let cone = Solid::cone(&bounds(), 6, 0.5, MATERIAL, &mut ids).unwrap();
+        assert_eq!(cone.sides.len(), 8);
+        assert_fits(&cone, &bounds());
+        let top = cone.faces().unwrap()[0].polygon.clone();
+        assert!(top.vertices.iter().all(|v| v.z == 128.0));
+        assert!(
+            top.vertices
+                .iter()
+                .any(|v| v.approx_eq(Vector3::new(32.0, 0.0, 128.0), 1e-6))
+        );
+    }
+
+    #[test]
+    fn sphere() {
+        let mut ids = VmfFile::default().id_allocator();
+        let sphere = Solid::sphere(&bounds(), 8, MATERIAL, &mut ids).unwrap();
+        // 8 sides around each of 4 rings.
+        assert_eq!(sphere.sides.len(), 32);
+        assert_fits(&sphere, &bounds());
+        let center = bounds().center();
+        for vertex in sphere.vertices().unwrap() {
+            assert!((vertex.distance(center) - 64.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn arch() {
+        let mut ids = VmfFile::default().id_allocator();
+        let options = ArchOptions {
+            sides: 4,
+            arc: 180.0,
+            wall_width: 16.0,
+            ..Default::default()
+        };
+        let arch = Solid::arch(&bounds(), &options, MATERIAL, &mut ids).unwrap();
+        assert_eq!(arch.len(), 4);
+        assert_eq!(
+            arch.iter().map(|s| s.id).collect::<Vec<_>>(),
+            vec![2, 3, 4, 5]
+        );
+        for piece in &arch {
+            assert_eq!(piece.sides.len(), 6);
+            assert_fits(piece, &bounds());
+        }
+        // The arch runs from +X to -X through +Y.
+        let vertices = arch[0].vertices().unwrap();
+        assert!(vertices.contains(&Vector3::new(64.0, 0.0, 0.0)));
+        assert!(vertices.contains(&Vector3::new(48.0, 0.0, 128.0)));
+        assert!(
+            arch.iter()
+                .all(|piece| { piece.vertices().unwrap().iter().all(|v| v.y > -1e-3) })
+        );
+
+        let stairs = ArchOptions {
+            add_height: 8.0,
+            ..Default::default()
+        };
+        let stairs = Solid::arch(&bounds(), &stairs, MATERIAL, &mut ids).unwrap();
+        assert_eq!(stairs.len(), 8);
+        let top = stairs[7].bounds().unwrap().unwrap();
+        assert_eq!((top.min.z, top.max.z), (56.0, 184.0));
+    }
+
+    #[test]
+    fn torus() {
+        let mut ids = VmfFile::default().id_allocator();
+        let options = TorusOptions {
+            sides: 12,
+            tube_sides: 6,
+            ..Default::default()
+        };
+        let torus = Solid::torus(&bounds(), &options, MATERIAL, &mut ids).unwrap();
+        assert_eq!(torus.len(), 12);
+        for piece in &torus {
+            assert_eq!(piece.sides.len(), 8);
+            assert_fits(piece, &bounds());
+        }
+    }
+
+    #[test]
+    fn rejects_bad_parameters() {
+        let mut ids = VmfFile::default().id_allocator();
+        let flat = Aabb::new(Vector3::ZERO, Vector3::new(64.0, 64.0, 0.0));
+        assert!(Solid::block(&flat, MATERIAL, &mut ids).is_err());
+        assert!(Solid::cylinder(&bounds(), 2, MATERIAL, &mut ids).is_err());
+        assert!(Solid::cone(&bounds(), 8, 1.5, MATERIAL, &mut ids).is_err());
+
+        for options in [
+            ArchOptions {
+                sides: 2,
+                ..Default::default()
+            },
+            ArchOptions {
+                wall_width: 80.0,
+                ..Default::default()
+            },
+            ArchOptions {
+                arc: 400.0,
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                Solid::arch(&bounds(), &options, MATERIAL, &mut ids),
+                Err(VmfError::InvalidFormat(_))
+            ));
+        }
+        // Nothing was allocated for the rejected brushes.
+        assert_eq!(ids.next_object_id(), 2);
+    }
+}
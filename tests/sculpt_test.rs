@@ -0,0 +1,236 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::geometry::{DispMesh, Falloff};
+    use vmf_forge::prelude::*;
+    use vmf_forge::vmf::world::{DispInfo, DispRows};
+
+    /// A flat power 2 displacement starting at `start`.
+    fn flat_disp(start: Vector3) -> DispInfo {
+        DispInfo {
+            power: 2,
+            start_position: format!("[{}]", start),
+            normals: DispRows::from_vectors(&vec![vec![Vector3::Z; 5]; 5]),
+            distances: DispRows::from_floats(&vec![vec![0.0; 5]; 5]),
+            alphas: DispRows::from_floats(&vec![vec![0.0; 5]; 5]),
+            ..Default::default()
+        }
+    }
+
+    /// Two 256 unit square floor brushes side by side along X, displaced on top.
+    fn terrain() -> VmfFile {
+        let mut vmf = VmfFile::default();
+        let mut ids = vmf.id_allocator();
+        for x in [0.0, 256.0] {
+            let bounds = Aabb::new(
+                Vector3::new(x, 0.0, -16.0),
+                Vector3::new(x + 256.0, 256.0, 0.0),
+            );
+            let mut solid = Solid::block(&bounds, "nature/blendgrassdirt", &mut ids).unwrap();
+            solid.sides[0].dispinfo = Some(flat_disp(Vector3::new(x, 0.0, 0.0)));
+            vmf.world.solids.push(solid);
+        }
+        vmf
+    }
+
+    fn set_power(vmf: &mut VmfFile, power: u8) -> VmfResult<()> {
+        let solid = &mut vmf.world.solids[0];
+        let mut disp = solid.sides[0].dispinfo.clone().unwrap();
+        disp.set_power(power, &solid.sides[0], solid)?;
+        solid.sides[0].dispinfo = Some(disp);
+        Ok(())
+    }
+
+    fn mesh(vmf: &VmfFile, index: usize) -> DispMesh {
+        let solid = &vmf.world.solids[index];
+        let side = &solid.sides[0];
+        side.dispinfo
+            .as_ref()
+            .unwrap()
+            .build_mesh(side, solid)
+            .unwrap()
+    }
+
+    /// The index of the vertex of `mesh` above `(x, y)`.
+    fn at(mesh: &DispMesh, x: f64, y: f64) -> usize {
+        mesh.flat_vertices
+            .iter()
+            .position(|v| v.x == x && v.y == y)
+            .unwrap()
+    }
+
+    /// Checks that the shared edge at x = 256 is closed.
+    fn assert_seam_closed(vmf: &VmfFile) {
+        let (west, east) = (mesh(vmf, 0), mesh(vmf, 1));
+        for y in [0.0, 64.0, 128.0, 192.0, 256.0] {
+            let a = west.vertices[at(&west, 256.0, y)];
+            let b = east.vertices[at(&east, 256.0, y)];
+            assert!(a.approx_eq(b, 1e-6), "{} != {}", a, b);
+        }
+    }
+
+    #[test]
+    fn raise_and_lower_across_a_seam() {
+        let mut vmf = terrain();
+        let mut brush = SculptBrush::new(Vector3::new(256.0, 128.0, 0.0), 100.0);
+        brush.falloff = Falloff::Constant;
+        assert_eq!(
+            vmf.sculpt_displacements(&brush, SculptOp::Raise(32.0))
+                .unwrap(),
+            2
+        );
+        assert_seam_closed(&vmf);
+
+        let west = mesh(&vmf, 0);
+        assert_eq!(west.vertices[at(&west, 256.0, 128.0)].z, 32.0);
+        assert_eq!(west.vertices[at(&west, 192.0, 128.0)].z, 32.0);
+        assert_eq!(west.vertices[at(&west, 128.0, 128.0)].z, 0.0);
+        assert_eq!(west.vertices[at(&west, 192.0, 192.0)].z, 32.0);
+
+        // Raising stores a distance along the face normal.
+        let disp = vmf.world.solids[0].sides[0].dispinfo.as_ref().unwrap();
+        let distances: Vec<f64> = disp.distances.floats().unwrap().concat();
+        assert_eq!(distances.iter().filter(|&&d| d == 32.0).count(), 6);
+        assert!(distances.iter().all(|&d| d == 0.0 || d == 32.0));
+
+        // Lowering past the face flips the normals and keeps the distances positive.
+        vmf.sculpt_displacements(&brush, SculptOp::Lower(48.0))
+            .unwrap();
+        assert_seam_closed(&vmf);
+        let west = mesh(&vmf, 0);
+        assert_eq!(west.vertices[at(&west, 256.0, 128.0)].z, -16.0);
+        let disp = vmf.world.solids[0].sides[0].dispinfo.as_ref().unwrap();
+        assert!(
+            disp.normals
+                .vectors()
+                .unwrap()
+                .concat()
+                .contains(&-Vector3::Z)
+        );
+        assert!(disp.distances.floats().unwrap().concat().contains(&16.0));
+
+        // Nothing within reach.
+        let far = SculptBrush::new(Vector3::new(0.0, 0.0, 1000.0), 64.0);
+        assert_eq!(
+            vmf.sculpt_displacements(&far, SculptOp::Raise(8.0))
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn smooth_and_noise_keep_seams_closed() {
+        let mut vmf = terrain();
+        let spike = SculptBrush::new(Vector3::new(256.0, 128.0, 0.0), 1.0);
+        vmf.sculpt_displacements(&spike, SculptOp::Raise(64.0))
+            .unwrap();
+
+        let everywhere = SculptBrush {
+            center: Vector3::new(256.0, 128.0, 0.0),
+            radius: 1000.0,
+            falloff: Falloff::Constant,
+        };
+        vmf.sculpt_displacements(&everywhere, SculptOp::Smooth(1.0))
+            .unwrap();
+        assert_seam_closed(&vmf);
+        let west = mesh(&vmf, 0);
+        // The spike is averaged with its four flat neighbors, which rise a quarter of it.
+        assert_eq!(west.vertices[at(&west, 256.0, 128.0)].z, 0.0);
+        assert_eq!(west.vertices[at(&west, 192.0, 128.0)].z, 16.0);
+
+        let noise = SculptOp::Noise {
+            amplitude: 8.0,
+            seed: 7,
+        };
+        let mut other = vmf.clone();
+        vmf.sculpt_displacements(&everywhere, noise).unwrap();
+        other.sculpt_displacements(&everywhere, noise).unwrap();
+        assert_eq!(vmf, other);
+        assert_seam_closed(&vmf);
+        let (before, after) = (west, mesh(&vmf, 0));
+        for (a, b) in before.vertices.iter().zip(&after.vertices) {
+            assert!((a.z - b.z).abs() <= 8.0);
+            assert_eq!((a.x, a.y), (b.x, b.y));
+        }
+        assert!(before.vertices != after.vertices);
+    }
+
+    #[test]
+    fn set_to_plane_and_paint_alpha() {
+        let mut vmf = terrain();
+        let mut brush = SculptBrush::new(Vector3::new(256.0, 128.0, 0.0), 1000.0);
+        brush.falloff = Falloff::Constant;
+        let plane = Plane::new(Vector3::Z, 8.0);
+        vmf.sculpt_displacements(&brush, SculptOp::SetToPlane(plane))
+            .unwrap();
+        for index in 0..2 {
+            assert!(mesh(&vmf, index).vertices.iter().all(|v| v.z == 8.0));
+        }
+
+        let mut paint = SculptBrush::new(Vector3::new(128.0, 128.0, 8.0), 64.0);
+        paint.falloff = Falloff::Linear;
+        assert_eq!(
+            vmf.world.solids[0]
+                .sculpt(&paint, SculptOp::PaintAlpha(255.0))
+                .unwrap(),
+            1
+        );
+        let west = mesh(&vmf, 0);
+        assert_eq!(west.alphas[at(&west, 128.0, 128.0)], 255.0);
+        assert_eq!(west.alphas[at(&west, 192.0, 128.0)], 0.0);
+        assert_eq!(west.alphas.iter().filter(|&&a| a > 0.0).count(), 1);
+        // Painting doesn't move anything.
+        assert!(west.vertices.iter().all(|v| v.z == 8.0));
+
+        let bad = SculptBrush::new(Vector3::ZERO, 0.0);
+        assert!(matches!(
+            vmf.sculpt_displacements(&bad, SculptOp::Raise(1.0)),
+            Err(VmfError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn set_power_resamples() {
+        let mut vmf = terrain();
+        let brush = SculptBrush::new(Vector3::new(128.0, 128.0, 0.0), 100.0);
+        vmf.sculpt_displacements(&brush, SculptOp::Raise(32.0))
+            .unwrap();
+        let before = mesh(&vmf, 0);
+
+        set_power(&mut vmf, 3).unwrap();
+        let disp = vmf.world.solids[0].sides[0].dispinfo.as_ref().unwrap();
+        assert_eq!(disp.power, 3);
+        assert_eq!(disp.triangle_tags.rows.len(), 8);
+        assert_eq!(disp.alphas.floats().unwrap().concat().len(), 81);
+        // The gentle slopes of the raised terrain stay walkable and buildable.
+        let tags = disp.triangle_tags.floats().unwrap().concat();
+        assert_eq!(tags.len(), 128);
+        assert!(tags.iter().all(|&tag| tag == 9.0));
+
+        let after = mesh(&vmf, 0);
+        assert_eq!(after.vertices.len(), 81);
+        for (i, vertex) in before.vertices.iter().enumerate() {
+            let (row, column) = (i / 5, i % 5);
+            assert!(after.vertex(row * 2, column * 2).approx_eq(*vertex, 1e-6));
+        }
+        // The vertex between the raised center and its neighbor is halfway up.
+        let center = before.vertices[at(&before, 128.0, 128.0)].z;
+        let side = before.vertices[at(&before, 192.0, 128.0)].z;
+        let between = after.vertices[at(&after, 160.0, 128.0)].z;
+        assert!((between - (center + side) / 2.0).abs() < 1e-6);
+
+        // And back down loses nothing on the original grid.
+        set_power(&mut vmf, 2).unwrap();
+        let down = mesh(&vmf, 0);
+        for (a, b) in before.vertices.iter().zip(&down.vertices) {
+            assert!(a.approx_eq(*b, 1e-6));
+        }
+
+        let unchanged = vmf.clone();
+        assert!(matches!(
+            set_power(&mut vmf, 5),
+            Err(VmfError::InvalidFormat(_))
+        ));
+        assert_eq!(vmf, unchanged);
+    }
+}
@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::geometry::{DispMesh, DispSew};
+    use vmf_forge::prelude::*;
+    use vmf_forge::vmf::world::DispRows;
+
+    /// Two 128 unit square floor tiles side by side along X, displaced on top with
+    /// the given powers.
+    fn tiles(powers: [u8; 2]) -> VmfFile {
+        let mut vmf = VmfFile::default();
+        let mut ids = vmf.id_allocator();
+        for (x, power) in [0.0, 128.0].into_iter().zip(powers) {
+            let bounds = Aabb::new(
+                Vector3::new(x, 0.0, -16.0),
+                Vector3::new(x + 128.0, 128.0, 0.0),
+            );
+            let mut solid = Solid::block(&bounds, "nature/blendgrassdirt", &mut ids).unwrap();
+            let disp = solid.sides[0].make_displacement(&solid, power).unwrap();
+            solid.sides[0].dispinfo = Some(disp);
+            vmf.world.solids.push(solid);
+        }
+        vmf
+    }
+
+    fn mesh(vmf: &VmfFile, index: usize) -> DispMesh {
+        let solid = &vmf.world.solids[index];
+        let side = &solid.sides[0];
+        side.dispinfo
+            .as_ref()
+            .unwrap()
+            .build_mesh(side, solid)
+            .unwrap()
+    }
+
+    /// Sets the distance of each vertex of a tile from its undisplaced position.
+    fn raise(vmf: &mut VmfFile, index: usize, height: impl Fn(Vector3) -> f64) {
+        let flat = mesh(vmf, index).flat_vertices;
+        let disp = vmf.world.solids[index].sides[0].dispinfo.as_mut().unwrap();
+        let size = (1usize << disp.power) + 1;
+        let distances: Vec<Vec<f64>> = flat
+            .chunks(size)
+            .map(|row| row.iter().map(|&v| height(v)).collect())
+            .collect();
+        disp.distances = DispRows::from_floats(&distances);
+    }
+
+    /// The displaced heights of the vertices on the shared edge at X = 128.
+    fn seam(vmf: &VmfFile, index: usize) -> Vec<f64> {
+        let mesh = mesh(vmf, index);
+        let mut seam: Vec<(f64, f64)> = mesh
+            .flat_vertices
+            .iter()
+            .zip(&mesh.vertices)
+            .filter(|(flat, _)| flat.x == 128.0)
+            .map(|(flat, v)| (flat.y, v.z))
+            .collect();
+        seam.sort_by(|a, b| a.0.total_cmp(&b.0));
+        seam.into_iter().map(|(_, z)| z).collect()
+    }
+
+    #[test]
+    fn shared_edges_meet_halfway() {
+        let mut vmf = tiles([2, 2]);
+        raise(&mut vmf, 0, |_| 16.0);
+        let sew = vmf.sew_displacements().unwrap();
+        // Both tiles changed, and each of the 5 seam vertices counts once.
+        assert_eq!(sew.displacements, 2);
+        assert_eq!(sew.sewn_vertices, 5);
+        assert_eq!(sew.disallowed_vertices, 0);
+        assert_eq!(seam(&vmf, 0), vec![8.0; 5]);
+        assert_eq!(seam(&vmf, 1), vec![8.0; 5]);
+
+        // A second pass finds nothing left to sew.
+        assert_eq!(vmf.sew_displacements().unwrap(), DispSew::default());
+        let disp = vmf.world.solids[0].sides[0].dispinfo.as_ref().unwrap();
+        assert_eq!(disp.allowed_verts["10"], vec![-1; 10]);
+    }
+
+    #[test]
+    fn finer_edges_follow_coarser_neighbors() {
+        let mut vmf = tiles([3, 2]);
+        raise(&mut vmf, 1, |v| v.y / 4.0);
+        let sew = vmf.sew_displacements().unwrap();
+
+        // The shared vertices meet halfway, and those between them stay on the line.
+        let expected: Vec<f64> = (0..9).map(|i| i as f64 * 2.0).collect();
+        assert_eq!(seam(&vmf, 0), expected);
+        assert_eq!(sew.disallowed_vertices, 4);
+
+        // The fine tile can't use the edge vertices its neighbor doesn't have.
+        let flat = mesh(&vmf, 0).flat_vertices;
+        let disp = vmf.world.solids[0].sides[0].dispinfo.as_ref().unwrap();
+        let words = &disp.allowed_verts["10"];
+        assert_eq!(words.len(), 10);
+        let disallowed: Vec<Vector3> = (0..320)
+            .filter(|&i| words[i / 32] & (1 << (i % 32)) == 0)
+            .map(|i| flat[i])
+            .collect();
+        assert_eq!(disallowed.len(), 4);
+        for v in disallowed {
+            assert_eq!(v.x, 128.0);
+            assert_eq!((v.y / 16.0) % 2.0, 1.0);
+        }
+        let coarse = vmf.world.solids[1].sides[0].dispinfo.as_ref().unwrap();
+        assert_eq!(coarse.allowed_verts["10"], vec![-1; 10]);
+    }
+
+    #[test]
+    fn triangle_tags_follow_slope() {
+        let mut vmf = tiles([2, 2]);
+        vmf.world.solids.truncate(1);
+        raise(&mut vmf, 0, |v| if v.x == 128.0 { 256.0 } else { 0.0 });
+        let disp = vmf.world.solids[0].sides[0].dispinfo.as_mut().unwrap();
+        // Force the walkable bit off everywhere.
+        disp.triangle_tags = DispRows::from_floats(&vec![vec![2.0; 8]; 4]);
+        vmf.sew_displacements().unwrap();
+
+        // Triangles touching the raised edge are too steep for either bit.
+        let mesh = mesh(&vmf, 0);
+        let disp = vmf.world.solids[0].sides[0].dispinfo.as_ref().unwrap();
+        let tags: Vec<f64> = disp.triangle_tags.floats().unwrap().concat();
+        assert_eq!(tags.len(), mesh.triangles.len());
+        for (triangle, tag) in mesh.triangles.iter().zip(tags) {
+            let steep = triangle
+                .iter()
+                .any(|&i| mesh.flat_vertices[i as usize].x == 128.0);
+            assert_eq!(tag, if steep { 2.0 } else { 10.0 });
+        }
+    }
+}
@@ -0,0 +1,234 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::prelude::*;
+    use vmf_forge::vmf_file::SpatialEntry;
+
+    const MATERIAL: &str = "dev/dev_measuregeneric01";
+
+    fn aabb(min: [f64; 3], max: [f64; 3]) -> Aabb {
+        Aabb::new(Vector3::from(min), Vector3::from(max))
+    }
+
+    /// A floor, a 10 by 10 grid of pillars on it, a trigger and a few nodes.
+    fn town() -> VmfFile {
+        let mut vmf = VmfFile::default();
+        let mut ids = vmf.id_allocator();
+        let floor = aabb([-1024.0, -1024.0, -16.0], [1024.0, 1024.0, 0.0]);
+        vmf.world
+            .solids
+            .push(Solid::block(&floor, MATERIAL, &mut ids).unwrap());
+        for i in 0..10 {
+            for j in 0..10 {
+                let min = [i as f64 * 128.0, j as f64 * 128.0, 0.0];
+                let max = [min[0] + 64.0, min[1] + 64.0, 256.0];
+                let pillar = Solid::block(&aabb(min, max), MATERIAL, &mut ids).unwrap();
+                vmf.world.solids.push(pillar);
+            }
+        }
+
+        let mut trigger = Entity::new("trigger_once", ids.next_object_id());
+        let volume = aabb([-512.0, -512.0, 0.0], [-256.0, -256.0, 128.0]);
+        trigger.solids = Some(vec![Solid::block(&volume, MATERIAL, &mut ids).unwrap()]);
+        vmf.entities.push(trigger);
+
+        for (x, y) in [(-300.0, -300.0), (-600.0, -600.0), (100.0, 96.0)] {
+            let mut node = Entity::new("info_node", ids.next_object_id());
+            node.set_origin(Vector3::new(x, y, 16.0));
+            vmf.entities.push(node);
+        }
+        vmf
+    }
+
+    fn keys(entries: Vec<&SpatialEntry>) -> Vec<SpatialKey> {
+        entries.iter().map(|e| e.key).collect()
+    }
+
+    #[test]
+    fn aabb_query_matches_a_scan() {
+        let vmf = town();
+        let index = SpatialIndex::build(&vmf, &BoundsOptions::default()).unwrap();
+        // 101 world brushes, the trigger and its brush, and 3 nodes.
+        assert_eq!(index.len(), 106);
+
+        let query = aabb([100.0, 100.0, 32.0], [400.0, 200.0, 64.0]);
+        let mut expected: Vec<SpatialKey> = vmf
+            .world
+            .solids
+            .iter()
+            .filter(|s| s.bounds().unwrap().unwrap().intersects(&query))
+            .map(|s| SpatialKey::Solid(s.id))
+            .collect();
+        expected.sort();
+        assert_eq!(expected.len(), 3);
+        assert_eq!(keys(index.query_aabb(&query)), expected);
+
+        let entry = index.get(SpatialKey::Solid(2)).unwrap();
+        assert_eq!(
+            entry.bounds,
+            aabb([-1024.0, -1024.0, -16.0], [1024.0, 1024.0, 0.0])
+        );
+        assert_eq!(entry.owner, None);
+    }
+
+    #[test]
+    fn point_sphere_and_brush_queries() {
+        let vmf = town();
+        let index = SpatialIndex::build(&vmf, &BoundsOptions::default()).unwrap();
+        let trigger = &vmf.entities[0];
+        let trigger_brush = &trigger.solids.as_ref().unwrap()[0];
+
+        assert_eq!(
+            keys(index.query_point(Vector3::new(-300.0, -300.0, 64.0))),
+            vec![
+                SpatialKey::Solid(trigger_brush.id),
+                SpatialKey::Entity(trigger.id())
+            ]
+        );
+        assert_eq!(
+            keys(index.query_point(Vector3::new(32.0, 32.0, 300.0))),
+            vec![]
+        );
+
+        // The entities inside the trigger: the trigger itself and one node.
+        let inside = index.query_solid(trigger_brush).unwrap();
+        let entities: Vec<&SpatialEntry> = inside
+            .into_iter()
+            .filter(|e| e.classname.as_deref() == Some("info_node"))
+            .collect();
+        assert_eq!(
+            keys(entities),
+            vec![SpatialKey::Entity(vmf.entities[1].id())]
+        );
+
+        // The floor under the node and the two pillars within reach.
+        let near = index.query_sphere(Vector3::new(100.0, 96.0, 16.0), 45.0);
+        assert_eq!(
+            keys(near),
+            vec![
+                SpatialKey::Solid(2),
+                SpatialKey::Solid(13),
+                SpatialKey::Solid(14),
+                SpatialKey::Entity(vmf.entities[3].id())
+            ]
+        );
+    }
+
+    #[test]
+    fn nearest_entity() {
+        let vmf = town();
+        let index = SpatialIndex::build(&vmf, &BoundsOptions::default()).unwrap();
+        let (entry, distance) = index
+            .nearest(Vector3::new(-700.0, -600.0, 16.0), |e| {
+                e.classname.as_deref() == Some("info_node")
+            })
+            .unwrap();
+        assert_eq!(entry.key, SpatialKey::Entity(vmf.entities[2].id()));
+        assert_eq!(distance, 100.0);
+        assert!(index.nearest(Vector3::ZERO, |_| false).is_none());
+    }
+
+    #[test]
+    fn ray_cast() {
+        let vmf = town();
+        let index = SpatialIndex::build(&vmf, &BoundsOptions::default()).unwrap();
+
+        // Straight down between the pillars onto the floor.
+        let hit = index
+            .ray_cast(Vector3::new(96.0, 96.0, 512.0), -Vector3::Z, 4096.0)
+            .unwrap();
+        let floor = &vmf.world.solids[0];
+        assert_eq!(hit.solid_id, floor.id);
+        assert_eq!(hit.side_id, floor.sides[0].id);
+        assert_eq!(hit.distance, 512.0);
+        assert_eq!(hit.point, Vector3::new(96.0, 96.0, 0.0));
+        assert_eq!(hit.normal, Vector3::Z);
+
+        // Sideways into the first pillar's -X face.
+        let hit = index
+            .ray_cast(Vector3::new(-100.0, 32.0, 64.0), Vector3::X * 2.0, 4096.0)
+            .unwrap();
+        assert_eq!(hit.solid_id, 3);
+        assert_eq!(hit.distance, 100.0);
+        assert_eq!(hit.normal, -Vector3::X);
+
+        assert!(
+            index
+                .ray_cast(Vector3::new(-100.0, 32.0, 64.0), Vector3::X, 50.0)
+                .is_none()
+        );
+        assert!(
+            index
+                .ray_cast(Vector3::new(96.0, 96.0, 512.0), Vector3::Z, 4096.0)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn incremental_updates() {
+        let mut vmf = town();
+        let mut index = SpatialIndex::build(&vmf, &BoundsOptions::default()).unwrap();
+
+        // Move a pillar far outside the original bounds.
+        let pillar = vmf.find_solid_mut(3).unwrap();
+        let offset = Transform::translation(Vector3::new(8192.0, 0.0, 0.0));
+        pillar
+            .apply_transform(&offset, TextureLock::default())
+            .unwrap();
+        index
+            .insert_solid(vmf.find_solid(3).unwrap(), None)
+            .unwrap();
+        assert_eq!(index.len(), 106);
+        assert_eq!(
+            keys(index.query_point(Vector3::new(8224.0, 32.0, 64.0))),
+            vec![SpatialKey::Solid(3)]
+        );
+        assert!(
+            index
+                .query_point(Vector3::new(32.0, 32.0, 64.0))
+                .iter()
+                .all(|e| e.key != SpatialKey::Solid(3))
+        );
+
+        // Removing the trigger removes its brush too.
+        let trigger_id = vmf.entities[0].id();
+        let brush_id = vmf.entities[0].solids.as_ref().unwrap()[0].id;
+        assert!(index.remove(SpatialKey::Entity(trigger_id)).is_some());
+        assert_eq!(index.get(SpatialKey::Solid(brush_id)), None);
+        assert_eq!(index.len(), 104);
+        assert!(index.remove(SpatialKey::Entity(trigger_id)).is_none());
+    }
+
+    #[test]
+    fn build_rejects_duplicate_ids() {
+        let mut vmf = town();
+        let copy = vmf.world.solids[5].clone();
+        vmf.world.solids.push(copy);
+        assert!(matches!(
+            SpatialIndex::build(&vmf, &BoundsOptions::default()),
+            Err(VmfError::InvalidFormat(_))
+        ));
+
+        // A brush entity reusing the ID of a world brush.
+        let mut vmf = town();
+        let id = vmf.world.solids[5].id;
+        vmf.entities[0].solids.as_mut().unwrap()[0].id = id;
+        assert!(SpatialIndex::build(&vmf, &BoundsOptions::default()).is_err());
+
+        let mut vmf = town();
+        let node = vmf.entities[1].clone();
+        vmf.entities.push(node);
+        assert!(SpatialIndex::build(&vmf, &BoundsOptions::default()).is_err());
+    }
+
+    #[test]
+    fn find_by_id() {
+        let vmf = town();
+        let trigger = &vmf.entities[0];
+        let brush = &trigger.solids.as_ref().unwrap()[0];
+        assert_eq!(vmf.find_solid(brush.id), Some(brush));
+        assert_eq!(vmf.find_entity(trigger.id()), Some(trigger));
+        assert_eq!(vmf.find_side(brush.sides[2].id), Some(&brush.sides[2]));
+        assert_eq!(vmf.find_solid(9999), None);
+    }
+}
@@ -0,0 +1,274 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::prelude::*;
+
+    /// Builds an axis-aligned box with world-aligned textures; side IDs start at `first_side_id`.
+    fn box_solid(id: u64, first_side_id: u32, min: Vector3, max: Vector3) -> Solid {
+        let planes = [
+            Plane::new(Vector3::Z, max.z),
+            Plane::new(-Vector3::Z, -min.z),
+            Plane::new(-Vector3::X, -min.x),
+            Plane::new(Vector3::X, max.x),
+            Plane::new(Vector3::Y, max.y),
+            Plane::new(-Vector3::Y, -min.y),
+        ];
+        let sides = planes
+            .iter()
+            .enumerate()
+            .map(|(i, plane)| {
+                let mut side = Side {
+                    id: first_side_id + i as u32,
+                    material: "dev/dev_measuregeneric01".to_string(),
+                    u_axis: "[1 0 0 0] 0.25".to_string(),
+                    v_axis: "[0 -1 0 0] 0.25".to_string(),
+                    ..Default::default()
+                };
+                side.set_plane_points(plane.to_points(64.0));
+                side.align_to_world().unwrap();
+                side
+            })
+            .collect();
+        Solid {
+            id,
+            sides,
+            ..Default::default()
+        }
+    }
+
+    fn top_polygon(solid: &Solid) -> Polygon {
+        solid.faces().unwrap()[0].polygon.clone()
+    }
+
+    #[test]
+    fn fit_texture() {
+        let mut solid = box_solid(
+            1,
+            1,
+            Vector3::new(-40.0, -8.0, 0.0),
+            Vector3::new(40.0, 8.0, 8.0),
+        );
+        let polygon = top_polygon(&solid);
+        let top = &mut solid.sides[0];
+        top.fit_texture(&polygon, [64, 64], [1.0, 1.0]).unwrap();
+
+        let [u, v] = top.texture_axes().unwrap();
+        assert_eq!(u.scale, 1.25);
+        assert_eq!(v.scale, 0.25);
+        assert_eq!(
+            top.texture_coords(Vector3::new(-40.0, 8.0, 8.0)).unwrap(),
+            [0.0, 0.0]
+        );
+        assert_eq!(
+            top.texture_coords(Vector3::new(40.0, -8.0, 8.0)).unwrap(),
+            [64.0, 64.0]
+        );
+
+        // Shifts wrap into the material, so only the scale tells the repeat count apart.
+        top.fit_texture(&polygon, [64, 64], [2.0, 1.0]).unwrap();
+        let [u, _] = top.texture_axes().unwrap();
+        assert_eq!(u.scale, 0.625);
+        assert_eq!(
+            top.texture_coords(Vector3::new(-40.0, 8.0, 8.0)).unwrap()[0].rem_euclid(64.0),
+            0.0
+        );
+    }
+
+    #[test]
+    fn justify_texture() {
+        let mut solid = box_solid(
+            1,
+            1,
+            Vector3::new(-40.0, -8.0, 0.0),
+            Vector3::new(40.0, 8.0, 8.0),
+        );
+        let polygon = top_polygon(&solid);
+        let top = &mut solid.sides[0];
+
+        top.justify_texture(&polygon, [64, 64], Justify::Right)
+            .unwrap();
+        let [u, _] = top.texture_axes().unwrap();
+        assert_eq!(u.scale, 0.25);
+        assert_eq!(
+            top.texture_coords(Vector3::new(40.0, 0.0, 8.0)).unwrap()[0] % 64.0,
+            0.0
+        );
+
+        top.justify_texture(&polygon, [64, 64], Justify::Left)
+            .unwrap();
+        assert_eq!(
+            top.texture_coords(Vector3::new(-40.0, 0.0, 8.0)).unwrap()[0] % 64.0,
+            0.0
+        );
+
+        top.justify_texture(&polygon, [64, 64], Justify::Top)
+            .unwrap();
+        assert_eq!(
+            top.texture_coords(Vector3::new(0.0, 8.0, 8.0)).unwrap()[1] % 64.0,
+            0.0
+        );
+
+        top.justify_texture(&polygon, [64, 32], Justify::Bottom)
+            .unwrap();
+        assert_eq!(
+            top.texture_coords(Vector3::new(0.0, -8.0, 8.0)).unwrap()[1] % 32.0,
+            0.0
+        );
+
+        top.justify_texture(&polygon, [64, 64], Justify::Center)
+            .unwrap();
+        assert_eq!(
+            top.texture_coords(Vector3::new(0.0, 0.0, 8.0)).unwrap(),
+            [32.0, 32.0]
+        );
+    }
+
+    #[test]
+    fn align_to_world_and_face() {
+        let mut side = Side {
+            u_axis: "[0.6 0.8 0 12] 0.5".to_string(),
+            v_axis: "[0.8 -0.6 0 4] 0.5".to_string(),
+            rotation: Some(37.0),
+            ..Default::default()
+        };
+        let slope = Plane::from_point_normal(Vector3::ZERO, Vector3::new(0.0, -1.0, 2.0));
+        side.set_plane_points(slope.to_points(64.0));
+
+        side.align_to_world().unwrap();
+        assert_eq!(side.u_axis, "[1 0 0 12] 0.5");
+        assert_eq!(side.v_axis, "[0 -1 0 4] 0.5");
+        assert_eq!(side.rotation, Some(0.0));
+
+        side.align_to_face().unwrap();
+        let [u, v] = side.texture_axes().unwrap();
+        assert!(u.axis.approx_eq(Vector3::X, 1e-9));
+        let expected_v = Vector3::new(0.0, -2.0, -1.0).normalize();
+        assert!(v.axis.approx_eq(expected_v, 1e-6));
+        assert!(v.axis.dot(slope.normal.normalize()).abs() < 1e-6);
+        assert_eq!((u.shift, u.scale), (12.0, 0.5));
+    }
+
+    #[test]
+    fn set_scale_and_shift() {
+        let mut side = Side {
+            u_axis: "[1 0 0 0] 0.25".to_string(),
+            v_axis: "[0 -1 0 0] 0.25".to_string(),
+            ..Default::default()
+        };
+        side.set_texture_scale([0.5, -1.0]).unwrap();
+        side.set_texture_shift([-16.0, 300.0], [128, 256]).unwrap();
+        assert_eq!(side.u_axis, "[1 0 0 112] 0.5");
+        assert_eq!(side.v_axis, "[0 -1 0 44] -1");
+    }
+
+    #[test]
+    fn copy_alignment_to_coplanar_faces() {
+        let mut vmf = VmfFile::default();
+        let solids = [
+            box_solid(2, 1, Vector3::ZERO, Vector3::new(64.0, 64.0, 64.0)),
+            box_solid(
+                3,
+                7,
+                Vector3::new(64.0, 0.0, 0.0),
+                Vector3::new(128.0, 64.0, 64.0),
+            ),
+            // Only touches the second box at a corner.
+            box_solid(
+                4,
+                13,
+                Vector3::new(128.0, 64.0, 0.0),
+                Vector3::new(192.0, 128.0, 64.0),
+            ),
+            // Coplanar but separated from the others.
+            box_solid(
+                5,
+                19,
+                Vector3::new(512.0, 0.0, 0.0),
+                Vector3::new(576.0, 64.0, 64.0),
+            ),
+            // Touching, but its top is lower.
+            box_solid(
+                6,
+                25,
+                Vector3::new(0.0, 64.0, 0.0),
+                Vector3::new(64.0, 128.0, 32.0),
+            ),
+        ];
+        vmf.world.solids.extend(solids);
+        vmf.world.solids[0].sides[0].u_axis = "[1 0 0 17] 0.5".to_string();
+
+        let updated = vmf.copy_alignment_to_coplanar(1).unwrap();
+        assert_eq!(updated, 2);
+
+        let tops: Vec<&str> = vmf
+            .world
+            .solids
+            .iter()
+            .map(|s| s.sides[0].u_axis.as_str())
+            .collect();
+        assert_eq!(
+            tops,
+            vec![
+                "[1 0 0 17] 0.5",
+                "[1 0 0 17] 0.5",
+                "[1 0 0 17] 0.5",
+                "[1 0 0 0] 0.25",
+                "[1 0 0 0] 0.25",
+            ]
+        );
+        // Side faces are left alone.
+        assert_eq!(vmf.world.solids[1].sides[2].u_axis, "[0 1 0 0] 0.25");
+
+        assert!(matches!(
+            vmf.copy_alignment_to_coplanar(999),
+            Err(VmfError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn copy_alignment_to_face_inside_another() {
+        // A small detail block whose top sits inside the top of a large one.
+        let mut vmf = VmfFile::default();
+        vmf.world.solids.extend([
+            box_solid(2, 1, Vector3::ZERO, Vector3::new(256.0, 256.0, 64.0)),
+            box_solid(
+                3,
+                7,
+                Vector3::new(96.0, 96.0, 32.0),
+                Vector3::new(160.0, 160.0, 64.0),
+            ),
+        ]);
+        vmf.world.solids[0].sides[0].u_axis = "[1 0 0 17] 0.5".to_string();
+
+        assert_eq!(vmf.copy_alignment_to_coplanar(1).unwrap(), 1);
+        assert_eq!(vmf.world.solids[1].sides[0].u_axis, "[1 0 0 17] 0.5");
+        // And the other way around.
+        vmf.world.solids[1].sides[0].u_axis = "[1 0 0 3] 0.5".to_string();
+        assert_eq!(vmf.copy_alignment_to_coplanar(7).unwrap(), 1);
+        assert_eq!(vmf.world.solids[0].sides[0].u_axis, "[1 0 0 3] 0.5");
+    }
+
+    #[test]
+    fn copy_alignment_across_crossing_faces() {
+        // Two flush blocks crossing like a plus sign, with no corner inside the other.
+        let mut vmf = VmfFile::default();
+        vmf.world.solids.extend([
+            box_solid(
+                2,
+                1,
+                Vector3::new(0.0, 96.0, 0.0),
+                Vector3::new(256.0, 160.0, 64.0),
+            ),
+            box_solid(
+                3,
+                7,
+                Vector3::new(96.0, 0.0, 0.0),
+                Vector3::new(160.0, 256.0, 64.0),
+            ),
+        ]);
+        vmf.world.solids[0].sides[0].u_axis = "[1 0 0 17] 0.5".to_string();
+
+        assert_eq!(vmf.copy_alignment_to_coplanar(1).unwrap(), 1);
+        assert_eq!(vmf.world.solids[1].sides[0].u_axis, "[1 0 0 17] 0.5");
+    }
+}
@@ -0,0 +1,241 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use vmf_forge::prelude::*;
+    use vmf_forge::vmf::world::{DispInfo, DispRows, TextureAxis};
+
+    /// The box from `frame.vmf`: 80x16x8 units, centered on X and Y, floor at 0.
+    fn frame_box() -> Solid {
+        let vmf = VmfFile::open("vmf_examples/instances/frame.vmf").unwrap();
+        vmf.world.solids[0].clone()
+    }
+
+    fn texture_coord(axis: &TextureAxis, point: Vector3) -> f64 {
+        point.dot(axis.axis) / axis.scale + axis.shift
+    }
+
+    fn assert_outward(solid: &Solid) {
+        let center = solid.bounds().unwrap().unwrap().center();
+        for face in solid.faces().unwrap() {
+            assert!(
+                face.plane.distance_to(center) < 0.0,
+                "side {}",
+                face.side.id
+            );
+        }
+        assert!(solid.is_valid());
+    }
+
+    #[test]
+    fn transform_builders() {
+        let p = Vector3::new(10.0, 20.0, 30.0);
+        assert_eq!(
+            Transform::translation(Vector3::X).transform_point(p),
+            Vector3::new(11.0, 20.0, 30.0)
+        );
+
+        let turn = Transform::rotation_around(Vector3::Z, 90.0, Vector3::new(10.0, 0.0, 0.0));
+        assert!(
+            turn.transform_point(p)
+                .approx_eq(Vector3::new(-10.0, 0.0, 30.0), 1e-9)
+        );
+
+        let scale = Transform::scale(Vector3::new(2.0, 1.0, 1.0), Vector3::new(10.0, 0.0, 0.0));
+        assert_eq!(scale.transform_point(p), Vector3::new(10.0, 20.0, 30.0));
+        assert_eq!(
+            scale.transform_point(Vector3::ZERO),
+            Vector3::new(-10.0, 0.0, 0.0)
+        );
+
+        let mirror = Transform::mirror(&Plane::new(Vector3::X, 16.0));
+        assert!(mirror.is_mirroring());
+        assert!(
+            mirror
+                .transform_point(p)
+                .approx_eq(Vector3::new(22.0, 20.0, 30.0), 1e-9)
+        );
+
+        let yaw = Transform::rotation(Vector3::new(0.0, 90.0, 0.0));
+        assert!(yaw.transform_vector(Vector3::X).approx_eq(Vector3::Y, 1e-9));
+    }
+
+    #[test]
+    fn translate_with_texture_lock() {
+        let mut solid = frame_box();
+        let before = solid.sides[0].texture_axes().unwrap();
+        let offset = Vector3::new(24.0, -8.0, 64.0);
+        solid
+            .apply_transform(&Transform::translation(offset), TextureLock::On)
+            .unwrap();
+
+        let bounds = solid.bounds().unwrap().unwrap();
+        assert_eq!(bounds.min, Vector3::new(-16.0, -16.0, 64.0));
+        assert_eq!(bounds.max, Vector3::new(64.0, 0.0, 72.0));
+
+        // The texture moved with the face.
+        let after = solid.sides[0].texture_axes().unwrap();
+        let corner = Vector3::new(-40.0, 8.0, 8.0);
+        for (old, new) in before.iter().zip(&after) {
+            assert_eq!(old.scale, new.scale);
+            let expected = texture_coord(old, corner);
+            assert!((texture_coord(new, corner + offset) - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn translate_without_texture_lock() {
+        let mut solid = frame_box();
+        let before = solid.sides[0].texture_axes().unwrap();
+        solid
+            .apply_transform(
+                &Transform::translation(Vector3::new(24.0, 0.0, 0.0)),
+                TextureLock::Off,
+            )
+            .unwrap();
+        assert_eq!(solid.sides[0].texture_axes().unwrap(), before);
+    }
+
+    #[test]
+    fn rotate_without_texture_lock_keeps_world_alignment() {
+        let mut solid = frame_box();
+        // Tip the box onto its side: the top now faces -Y.
+        let tip = Transform::rotation_around(Vector3::X, 90.0, Vector3::ZERO);
+        solid.apply_transform(&tip, TextureLock::Off).unwrap();
+
+        let top = &solid.sides[0];
+        assert!(top.to_plane().unwrap().normal.approx_eq(-Vector3::Y, 1e-9));
+        let [u, v] = top.texture_axes().unwrap();
+        assert_eq!(u.axis, Vector3::X);
+        assert_eq!(v.axis, Vector3::new(0.0, 0.0, -1.0));
+        assert_outward(&solid);
+    }
+
+    #[test]
+    fn rotate_with_texture_lock_turns_axes() {
+        let mut solid = frame_box();
+        let turn = Transform::rotation_around(Vector3::Z, 90.0, Vector3::ZERO);
+        solid.apply_transform(&turn, TextureLock::On).unwrap();
+
+        let [u, v] = solid.sides[0].texture_axes().unwrap();
+        assert!(u.axis.approx_eq(Vector3::Y, 1e-9));
+        assert!(v.axis.approx_eq(Vector3::X, 1e-9));
+        assert_outward(&solid);
+    }
+
+    #[test]
+    fn scale_texture_lock_modes() {
+        let stretch = Transform::scale(Vector3::new(2.0, 1.0, 1.0), Vector3::ZERO);
+
+        let mut locked = frame_box();
+        locked.apply_transform(&stretch, TextureLock::On).unwrap();
+        let [u, _] = locked.sides[0].texture_axes().unwrap();
+        assert_eq!(u.scale, 0.25);
+
+        let mut scaled = frame_box();
+        scaled
+            .apply_transform(&stretch, TextureLock::WithScale)
+            .unwrap();
+        let [u, _] = scaled.sides[0].texture_axes().unwrap();
+        assert!((u.scale - 0.5).abs() < 1e-9);
+
+        let bounds = scaled.bounds().unwrap().unwrap();
+        assert_eq!(bounds.size(), Vector3::new(160.0, 16.0, 8.0));
+    }
+
+    #[test]
+    fn mirror_keeps_planes_outward() {
+        let mut solid = frame_box();
+        let mirror = Transform::mirror(&Plane::new(Vector3::X, 64.0));
+        solid.apply_transform(&mirror, TextureLock::On).unwrap();
+
+        let bounds = solid.bounds().unwrap().unwrap();
+        assert_eq!(bounds.min.x, 88.0);
+        assert_eq!(bounds.max.x, 168.0);
+        assert_outward(&solid);
+
+        // The texture is mirrored along with the geometry.
+        let [u, _] = solid.sides[0].texture_axes().unwrap();
+        assert!(u.axis.approx_eq(-Vector3::X, 1e-9));
+    }
+
+    #[test]
+    fn mirror_transposes_displacement_grids() {
+        let mut disp = DispInfo {
+            start_position: "[0 0 0]".to_string(),
+            normals: DispRows::from_vectors(&[
+                vec![Vector3::Z, Vector3::X],
+                vec![Vector3::Y, Vector3::Z],
+            ]),
+            distances: DispRows::from_floats(&[vec![1.0, 2.0], vec![3.0, 4.0]]),
+            alphas: DispRows::from_floats(&[vec![0.0, 255.0], vec![128.0, 0.0]]),
+            ..Default::default()
+        };
+
+        disp.apply_transform(&Transform::mirror(&Plane::new(Vector3::X, 0.0)))
+            .unwrap();
+        assert_eq!(
+            disp.distances.floats().unwrap(),
+            vec![vec![1.0, 3.0], vec![2.0, 4.0]]
+        );
+        assert_eq!(
+            disp.alphas.floats().unwrap(),
+            vec![vec![0.0, 128.0], vec![255.0, 0.0]]
+        );
+        assert_eq!(
+            disp.normals.vectors().unwrap(),
+            vec![vec![Vector3::Z, Vector3::Y], vec![-Vector3::X, Vector3::Z]]
+        );
+
+        disp.apply_transform(&Transform::translation(Vector3::new(0.0, 0.0, 16.0)))
+            .unwrap();
+        assert_eq!(disp.start_position, "[0 0 16]");
+        assert_eq!(
+            disp.distances.floats().unwrap(),
+            vec![vec![1.0, 3.0], vec![2.0, 4.0]]
+        );
+    }
+
+    #[test]
+    fn entity_transform() {
+        let mut ent = Entity::new("info_player_start", 2);
+        ent.set_origin(Vector3::new(16.0, 0.0, 0.0));
+        ent.set("angle".to_string(), "30".to_string());
+
+        let turn = Transform::rotation_around(Vector3::Z, 90.0, Vector3::ZERO);
+        ent.apply_transform(&turn, TextureLock::On).unwrap();
+        assert_eq!(ent.get("origin").unwrap(), "0 16 0");
+        assert_eq!(ent.get("angle").unwrap(), "120");
+        assert!(ent.get("angles").is_none());
+
+        // Mirroring across the YZ plane turns a yaw of 120 into 60.
+        let mirror = Transform::mirror(&Plane::new(Vector3::X, 0.0));
+        ent.apply_transform(&mirror, TextureLock::On).unwrap();
+        assert_eq!(ent.get("angle").unwrap(), "60");
+
+        let mut brush = Entity::new("func_detail", 3);
+        brush.solids = Some(vec![frame_box()]);
+        brush
+            .apply_transform(
+                &Transform::translation(Vector3::new(0.0, 0.0, 32.0)),
+                TextureLock::On,
+            )
+            .unwrap();
+        let bounds = brush.bounds(&BoundsOptions::default()).unwrap().unwrap();
+        assert_eq!(bounds.min.z, 32.0);
+    }
+
+    #[test]
+    fn entity_transform_keeps_roll() {
+        // Rolling a yaw-only entity can't be written back as a yaw.
+        let mut ent = Entity::new("prop_static", 2);
+        ent.set("angle".to_string(), "0".to_string());
+        let roll = Transform::rotation_around(Vector3::X, 90.0, Vector3::ZERO);
+        ent.apply_transform(&roll, TextureLock::On).unwrap();
+        assert!(ent.get("angle").is_none());
+        assert!(
+            ent.angles()
+                .unwrap()
+                .approx_eq(Vector3::new(0.0, 0.0, 90.0), 1e-9)
+        );
+    }
+}